@@ -0,0 +1,95 @@
+use crate::compliance::{self, MarginPoint, TargetViolation};
+use crate::config::PlotConfig;
+use crate::corners::{self, Corner};
+use crate::error::{self, PlotError};
+use crate::plottable::Plottable;
+use crate::resonance::{self, AntiResonance};
+use crate::sweep::Sweep;
+use crate::target::TargetMask;
+
+/// Metrics of a model found by `analyze`, the same ones the plotters draw.
+#[derive(Clone, Debug)]
+pub struct AnalysisReport {
+    /// Name of the model analyzed.
+    pub name: String,
+    /// The sampled response.
+    pub sweep: Sweep,
+    /// (frequency, magnitude) of the smallest magnitude in the sweep.
+    pub min: (f64, f64),
+    /// (frequency, magnitude) of the largest magnitude in the sweep.
+    pub max: (f64, f64),
+    /// Impedance peaks, with their Q and damping resistance.
+    pub anti_resonances: Vec<AntiResonance>,
+    /// Corner frequencies of the magnitude response.
+    pub corners: Vec<Corner>,
+    /// (Optional) Result of checking the sweep against the configured
+    /// target, if there is one.
+    pub compliance: Option<Result<MarginPoint, TargetViolation>>,
+}
+
+impl AnalysisReport {
+    /// Whether the model meets the configured target. A report without a
+    /// target always passes.
+    pub fn passes(&self) -> bool {
+        !matches!(self.compliance, Some(Err(_)))
+    }
+
+    /// Check the analyzed sweep against a frequency-dependent mask instead
+    /// of the configured flat target.
+    pub fn check(&self, mask: &TargetMask) -> Result<MarginPoint, TargetViolation> {
+        compliance::check_sweep(&self.sweep, mask)
+    }
+}
+
+/// Run the sweep, peak, corner, and target analysis that the plotters
+/// perform, without drawing anything, for scripts and CI jobs that only need
+/// the numbers. The sweep follows the frequency range, scale, and unit of
+/// `config`, and the target is `config.target`.
+/// Returns the report, or an error if the frequency range is invalid or the
+/// response has no finite values.
+///
+/// # Arguments
+/// * `model` - A power distribution network model, or any model to analyze.
+/// * `config` - Options shared with the plotters.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::analysis;
+/// use sdomain_test_plotters::config::PlotConfig;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+/// use sdomain_test::pdn::PDNModel;
+/// use sdomain_test::sdomain;
+///
+/// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+/// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+///
+/// let config = PlotConfig {fmax: 100e6, target: Some(0.1), ..PlotConfig::default()};
+/// let report = analysis::analyze(&pdn, &config).unwrap();
+/// assert_eq!(report.name, "PDN");
+/// assert!(!report.anti_resonances.is_empty());
+/// assert!(!report.passes());
+/// if let Some(Err(violation)) = &report.compliance {
+///     println!("{violation}");
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(model = %model.display_name())))]
+pub fn analyze<P: Plottable + ?Sized>(model: &P, config: &PlotConfig) -> Result<AnalysisReport, PlotError> {
+    error::check_range(config.fmin, config.fmax)?;
+    let sweep = config.check_finite(Sweep::at(model, config.sample_freqs()), &model.display_name())?;
+    error::check_limits(sweep.min_mag(), sweep.max_mag())?;
+
+    let min = sweep.mag_points().fold((f64::NAN, f64::INFINITY), |best, p| if p.1 < best.1 {p} else {best});
+    let max = sweep.mag_points().fold((f64::NAN, f64::NEG_INFINITY), |best, p| if p.1 > best.1 {p} else {best});
+    let compliance = config.target.map(|target| compliance::check_sweep(&sweep, &TargetMask::flat(target)));
+
+    Ok(AnalysisReport {
+        name: model.display_name(),
+        anti_resonances: resonance::find_anti_resonances(&sweep),
+        corners: corners::find_corners(&sweep),
+        min,
+        max,
+        compliance,
+        sweep,
+    })
+}
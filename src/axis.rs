@@ -0,0 +1,311 @@
+use std::f64::consts::PI;
+use std::ops::Range;
+
+use sdomain_test::range_generators::gen_log_range;
+
+use crate::units::{format_freq, format_plain, format_si};
+
+/// Number of frequencies sampled across a linear frequency axis.
+pub const LINEAR_POINTS: usize = 1000;
+
+/// Spacing of the frequency axis. Charts are drawn on plain coordinates and
+/// each frequency is mapped to its position along the axis, so every scale
+/// shares the same drawing code.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FreqScale {
+    /// Decades evenly spaced, for wideband plots.
+    #[default]
+    Log,
+    /// Hertz evenly spaced, for narrowband investigations such as zooming
+    /// into a single resonance.
+    Linear,
+}
+
+impl FreqScale {
+    /// Position of `freq` along the axis.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::FreqScale;
+    ///
+    /// assert_eq!(FreqScale::Log.to_axis(1e3), 3.0);
+    /// assert_eq!(FreqScale::Linear.to_axis(1e3), 1e3);
+    /// assert!((FreqScale::Log.from_axis(3.0) - 1e3).abs() < 1e-9);
+    /// ```
+    pub fn to_axis(self, freq: f64) -> f64 {
+        match self {
+            FreqScale::Log => freq.log10(),
+            FreqScale::Linear => freq,
+        }
+    }
+
+    /// Frequency at position `x` along the axis.
+    pub fn from_axis(self, x: f64) -> f64 {
+        match self {
+            FreqScale::Log => 10f64.powf(x),
+            FreqScale::Linear => x,
+        }
+    }
+
+    /// Axis range covering `fmin` to `fmax`.
+    pub fn range(self, fmin: f64, fmax: f64) -> Range<f64> {
+        self.to_axis(fmin)..self.to_axis(fmax)
+    }
+
+    /// Frequencies to sample from `fmin` to `fmax`, spaced evenly along the axis.
+    pub fn sample(self, fmin: f64, fmax: f64) -> Vec<f64> {
+        match self {
+            FreqScale::Log => gen_log_range(fmin, fmax, 10.0, 100),
+            FreqScale::Linear => {
+                let step = (fmax - fmin)/(LINEAR_POINTS - 1) as f64;
+                (0..LINEAR_POINTS).map(|k| fmin + k as f64*step).collect()
+            },
+        }
+    }
+
+    /// Number of tick labels to ask for. On a log axis this puts a tick on
+    /// every decade; on a linear axis the default spacing is kept.
+    pub fn label_count(self, fmin: f64, fmax: f64) -> usize {
+        match self {
+            FreqScale::Log => (fmax/fmin).log10().ceil() as usize + 1,
+            FreqScale::Linear => 10,
+        }
+    }
+}
+
+/// Scale of the magnitude axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MagScale {
+    /// Decades evenly spaced, labelled in linear units.
+    #[default]
+    Log,
+    /// Linear units evenly spaced, starting from zero.
+    Linear,
+    /// Decibels, `20·log10(|H|)`.
+    Db,
+    /// Decibels relative to 1µV, `20·log10(|V|/1µV)`, the unit of EMC
+    /// receivers and emissions limits. The magnitude must be an rms voltage.
+    DbMicrovolt,
+    /// Decibels relative to 1mW of the power an rms voltage delivers into
+    /// the given reference impedance in Ω, `10·log10(|V|²/R/1mW)`, the unit
+    /// of spectrum analyzers, usually with a 50Ω input. The peak amplitude
+    /// of a sinusoid delivers half that power, so spectra, which hold peak
+    /// amplitudes, are converted with `spectrum::peak_to_rms` first.
+    Dbm(f64),
+}
+
+impl MagScale {
+    /// Position of `mag` along the axis.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::MagScale;
+    ///
+    /// assert_eq!(MagScale::Log.to_axis(100.0), 2.0);
+    /// assert_eq!(MagScale::Linear.to_axis(100.0), 100.0);
+    /// assert_eq!(MagScale::Db.to_axis(100.0), 40.0);
+    /// assert!((MagScale::Db.from_axis(40.0) - 100.0).abs() < 1e-9);
+    ///
+    /// // 1mV rms is 60 dBµV, and -47 dBm into 50Ω
+    /// assert!((MagScale::DbMicrovolt.to_axis(1e-3) - 60.0).abs() < 1e-9);
+    /// assert!((MagScale::Dbm(50.0).to_axis(1e-3) + 46.99).abs() < 1e-3);
+    /// assert!((MagScale::Dbm(50.0).from_axis(-46.99) - 1e-3).abs() < 1e-6);
+    ///
+    /// // A 1mV peak sinusoid delivers 10nW into 50Ω, -50 dBm
+    /// let rms = sdomain_test_plotters::spectrum::peak_to_rms(1e-3);
+    /// assert!((MagScale::Dbm(50.0).to_axis(rms) + 50.0).abs() < 1e-9);
+    /// ```
+    pub fn to_axis(self, mag: f64) -> f64 {
+        match self {
+            MagScale::Log => mag.log10(),
+            MagScale::Linear => mag,
+            MagScale::Db => 20.0*mag.log10(),
+            MagScale::DbMicrovolt => 20.0*(mag/1e-6).log10(),
+            MagScale::Dbm(reference) => 10.0*(mag*mag/reference/1e-3).log10(),
+        }
+    }
+
+    /// Magnitude at position `y` along the axis.
+    pub fn from_axis(self, y: f64) -> f64 {
+        match self {
+            MagScale::Log => 10f64.powf(y),
+            MagScale::Linear => y,
+            MagScale::Db => 10f64.powf(y/20.0),
+            MagScale::DbMicrovolt => 1e-6*10f64.powf(y/20.0),
+            MagScale::Dbm(reference) => (1e-3*reference*10f64.powf(y/10.0)).sqrt(),
+        }
+    }
+
+    /// Whether the axis is in decibels of any reference.
+    pub fn is_db(self) -> bool {
+        matches!(self, MagScale::Db | MagScale::DbMicrovolt | MagScale::Dbm(_))
+    }
+
+    /// Axis range covering magnitudes from `min` to `max` with `padding`
+    /// decades of room above and below. A linear axis starts from zero and
+    /// leaves the same factor of room above `max`.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::MagScale;
+    ///
+    /// let range = MagScale::Db.padded_range(1e-3, 10.0, 0.5);
+    /// assert!((range.start + 70.0).abs() < 1e-9);
+    /// assert!((range.end - 30.0).abs() < 1e-9);
+    /// ```
+    pub fn padded_range(self, min: f64, max: f64, padding: f64) -> Range<f64> {
+        let factor = 10f64.powf(padding);
+        match self {
+            MagScale::Linear => 0.0..max*factor,
+            _ => self.to_axis(min/factor)..self.to_axis(max*factor),
+        }
+    }
+
+    /// Number of tick labels to ask for over the axis range `range`. On a log
+    /// axis this puts a tick on every decade; otherwise the default spacing is kept.
+    pub fn label_count(self, range: &Range<f64>) -> usize {
+        match self {
+            MagScale::Log => (range.end - range.start).ceil() as usize + 1,
+            _ => 10,
+        }
+    }
+
+    /// Axis label for a quantity named `quantity` measured in `unit`, e.g.
+    /// "Impedance [Ω]" or "Impedance [dBΩ]".
+    pub fn label(self, quantity: &str, unit: &str) -> String {
+        match self {
+            MagScale::Log | MagScale::Linear if unit.is_empty() => quantity.to_string(),
+            _ => format!("{quantity} [{}]", self.unit(unit)),
+        }
+    }
+
+    /// Unit of positions along the axis for magnitudes in `unit`, e.g. "Ω",
+    /// "dBΩ", or "dBµV". The absolute decibel units replace `unit`.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::MagScale;
+    ///
+    /// assert_eq!(MagScale::Db.unit("Ω"), "dBΩ");
+    /// assert_eq!(MagScale::DbMicrovolt.unit("V"), "dBµV");
+    /// assert_eq!(MagScale::Dbm(50.0).unit("V"), "dBm");
+    /// ```
+    pub fn unit(self, unit: &str) -> String {
+        match self {
+            MagScale::Log | MagScale::Linear => unit.to_string(),
+            MagScale::Db => format!("dB{unit}"),
+            MagScale::DbMicrovolt => "dBµV".to_string(),
+            MagScale::Dbm(_) => "dBm".to_string(),
+        }
+    }
+}
+
+/// Unit of the frequency axis. The sweep range and every frequency shown on
+/// the axis are given in this unit; models are still evaluated in Hz.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FreqUnit {
+    /// Hertz.
+    #[default]
+    Hz,
+    /// Angular frequency ω in rad/s, for specs written in controls terms.
+    RadPerSec,
+    /// Frequency divided by a normalization frequency f₀ in Hz, for comparing
+    /// designs built around different center frequencies on one scale.
+    Normalized(f64),
+}
+
+impl FreqUnit {
+    /// Convert a frequency in this unit to Hz.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use sdomain_test_plotters::axis::FreqUnit;
+    ///
+    /// assert_eq!(FreqUnit::Hz.to_hz(50.0), 50.0);
+    /// assert!((FreqUnit::RadPerSec.to_hz(2.0*PI*50.0) - 50.0).abs() < 1e-9);
+    /// assert_eq!(FreqUnit::Normalized(1e6).to_hz(0.5), 500e3);
+    /// ```
+    pub fn to_hz(self, value: f64) -> f64 {
+        match self {
+            FreqUnit::Hz => value,
+            FreqUnit::RadPerSec => value/(2.0*PI),
+            FreqUnit::Normalized(f0) => value*f0,
+        }
+    }
+
+    /// Convert a frequency in Hz to this unit.
+    pub fn from_hz(self, freq: f64) -> f64 {
+        match self {
+            FreqUnit::Hz => freq,
+            FreqUnit::RadPerSec => 2.0*PI*freq,
+            FreqUnit::Normalized(f0) => freq/f0,
+        }
+    }
+
+    /// Tick label for `value` given in this unit.
+    pub fn format(self, value: f64) -> String {
+        match self {
+            FreqUnit::Hz => format_freq(value),
+            FreqUnit::RadPerSec => format_si(value, "rad/s"),
+            FreqUnit::Normalized(_) => format_plain(value),
+        }
+    }
+
+    /// Default axis label.
+    pub fn axis_label(self) -> &'static str {
+        match self {
+            FreqUnit::Hz => "Frequency [Hz]",
+            FreqUnit::RadPerSec => "Angular Frequency [rad/s]",
+            FreqUnit::Normalized(_) => "Normalized Frequency [f/f₀]",
+        }
+    }
+}
+
+/// Unit of slope readouts, such as the labels on bode plot asymptotes.
+/// Slopes are computed in dB/decade and converted for display.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SlopeUnit {
+    /// Decibels per decade, e.g. -20 dB/dec for a single pole.
+    #[default]
+    DbPerDecade,
+    /// Decibels per octave, e.g. -6 dB/oct for a single pole, as rolloff is
+    /// usually given for audio filters.
+    DbPerOctave,
+}
+
+impl SlopeUnit {
+    /// Convert a slope in dB/decade to this unit.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::SlopeUnit;
+    ///
+    /// assert_eq!(SlopeUnit::DbPerDecade.from_db_per_decade(-40.0), -40.0);
+    /// assert!((SlopeUnit::DbPerOctave.from_db_per_decade(-20.0) + 6.02).abs() < 0.01);
+    /// ```
+    pub fn from_db_per_decade(self, slope: f64) -> f64 {
+        match self {
+            SlopeUnit::DbPerDecade => slope,
+            SlopeUnit::DbPerOctave => slope*2f64.log10(),
+        }
+    }
+
+    /// Readout for a slope given in dB/decade, converted to this unit.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::SlopeUnit;
+    ///
+    /// assert_eq!(SlopeUnit::DbPerDecade.format(-20.0), "-20 dB/dec");
+    /// assert_eq!(SlopeUnit::DbPerOctave.format(-40.0), "-12 dB/oct");
+    /// assert_eq!(SlopeUnit::DbPerOctave.format(0.0), "+0 dB/oct");
+    /// ```
+    pub fn format(self, slope: f64) -> String {
+        let value = self.from_db_per_decade(slope);
+        match self {
+            SlopeUnit::DbPerDecade => format!("{value:+.0} dB/dec"),
+            SlopeUnit::DbPerOctave => format!("{value:+.0} dB/oct"),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use plotters::coord::ranged1d::Ranged;
+use plotters::prelude::*;
+
+/// A named frequency band shaded behind the curves of a plot, such as a
+/// broadcast band or a CISPR measurement band, so results can be read
+/// against the frequencies of concern at a glance.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::bands::FreqBand;
+///
+/// use plotters::style::RGBColor;
+///
+/// let fm = FreqBand::fm_broadcast();
+/// assert!(fm.contains(100e6));
+/// assert!(!fm.contains(50e6));
+///
+/// let cispr = FreqBand::cispr();
+/// assert_eq!(cispr.iter().map(|band| band.name.as_str()).collect::<Vec<&str>>(), vec!["CISPR A", "CISPR B", "CISPR C", "CISPR D"]);
+/// // The bands tile 9kHz to 1GHz
+/// assert!(cispr.windows(2).all(|pair| pair[0].end == pair[1].start));
+///
+/// let gps = FreqBand::custom("GPS L1", 1.57e9, 1.58e9, RGBColor(128, 0, 128));
+/// assert_eq!(gps.start, 1.57e9);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FreqBand {
+    /// Name to print in the legend and over the band.
+    pub name: String,
+    /// Lowest frequency of the band in Hz.
+    pub start: f64,
+    /// Highest frequency of the band in Hz.
+    pub end: f64,
+    /// Shading color, drawn translucent.
+    pub color: RGBColor,
+}
+
+impl FreqBand {
+    /// A band named `name` from `start` to `end` in Hz shaded in `color`.
+    pub fn custom(name: &str, start: f64, end: f64, color: RGBColor) -> FreqBand {
+        FreqBand {name: name.to_string(), start, end, color}
+    }
+
+    /// AM medium wave broadcast band, 530kHz to 1.71MHz.
+    pub fn am_broadcast() -> FreqBand {
+        FreqBand::custom("AM Broadcast", 530e3, 1.71e6, RGBColor(0, 114, 178))
+    }
+
+    /// FM broadcast band, 87.5MHz to 108MHz.
+    pub fn fm_broadcast() -> FreqBand {
+        FreqBand::custom("FM Broadcast", 87.5e6, 108e6, RGBColor(213, 94, 0))
+    }
+
+    /// CISPR 16 band A, 9kHz to 150kHz.
+    pub fn cispr_a() -> FreqBand {
+        FreqBand::custom("CISPR A", 9e3, 150e3, RGBColor(230, 159, 0))
+    }
+
+    /// CISPR 16 band B, 150kHz to 30MHz, where conducted emissions are measured.
+    pub fn cispr_b() -> FreqBand {
+        FreqBand::custom("CISPR B", 150e3, 30e6, RGBColor(0, 158, 115))
+    }
+
+    /// CISPR 16 band C, 30MHz to 300MHz.
+    pub fn cispr_c() -> FreqBand {
+        FreqBand::custom("CISPR C", 30e6, 300e6, RGBColor(86, 180, 233))
+    }
+
+    /// CISPR 16 band D, 300MHz to 1GHz.
+    pub fn cispr_d() -> FreqBand {
+        FreqBand::custom("CISPR D", 300e6, 1e9, RGBColor(204, 121, 167))
+    }
+
+    /// CISPR 16 bands A to D, lowest first.
+    pub fn cispr() -> Vec<FreqBand> {
+        vec![FreqBand::cispr_a(), FreqBand::cispr_b(), FreqBand::cispr_c(), FreqBand::cispr_d()]
+    }
+
+    /// Whether `freq` in Hz falls within the band.
+    pub fn contains(&self, freq: f64) -> bool {
+        self.start <= freq && freq <= self.end
+    }
+}
+
+/// Shade `bands` behind the curves of a chart, each clipped to the
+/// frequency axis, named in the legend and over its top left corner.
+/// `to_axis` gives the position along the x axis of a frequency in Hz.
+/// Bands entirely off the axis are left out.
+pub fn draw<DB: DrawingBackend, X: Ranged<ValueType = f64>, Y: Ranged<ValueType = f64>>(chart: &mut ChartContext<DB, Cartesian2d<X, Y>>, bands: &[FreqBand], to_axis: impl Fn(f64) -> f64) -> Result<(), Box <dyn std::error::Error>>
+where DB::ErrorType: 'static {
+    let x_range = chart.plotting_area().get_x_range();
+    let y_range = chart.plotting_area().get_y_range();
+    for band in bands.iter() {
+        let start = to_axis(band.start).max(x_range.start);
+        let end = to_axis(band.end).min(x_range.end);
+        if start >= end {continue;}
+        let color = band.color.mix(0.15);
+        chart.draw_series(std::iter::once(Rectangle::new([(start, y_range.start), (end, y_range.end)], color.filled())))?
+            .label(band.name.as_str())
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+        chart.draw_series(std::iter::once(
+                EmptyElement::at((start, y_range.end))
+                + Text::new(band.name.clone(), (4, 4), ("Arial", 13).into_font().color(&band.color))
+            ))?;
+    }
+    Ok(())
+}
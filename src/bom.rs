@@ -0,0 +1,37 @@
+use sdomain_test::passives::capacitor::Capacitor;
+use sdomain_test::pdn::PDNModel;
+use sdomain_test::sdomain::Fs;
+
+/// One line of a decoupling bill of materials.
+#[derive(Clone, Debug)]
+pub struct Decap {
+    /// Name to list the capacitor under in the PDN.
+    pub name: String,
+    /// Capacitance in F.
+    pub value: f64,
+    /// Package size code, e.g. "0402".
+    pub package: String,
+    /// Number of identical capacitors placed.
+    pub count: usize,
+}
+
+impl Decap {
+    pub fn capacitor(&self) -> Capacitor {
+        Capacitor::from(self.value, &self.package)
+    }
+
+    /// Self-resonant frequency of one capacitor in Hz.
+    pub fn srf(&self) -> f64 {
+        self.capacitor().resonant()
+    }
+}
+
+/// Build a PDN from a source impedance and a bill of materials.
+pub fn build_pdn(source: &Fs, bom: &[Decap]) -> PDNModel {
+    let mut pdn = PDNModel::from(source.clone(), None);
+    for decap in bom.iter() {
+        pdn.add_capacitor(&decap.name, decap.capacitor().model(), decap.count);
+    }
+    pdn
+}
+
@@ -0,0 +1,309 @@
+pub mod library_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::GREY;
+
+    use sdomain_test::passives::capacitor::Capacitor;
+
+    use crate::error::{self, PlotError};
+    use crate::sweep::Sweep;
+    use crate::units::format_freq;
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    const MAX_FREQ: f64 = 1e9;
+
+    /// Overlay the impedance curves of a library of capacitors, such as an
+    /// approved parts list. Each curve is colored along a gradient by its
+    /// self-resonant frequency (red for the lowest SRF through blue for the
+    /// highest), so gaps in the library's frequency coverage stand out.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `library` - Capacitors to plot.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::capacitor_plotter::library_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/capacitor_library.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let library = vec![
+    ///     Capacitor::from(22e-6, "0603"),
+    ///     Capacitor::from(4.7e-6, "0402"),
+    ///     Capacitor::from(100e-9, "0201"),
+    ///     Capacitor::from(2.2e-9, "0201"),
+    /// ];
+    /// library_plotter::plot(&library, &drawing_area).unwrap();
+    /// ```
+    pub fn plot<DB: DrawingBackend>(library: &[Capacitor], canvas: &DrawAreaType<DB>) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if library.is_empty() {return Err(PlotError::Empty.into());}
+        let mut entries = library.iter()
+            .map(|cap| Ok((cap, cap.resonant(), Sweep::checked(cap, 1.0, MAX_FREQ)?)))
+            .collect::<Result<Vec<_>, PlotError>>()?;
+        entries.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let min_mag = entries.iter().map(|e| e.2.min_mag()).fold(f64::INFINITY, f64::min);
+        let max_mag = entries.iter().map(|e| e.2.max_mag()).fold(f64::NEG_INFINITY, f64::max);
+        error::check_limits(min_mag, max_mag)?;
+        let min_srf = entries.first().map(|e| e.1).unwrap_or(1.0);
+        let max_srf = entries.last().map(|e| e.1).unwrap_or(1.0);
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption("Capacitor Library Impedance", ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((1.0f64..MAX_FREQ).log_scale(), (min_mag*0.5..max_mag*2.0).log_scale())?;
+
+        chart.configure_mesh().x_desc("Frequency [Hz]").y_desc("Impedance [Ω]").draw()?;
+
+        for (cap, srf, sweep) in entries.iter() {
+            let color = srf_color(*srf, min_srf, max_srf);
+            chart.draw_series(LineSeries::new(
+                    sweep.mag_points(),
+                    &color
+                ))?
+                .label(format!("{cap} (SRF {})", format_freq(*srf)))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerLeft)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(())
+    }
+
+    /// Color along a red to blue gradient for an SRF, spaced logarithmically
+    /// between the lowest and highest SRF in the library.
+    fn srf_color(srf: f64, min_srf: f64, max_srf: f64) -> RGBAColor {
+        let span = (max_srf/min_srf).log10();
+        let frac = if span > 0.0 {(srf/min_srf).log10()/span} else {0.0};
+        HSLColor(0.7*frac, 0.8, 0.45).to_rgba()
+    }
+}
+
+pub mod vendor_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{ORANGE, GREY};
+
+    use sdomain_test::passives::capacitor::Capacitor;
+
+    use crate::error;
+    use crate::spice::{self, ModelComparison, Subckt};
+    use crate::sweep::Sweep;
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    const MAX_FREQ: f64 = 1e9;
+
+    /// Overlay the impedance of a vendor's SPICE model of a capacitor on the
+    /// crate's simple model of the same part, and print how far the vendor
+    /// model rises above the simple one. Vendor ladder models capture ESR
+    /// rising with frequency and the DC bias and package effects the simple
+    /// series RLC leaves out, so the gap shows how optimistic the simple
+    /// model is.
+    /// Returns the comparison, or an error if the subcircuit does not reduce
+    /// to an impedance or the plot could not be drawn.
+    ///
+    /// # Arguments
+    /// * `vendor` - The vendor subcircuit, from `spice::parse_subckt`.
+    /// * `capacitor` - The simple model of the same part.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::capacitor_plotter::vendor_plotter;
+    /// use sdomain_test_plotters::spice;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/capacitor_vendor.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // A ladder model with ESR that rises with frequency
+    /// let vendor = spice::parse_subckt("\
+    /// .subckt GRM155 1 2
+    /// C1 1 3 1u
+    /// R1 3 4 3m
+    /// R2 4 5 10m
+    /// L2 4 5 5n
+    /// L1 5 2 0.4n
+    /// .ends
+    /// ").unwrap();
+    /// let comparison = vendor_plotter::plot(&vendor, &Capacitor::from(1e-6, "0402"), &drawing_area).unwrap();
+    /// println!("{comparison}");
+    /// ```
+    pub fn plot<DB: DrawingBackend>(vendor: &Subckt, capacitor: &Capacitor, canvas: &DrawAreaType<DB>) -> Result<ModelComparison, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let vendor_sweep = Sweep::checked(&vendor.model()?, 1.0, MAX_FREQ)?;
+        let simple_sweep = Sweep::checked(capacitor, 1.0, MAX_FREQ)?;
+        let comparison = spice::compare(&vendor_sweep, &simple_sweep);
+
+        let min_mag = vendor_sweep.min_mag().min(simple_sweep.min_mag());
+        let max_mag = vendor_sweep.max_mag().max(simple_sweep.max_mag());
+        error::check_limits(min_mag, max_mag)?;
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("{} vs {capacitor}", vendor.name), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((1.0f64..MAX_FREQ).log_scale(), (min_mag*0.5..max_mag*2.0).log_scale())?;
+
+        chart.configure_mesh().x_desc("Frequency [Hz]").y_desc("Impedance [Ω]").draw()?;
+
+        chart.draw_series(LineSeries::new(
+                vendor_sweep.mag_points(),
+                &ORANGE
+            ))?
+            .label(format!("{} (vendor)", vendor.name))
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &ORANGE));
+        chart.draw_series(LineSeries::new(
+                simple_sweep.mag_points(),
+                &BLUE
+            ))?
+            .label(format!("{capacitor} (simple)"))
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+
+        let (ratio, freq) = comparison.max_ratio;
+        chart.draw_series(std::iter::once(
+                EmptyElement::at((freq, vendor_sweep.mags[vendor_sweep.freqs.iter().position(|f| *f == freq).unwrap_or(0)]))
+                + Circle::new((0, 0), 4, RED.filled())
+                + Text::new(format!("{ratio:+.1} dB"), (8, -16), ("Arial", 15))
+            ))?;
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        canvas.draw(&Text::new(
+            format!("{comparison}"),
+            (60, 50),
+            ("Arial", 15).into_font().color(&BLACK)
+        ))?;
+
+        Ok(comparison)
+    }
+}
+
+pub mod measured_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{ORANGE, GREY};
+
+    use crate::error;
+    use crate::sweep::Sweep;
+    use crate::touchstone::{self, Fixture, RclFit, S2p};
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Fit a series R-L-C model to a measured capacitor and plot the fit.
+    /// The upper panel overlays the measured and fitted impedance with the
+    /// fitted values in the legend; the lower panel shows the error of the
+    /// fit in percent of the measured |Z|, which grows where the part departs
+    /// from a simple series RLC, e.g. through ESR rising with frequency.
+    /// Returns the fitted model, or an error if the plot could not be drawn.
+    ///
+    /// # Arguments
+    /// * `data` - Two-port S parameters from `touchstone::parse_s2p`.
+    /// * `fixture` - How the capacitor was mounted for the measurement.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plots.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::capacitor_plotter::measured_plotter;
+    /// use sdomain_test_plotters::touchstone::{self, Fixture};
+    ///
+    /// use plotters::prelude::*;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/capacitor_measured.png", (960, 960))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let data = touchstone::parse_s2p("\
+    /// # MHz S DB R 50
+    /// 0.1 0 0 -30.1 -86.5 -30.1 -86.5 0 0
+    /// 1 0 0 -50.1 -60.2 -50.1 -60.2 0 0
+    /// 10 0 0 -62.0 30.0 -62.0 30.0 0 0
+    /// 100 0 0 -44.2 87.0 -44.2 87.0 0 0
+    /// ").unwrap();
+    /// let fit = measured_plotter::plot(&data, Fixture::ShuntThrough, &drawing_area).unwrap();
+    /// assert!(fit.capacitance > 0.0);
+    /// ```
+    pub fn plot<DB: DrawingBackend>(data: &S2p, fixture: Fixture, canvas: &DrawAreaType<DB>) -> Result<RclFit, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let measured = data.impedance(fixture);
+        let fit = touchstone::fit_rcl(&measured);
+        let fitted = Sweep::at(&fit.model(), measured.freqs.clone());
+        let fmin = measured.freqs.first().cloned().unwrap_or(1.0);
+        let fmax = measured.freqs.last().cloned().unwrap_or(1.0);
+        error::check_range(fmin, fmax)?;
+
+        let (upper, lower) = canvas.split_vertically((65).percent_height());
+
+        let min_mag = measured.min_mag().min(fitted.min_mag());
+        let max_mag = measured.max_mag().max(fitted.max_mag());
+        error::check_limits(min_mag, max_mag)?;
+        let mut chart = ChartBuilder::on(&upper)
+        .caption("Measured vs Fitted Impedance", ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((fmin..fmax).log_scale(), (min_mag*0.5..max_mag*2.0).log_scale())?;
+
+        chart.configure_mesh().x_desc("Frequency [Hz]").y_desc("Impedance [Ω]").draw()?;
+
+        chart.draw_series(LineSeries::new(
+                measured.mag_points(),
+                &BLUE
+            ))?
+            .label("Measured")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+        chart.draw_series(LineSeries::new(
+                fitted.mag_points(),
+                &ORANGE
+            ))?
+            .label(format!("Fit: {fit}"))
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &ORANGE));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        let errors = fitted.mags.iter().zip(measured.mags.iter())
+            .map(|(fit, meas)| 100.0*(fit - meas)/meas)
+            .collect::<Vec<f64>>();
+        let worst = errors.iter().cloned().filter(|e| e.is_finite()).fold(1.0, |worst, e| worst.max(e.abs()));
+        let mut chart = ChartBuilder::on(&lower)
+        .caption("Fit Error", ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((fmin..fmax).log_scale(), -worst*1.1..worst*1.1)?;
+
+        chart.configure_mesh().x_desc("Frequency [Hz]").y_desc("Error [%]").draw()?;
+
+        chart.draw_series(LineSeries::new(
+                vec![(fmin, 0.0), (fmax, 0.0)],
+                &BLACK.mix(0.5)
+            ))?;
+        chart.draw_series(LineSeries::new(
+                measured.freqs.iter().cloned().zip(errors),
+                &RED
+            ))?;
+
+        Ok(fit)
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+
+use crate::plottable::Plottable;
+use crate::sweep::Sweep;
+use crate::target::TargetMask;
+use crate::units::{format_freq, format_ohms};
+
+/// Impedance and limit at one frequency of a compliance check.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarginPoint {
+    /// Frequency in Hz.
+    pub freq: f64,
+    /// Impedance magnitude of the model.
+    pub impedance: f64,
+    /// Target limit at `freq`.
+    pub limit: f64,
+}
+
+impl MarginPoint {
+    /// Margin below the target in dB. Positive when the impedance is under
+    /// the limit, negative when it is over.
+    pub fn margin_db(&self) -> f64 {
+        20.0*(self.limit/self.impedance).log10()
+    }
+}
+
+impl fmt::Display for MarginPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} against a limit of {} at {} ({:.2} dB margin)",
+            format_ohms(self.impedance), format_ohms(self.limit), format_freq(self.freq), self.margin_db()
+        )
+    }
+}
+
+/// A model that exceeds its target somewhere in the sweep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetViolation {
+    /// The point furthest over the target.
+    pub worst: MarginPoint,
+    /// Frequency ranges in Hz, (start, end), over which the model exceeds
+    /// the target.
+    pub bands: Vec<(f64, f64)>,
+}
+
+impl fmt::Display for TargetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "impedance exceeds the target: worst is {}", self.worst)?;
+        let bands = self.bands.iter()
+            .map(|(start, end)| format!("{} to {}", format_freq(*start), format_freq(*end)))
+            .collect::<Vec<String>>();
+        write!(f, "; over the target from {}", bands.join(", "))
+    }
+}
+
+impl std::error::Error for TargetViolation {}
+
+/// Check a sweep against a target mask.
+/// Returns the point with the least margin if the sweep stays at or under
+/// the mask everywhere, or the violation otherwise.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::compliance;
+/// use sdomain_test_plotters::sweep::Sweep;
+/// use sdomain_test_plotters::target::TargetMask;
+///
+/// use sdomain_test::sdomain;
+///
+/// // 1mΩ in series with 1nH crosses 100mΩ near 16MHz
+/// let sweep = Sweep::of(&sdomain::gen::rl(1e-3, 1e-9), 1e3, 100e6);
+/// let violation = compliance::check_sweep(&sweep, &TargetMask::flat(0.1)).unwrap_err();
+/// assert!(violation.worst.freq > 90e6 && violation.worst.margin_db() < 0.0);
+/// assert_eq!(violation.bands.len(), 1);
+/// assert!(violation.bands[0].0 > 10e6 && violation.bands[0].0 < 20e6);
+///
+/// let tightest = compliance::check_sweep(&sweep, &TargetMask::flat(1.0)).unwrap();
+/// assert!(tightest.margin_db() > 0.0);
+/// ```
+pub fn check_sweep(sweep: &Sweep, mask: &TargetMask) -> Result<MarginPoint, TargetViolation> {
+    let points = sweep.mag_points()
+        .map(|(freq, impedance)| MarginPoint {freq, impedance, limit: mask.limit_at(freq)})
+        .collect::<Vec<MarginPoint>>();
+    let worst = points.iter()
+        .cloned()
+        .fold(None, |worst: Option<MarginPoint>, point| match worst {
+            Some(worst) if worst.margin_db() <= point.margin_db() => Some(worst),
+            _ => Some(point),
+        });
+    let worst = match worst {
+        Some(worst) => worst,
+        None => return Ok(MarginPoint {freq: f64::NAN, impedance: 0.0, limit: f64::INFINITY}),
+    };
+    if worst.margin_db() >= 0.0 {
+        return Ok(worst);
+    }
+
+    let mut bands = vec![];
+    let mut start = None;
+    for point in points.iter() {
+        match (start, point.impedance > point.limit) {
+            (None, true) => start = Some(point.freq),
+            (Some(from), false) => {
+                bands.push((from, point.freq));
+                start = None;
+            },
+            _ => {},
+        }
+    }
+    if let (Some(from), Some(last)) = (start, points.last()) {
+        bands.push((from, last.freq));
+    }
+    Err(TargetViolation {worst, bands})
+}
+
+/// Sweep a model from `fmin` to `fmax` with the same sampling the plotters
+/// use and check it against a target mask, without drawing anything.
+/// Returns the point with the least margin if the model meets the mask, or
+/// the violation otherwise.
+///
+/// # Arguments
+/// * `model` - A power distribution network model, or any impedance model.
+/// * `mask` - The impedance target.
+/// * `fmin` - Lowest frequency of the sweep in Hz.
+/// * `fmax` - Highest frequency of the sweep in Hz.
+pub fn check_target<P: Plottable + ?Sized>(model: &P, mask: &TargetMask, fmin: f64, fmax: f64) -> Result<MarginPoint, TargetViolation> {
+    check_sweep(&Sweep::of(model, fmin, fmax), mask)
+}
+
+/// Panic with the worst frequency and margin if a model exceeds a target
+/// mask anywhere from `fmin` to `fmax`, for use in unit tests.
+///
+/// # Arguments
+/// * `model` - A power distribution network model, or any impedance model.
+/// * `mask` - The impedance target.
+/// * `fmin` - Lowest frequency of the sweep in Hz.
+/// * `fmax` - Highest frequency of the sweep in Hz.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::compliance;
+/// use sdomain_test_plotters::target::TargetMask;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+/// use sdomain_test::pdn::PDNModel;
+/// use sdomain_test::sdomain;
+///
+/// let mut pdn = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-9), None);
+/// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+///
+/// compliance::assert_meets_target(&pdn, &TargetMask::flat(1.0), 1.0, 10e6);
+/// ```
+///
+/// ```should_panic
+/// use sdomain_test_plotters::compliance;
+/// use sdomain_test_plotters::target::TargetMask;
+///
+/// use sdomain_test::sdomain;
+///
+/// compliance::assert_meets_target(&sdomain::gen::resistor(0.2), &TargetMask::flat(0.1), 1.0, 10e6);
+/// ```
+pub fn assert_meets_target<P: Plottable + ?Sized>(model: &P, mask: &TargetMask, fmin: f64, fmax: f64) {
+    if let Err(violation) = check_target(model, mask, fmin, fmax) {
+        panic!("{} {violation}", model.display_name());
+    }
+}
@@ -0,0 +1,519 @@
+use std::ops::Range;
+
+use crate::axis::{FreqScale, FreqUnit, MagScale, SlopeUnit};
+use crate::bands::FreqBand;
+use crate::error::PlotError;
+use crate::harmonics::HarmonicComb;
+use crate::legend::LegendGroups;
+use crate::plottable::Plottable;
+use crate::sweep::{wrap_phase, Sweep};
+use crate::units::{format_eng, format_plain};
+
+/// Options shared by the chart builders. Each builder exposes fluent setters
+/// for these, or a whole configuration can be reused across plots.
+#[derive(Clone, Debug)]
+pub struct PlotConfig {
+    /// Lowest frequency of the sweep, in `freq_unit`.
+    pub fmin: f64,
+    /// Highest frequency of the sweep, in `freq_unit`.
+    pub fmax: f64,
+    /// Spacing of the frequency axis.
+    pub freq_scale: FreqScale,
+    /// Unit of the frequency axis and of `fmin` and `fmax`.
+    pub freq_unit: FreqUnit,
+    /// Scale of the magnitude axis.
+    pub mag_scale: MagScale,
+    /// Room left above and below the data on the magnitude axis, in decades.
+    pub y_padding: f64,
+    /// Significant digits in the magnitude axis tick labels, which are
+    /// written in engineering notation such as `3.5 mΩ`.
+    pub y_digits: usize,
+    /// Whether to label the right axis in dB alongside a log magnitude axis
+    /// on the left, with gridlines shared by both. The dB axis takes the
+    /// place of the phase axis.
+    pub dual_mag_axis: bool,
+    /// (Optional) Magnitude axis limits in linear units, overriding the
+    /// range found from the data.
+    pub y_limits: Option<(f64, f64)>,
+    /// (Optional) Magnitudes in linear units to cut out of the magnitude
+    /// axis. The plot is drawn as two stacked panels, one below and one
+    /// above the break, so that e.g. milliohm floors and kilo-ohm DC values
+    /// both stay readable. Panels with a break leave out the phase. Data
+    /// not reaching both sides of the break is drawn without one.
+    pub y_break: Option<(f64, f64)>,
+    /// Whether to draw the phase curve on the secondary axis. Without the
+    /// phase the secondary axis is left out and its margin is freed.
+    pub show_phase: bool,
+    /// Whether the secondary axis shows the group delay instead of the
+    /// phase, e.g. for filters feeding an ADC where delay matters more.
+    pub group_delay: bool,
+    /// Where a bode plot draws the phase or group delay.
+    pub phase_layout: PhaseLayout,
+    /// Phase axis limits in degrees. A range wider than 360° draws the
+    /// unwrapped phase, e.g. -720..0 for a multi-pole response.
+    pub phase_range: (f64, f64),
+    /// What to do with samples where the model's response is NaN or infinite.
+    pub non_finite: NonFinitePolicy,
+    /// (Optional) Magnitude target, in linear units, drawn as a horizontal line.
+    pub target: Option<f64>,
+    /// Whether to draw |Z|/target on a thin panel under the impedance chart,
+    /// with a line at 1, so the compliance margin reads directly. Needs a
+    /// `target`; ignored on a broken magnitude axis.
+    pub target_ratio: bool,
+    /// Whether to mark corner frequencies found in the magnitude response.
+    pub annotate_corners: bool,
+    /// Whether to draw the asymptotic construction lines of the magnitude
+    /// response, extended past each corner to their intersection.
+    pub asymptotes: bool,
+    /// Unit of slope readouts, such as the asymptote labels.
+    pub slope_unit: SlopeUnit,
+    /// Whether to mark the frequencies where the magnitude crosses 0 dB,
+    /// e.g. the crossovers of a loop gain.
+    pub annotate_crossovers: bool,
+    /// Whether to list the poles and zeros estimated from the response in
+    /// a table in the corner of the chart.
+    pub pole_zero_table: bool,
+    /// Whether to mark where the gain and phase margins of a loop gain are
+    /// measured and list the margins on the chart.
+    pub annotate_margins: bool,
+    /// (Optional) Markers at a switching frequency and its harmonics, each
+    /// reading out the magnitude of the response there.
+    pub harmonic_comb: Option<HarmonicComb>,
+    /// Frequency bands to shade behind the curves, e.g. broadcast or CISPR
+    /// bands.
+    pub bands: Vec<FreqBand>,
+    /// Grouping and order of the legend entries of charts with many curves,
+    /// such as one per capacitor bank, matched by curve name. Without any
+    /// groups the legend keeps the plotting order.
+    pub legend: LegendGroups,
+    /// Caption printed above the chart.
+    pub caption: Caption,
+    /// (Optional) Frequency axis label to use instead of the plotter's default.
+    pub x_label: Option<String>,
+    /// (Optional) Magnitude axis label to use instead of the plotter's default.
+    pub y_label: Option<String>,
+    /// (Optional) Phase axis label to use instead of the plotter's default.
+    pub phase_label: Option<String>,
+}
+
+/// Caption choice for a chart. Plots embedded in documents that supply their
+/// own figure captions can hide the caption entirely.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Caption {
+    /// The plotter's own title, e.g. "Impedance of PDN".
+    #[default]
+    Default,
+    /// Custom caption text.
+    Text(String),
+    /// No caption; the chart uses the full height of the drawing area.
+    Hidden,
+}
+
+/// Placement of the phase of a bode plot relative to its magnitude.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PhaseLayout {
+    /// On a secondary axis over the magnitude chart.
+    #[default]
+    Overlay,
+    /// On its own panel under the magnitude chart, sharing the frequency
+    /// axis, so the phase is never read against the magnitude scale.
+    Stacked,
+}
+
+/// Handling of NaN or infinite samples in a sweep, e.g. from zero-valued
+/// components or a model evaluated at 0 Hz.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NonFinitePolicy {
+    /// Fail with an error naming the model and the first bad frequency.
+    #[default]
+    Error,
+    /// Leave the bad samples out of the plot.
+    Skip,
+    /// Replace each bad sample with the nearest finite one.
+    Clamp,
+}
+
+impl Default for PlotConfig {
+    fn default() -> PlotConfig {
+        PlotConfig {
+            fmin: 1.0,
+            fmax: 10e6,
+            freq_scale: FreqScale::Log,
+            freq_unit: FreqUnit::Hz,
+            mag_scale: MagScale::Log,
+            y_padding: 0.3,
+            y_limits: None,
+            y_break: None,
+            y_digits: 3,
+            dual_mag_axis: false,
+            show_phase: true,
+            group_delay: false,
+            phase_layout: PhaseLayout::Overlay,
+            phase_range: (-180.0, 180.0),
+            non_finite: NonFinitePolicy::Error,
+            target: None,
+            target_ratio: false,
+            annotate_corners: false,
+            asymptotes: false,
+            slope_unit: SlopeUnit::DbPerDecade,
+            annotate_crossovers: false,
+            pole_zero_table: false,
+            annotate_margins: false,
+            harmonic_comb: None,
+            bands: vec![],
+            legend: LegendGroups::new(),
+            caption: Caption::Default,
+            x_label: None,
+            y_label: None,
+            phase_label: None,
+        }
+    }
+}
+
+impl PlotConfig {
+    /// Caption to draw, given the plotter's default title, or `None` if the
+    /// caption is hidden.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::{Caption, PlotConfig};
+    ///
+    /// let mut config = PlotConfig::default();
+    /// assert_eq!(config.caption_or("Impedance of PDN"), Some("Impedance of PDN".to_string()));
+    /// config.caption = Caption::Text("Figure 3".to_string());
+    /// assert_eq!(config.caption_or("Impedance of PDN"), Some("Figure 3".to_string()));
+    /// config.caption = Caption::Hidden;
+    /// assert_eq!(config.caption_or("Impedance of PDN"), None);
+    /// ```
+    pub fn caption_or(&self, default: &str) -> Option<String> {
+        match &self.caption {
+            Caption::Default => Some(default.to_string()),
+            Caption::Text(text) => Some(text.clone()),
+            Caption::Hidden => None,
+        }
+    }
+
+    /// Apply the non-finite policy to a sweep of the model named `name`.
+    /// Returns the sweep to draw, or an error naming the model and frequency
+    /// if the policy is `Error` and a sample is not finite.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::{NonFinitePolicy, PlotConfig};
+    /// use sdomain_test_plotters::error::PlotError;
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// let sweep = Sweep {freqs: vec![1.0, 2.0], mags: vec![f64::NAN, 1.0], phases: vec![0.0, 0.0]};
+    /// let mut config = PlotConfig::default();
+    /// assert_eq!(
+    ///     config.check_finite(sweep.clone(), "RC").unwrap_err(),
+    ///     PlotError::NonFiniteSample {model: "RC".to_string(), freq: 1.0}
+    /// );
+    /// config.non_finite = NonFinitePolicy::Skip;
+    /// assert_eq!(config.check_finite(sweep, "RC").unwrap().freqs, vec![2.0]);
+    /// ```
+    pub fn check_finite(&self, sweep: Sweep, name: &str) -> Result<Sweep, PlotError> {
+        match (self.non_finite, sweep.first_non_finite()) {
+            (_, None) => Ok(sweep),
+            (NonFinitePolicy::Error, Some(freq)) => Err(PlotError::NonFiniteSample {model: name.to_string(), freq}),
+            (NonFinitePolicy::Skip, Some(_)) => Ok(sweep.without_non_finite()),
+            (NonFinitePolicy::Clamp, Some(_)) => Ok(sweep.clamp_non_finite()),
+        }
+    }
+
+    /// Apply the non-finite policy to two sweeps, of the models named
+    /// `name_a` and `name_b`, taken over the same frequencies so they are
+    /// compared point by point. Under `Skip` a sample that is not finite in
+    /// either sweep is left out of both, keeping them aligned.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::{NonFinitePolicy, PlotConfig};
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// let a = Sweep {freqs: vec![1.0, 2.0, 3.0], mags: vec![f64::NAN, 1.0, 1.0], phases: vec![0.0; 3]};
+    /// let b = Sweep {freqs: vec![1.0, 2.0, 3.0], mags: vec![1.0, 1.0, f64::NAN], phases: vec![0.0; 3]};
+    /// let config = PlotConfig {non_finite: NonFinitePolicy::Skip, ..PlotConfig::default()};
+    /// let (a, b) = config.check_finite_pair(a, "A", b, "B").unwrap();
+    /// assert_eq!((a.freqs, b.freqs), (vec![2.0], vec![2.0]));
+    /// ```
+    pub fn check_finite_pair(&self, a: Sweep, name_a: &str, b: Sweep, name_b: &str) -> Result<(Sweep, Sweep), PlotError> {
+        match self.non_finite {
+            NonFinitePolicy::Skip => Ok(a.without_non_finite_jointly(&b)),
+            NonFinitePolicy::Error | NonFinitePolicy::Clamp => Ok((self.check_finite(a, name_a)?, self.check_finite(b, name_b)?)),
+        }
+    }
+
+    /// Sample a model over the configured frequencies and apply the
+    /// non-finite policy, naming the model by its display name.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// let config = PlotConfig {fmin: 1e3, fmax: 1e6, ..PlotConfig::default()};
+    /// let sweep = config.sweep(&sdomain::gen::capacitor(1e-6)).unwrap();
+    /// assert_eq!(sweep.freqs, config.sample_freqs());
+    /// ```
+    pub fn sweep<P: Plottable + ?Sized>(&self, model: &P) -> Result<Sweep, PlotError> {
+        self.check_finite(Sweep::at(model, self.sample_freqs()), &model.display_name())
+    }
+
+    /// Lowest and highest frequency of the sweep in Hz.
+    pub fn freq_limits_hz(&self) -> (f64, f64) {
+        (self.freq_unit.to_hz(self.fmin), self.freq_unit.to_hz(self.fmax))
+    }
+
+    /// Frequencies in Hz to sample, spaced evenly along the frequency axis.
+    pub fn sample_freqs(&self) -> Vec<f64> {
+        self.freq_scale.sample(self.fmin, self.fmax).into_iter()
+            .map(|value| self.freq_unit.to_hz(value))
+            .collect()
+    }
+
+    /// Position of `freq` in Hz along the frequency axis.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use sdomain_test_plotters::axis::FreqUnit;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// let config = PlotConfig {freq_unit: FreqUnit::RadPerSec, ..PlotConfig::default()};
+    /// assert!((config.freq_to_axis(1e3/(2.0*PI)) - 3.0).abs() < 1e-9);
+    /// ```
+    pub fn freq_to_axis(&self, freq: f64) -> f64 {
+        self.freq_scale.to_axis(self.freq_unit.from_hz(freq))
+    }
+
+    /// Frequency axis range.
+    pub fn freq_range(&self) -> Range<f64> {
+        self.freq_scale.range(self.fmin, self.fmax)
+    }
+
+    /// Tick label at position `x` along the frequency axis.
+    pub fn freq_tick(&self, x: f64) -> String {
+        self.freq_unit.format(self.freq_scale.from_axis(x))
+    }
+
+    /// Number of tick labels to ask for on the frequency axis.
+    pub fn freq_label_count(&self) -> usize {
+        self.freq_scale.label_count(self.fmin, self.fmax)
+    }
+
+    /// Magnitude axis range in axis units for data spanning `min` to `max`
+    /// in linear units. Pinned limits are used as given; otherwise the range
+    /// is padded by `y_padding` decades.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// let mut config = PlotConfig {y_padding: 1.0, ..PlotConfig::default()};
+    /// let padded = config.y_range(1e-3, 10.0);
+    /// assert!((padded.start + 4.0).abs() < 1e-9 && (padded.end - 2.0).abs() < 1e-9);
+    ///
+    /// config.y_limits = Some((1e-2, 1e2));
+    /// let pinned = config.y_range(1e-3, 10.0);
+    /// assert!((pinned.start + 2.0).abs() < 1e-9 && (pinned.end - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn y_range(&self, min: f64, max: f64) -> Range<f64> {
+        match self.y_limits {
+            Some((low, high)) => self.mag_scale.to_axis(low)..self.mag_scale.to_axis(high),
+            None => self.mag_scale.padded_range(min, max, self.y_padding),
+        }
+    }
+
+    /// Magnitude axis ranges, in axis units, of the lower and upper panels of
+    /// a broken axis for data spanning `min` to `max` in linear units, or
+    /// `None` without a break or if the data does not reach below and above
+    /// it. The outer ends are found like `y_range`.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// let config = PlotConfig {y_padding: 0.0, y_break: Some((0.1, 100.0)), ..PlotConfig::default()};
+    /// let (lower, upper) = config.y_break_ranges(1e-3, 1e4).unwrap();
+    /// assert!((lower.start + 3.0).abs() < 1e-9 && (lower.end + 1.0).abs() < 1e-9);
+    /// assert!((upper.start - 2.0).abs() < 1e-9 && (upper.end - 4.0).abs() < 1e-9);
+    ///
+    /// // Nothing below the break to show in a lower panel
+    /// assert!(config.y_break_ranges(1.0, 1e4).is_none());
+    /// ```
+    pub fn y_break_ranges(&self, min: f64, max: f64) -> Option<(Range<f64>, Range<f64>)> {
+        let (low, high) = self.y_break?;
+        if min >= low || max <= high {
+            return None;
+        }
+        let lower = self.y_range(min, low).start..self.mag_scale.to_axis(low);
+        let upper = self.mag_scale.to_axis(high)..self.y_range(high, max).end;
+        if lower.start >= lower.end || upper.start >= upper.end {
+            return None;
+        }
+        Some((lower, upper))
+    }
+
+    /// Tick label at position `y` along the magnitude axis for a quantity
+    /// measured in `unit`. Log and linear axes use engineering notation;
+    /// a dB axis shows plain numbers.
+    pub fn mag_tick(&self, y: f64, unit: &str) -> String {
+        match self.mag_scale {
+            MagScale::Log | MagScale::Linear => format_eng(self.mag_scale.from_axis(y), unit, self.y_digits),
+            _ => format_plain(y),
+        }
+    }
+
+    /// Phase values to draw for `sweep`, unwrapped if the phase range is
+    /// wider than 360°.
+    pub fn phases(&self, sweep: &Sweep) -> Vec<f64> {
+        if self.phase_range.1 - self.phase_range.0 > 360.0 {
+            sweep.unwrapped_phases()
+        } else {
+            sweep.phases.clone()
+        }
+    }
+
+    /// Position on the phase axis of an unwrapped phase in degrees, matching
+    /// the values from `phases`.
+    pub fn phase_to_axis(&self, unwrapped: f64) -> f64 {
+        if self.phase_range.1 - self.phase_range.0 > 360.0 {unwrapped} else {wrap_phase(unwrapped)}
+    }
+
+    /// Frequency axis label, or the default label for `freq_unit` if none was set.
+    pub fn x_label(&self) -> String {
+        self.x_label.clone().unwrap_or_else(|| self.freq_unit.axis_label().to_string())
+    }
+
+    /// Magnitude axis label, or `default` if none was set.
+    pub fn y_label_or(&self, default: &str) -> String {
+        self.y_label.clone().unwrap_or_else(|| default.to_string())
+    }
+
+    /// Phase axis label, or `default` if none was set.
+    pub fn phase_label_or(&self, default: &str) -> String {
+        self.phase_label.clone().unwrap_or_else(|| default.to_string())
+    }
+
+    /// The configuration as a TOML document with one key per field, so a
+    /// chart's settings can be saved next to it and read back by any TOML
+    /// parser. Unset options are left out. Enums are written by variant
+    /// name, and variants carrying a value as a table such as
+    /// `{Dbm = 50.0}`.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::bands::FreqBand;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// let config = PlotConfig {
+    ///     fmax: 100e6,
+    ///     mag_scale: MagScale::Dbm(50.0),
+    ///     target: Some(0.1),
+    ///     bands: vec![FreqBand::fm_broadcast()],
+    ///     ..PlotConfig::default()
+    /// };
+    /// let toml = config.to_toml();
+    /// assert!(toml.contains("fmax = 100000000.0\n"));
+    /// assert!(toml.contains("freq_scale = \"Log\"\n"));
+    /// assert!(toml.contains("mag_scale = {Dbm = 50.0}\n"));
+    /// assert!(toml.contains("target = 0.1\n"));
+    /// assert!(!toml.contains("y_limits"));
+    /// assert!(toml.contains("[[bands]]\nname = \"FM Broadcast\"\n"));
+    /// ```
+    pub fn to_toml(&self) -> String {
+        let mut keys = vec![
+            ("fmin", toml_float(self.fmin)),
+            ("fmax", toml_float(self.fmax)),
+            ("freq_scale", toml_string(&format!("{:?}", self.freq_scale))),
+            ("freq_unit", match self.freq_unit {
+                FreqUnit::Normalized(f0) => format!("{{Normalized = {}}}", toml_float(f0)),
+                unit => toml_string(&format!("{unit:?}")),
+            }),
+            ("mag_scale", match self.mag_scale {
+                MagScale::Dbm(reference) => format!("{{Dbm = {}}}", toml_float(reference)),
+                scale => toml_string(&format!("{scale:?}")),
+            }),
+            ("y_padding", toml_float(self.y_padding)),
+            ("y_digits", self.y_digits.to_string()),
+            ("dual_mag_axis", self.dual_mag_axis.to_string()),
+        ];
+        keys.extend(self.y_limits.map(|limits| ("y_limits", toml_pair(limits))));
+        keys.extend(self.y_break.map(|limits| ("y_break", toml_pair(limits))));
+        keys.extend([
+            ("show_phase", self.show_phase.to_string()),
+            ("group_delay", self.group_delay.to_string()),
+            ("phase_layout", toml_string(&format!("{:?}", self.phase_layout))),
+            ("phase_range", toml_pair(self.phase_range)),
+            ("non_finite", toml_string(&format!("{:?}", self.non_finite))),
+        ]);
+        keys.extend(self.target.map(|target| ("target", toml_float(target))));
+        keys.extend([
+            ("target_ratio", self.target_ratio.to_string()),
+            ("annotate_corners", self.annotate_corners.to_string()),
+            ("asymptotes", self.asymptotes.to_string()),
+            ("slope_unit", toml_string(&format!("{:?}", self.slope_unit))),
+            ("annotate_crossovers", self.annotate_crossovers.to_string()),
+            ("pole_zero_table", self.pole_zero_table.to_string()),
+            ("annotate_margins", self.annotate_margins.to_string()),
+            ("caption", match &self.caption {
+                Caption::Text(text) => format!("{{Text = {}}}", toml_string(text)),
+                caption => toml_string(&format!("{caption:?}")),
+            }),
+        ]);
+        keys.extend(self.x_label.as_deref().map(|label| ("x_label", toml_string(label))));
+        keys.extend(self.y_label.as_deref().map(|label| ("y_label", toml_string(label))));
+        keys.extend(self.phase_label.as_deref().map(|label| ("phase_label", toml_string(label))));
+
+        let mut toml = keys.into_iter()
+            .map(|(key, value)| format!("{key} = {value}\n"))
+            .collect::<String>();
+        if let Some(comb) = &self.harmonic_comb {
+            toml.push_str(&format!("\n[harmonic_comb]\nf_sw = {}\ncount = {}\n", toml_float(comb.f_sw), comb.count));
+        }
+        for band in &self.bands {
+            toml.push_str(&format!(
+                "\n[[bands]]\nname = {}\nstart = {}\nend = {}\ncolor = [{}, {}, {}]\n",
+                toml_string(&band.name), toml_float(band.start), toml_float(band.end),
+                band.color.0, band.color.1, band.color.2
+            ));
+        }
+        for (header, members) in &self.legend.groups {
+            toml.push_str(&format!(
+                "\n[[legend]]\nheader = {}\nmembers = [{}]\n",
+                toml_string(header),
+                members.iter().map(|member| toml_string(member)).collect::<Vec<String>>().join(", ")
+            ));
+        }
+        toml
+    }
+}
+
+/// `value` as a TOML float, which always has a fraction or an exponent.
+fn toml_float(value: f64) -> String {
+    if value.is_nan() {"nan".to_string()} else {format!("{value:?}")}
+}
+
+/// `pair` as a TOML array of two floats.
+fn toml_pair(pair: (f64, f64)) -> String {
+    format!("[{}, {}]", toml_float(pair.0), toml_float(pair.1))
+}
+
+/// `text` as a TOML basic string, with quotes, backslashes, and control
+/// characters escaped.
+fn toml_string(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+
+use crate::sweep::Sweep;
+use crate::units::{format_freq, format_ohms};
+
+/// Resistance below this fraction of |Z| is flagged, leaving room for
+/// rounding in the model evaluation of purely reactive parts.
+const RESISTANCE_TOLERANCE: f64 = 1e-8;
+
+/// Kind of non-physical behavior found in a passive impedance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Issue {
+    /// The real part of the impedance is negative, i.e. the network would
+    /// deliver power. Seen from the phase, it is outside ±90°.
+    NegativeResistance,
+}
+
+/// One kind of non-physical behavior and where it is worst.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub issue: Issue,
+    /// Number of samples showing the issue.
+    pub samples: usize,
+    /// Frequency in Hz of the worst sample.
+    pub freq: f64,
+    /// Value at the worst sample: the resistance in Ω.
+    pub value: f64,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.issue {
+            Issue::NegativeResistance => write!(f, "negative resistance of {}", format_ohms(self.value))?,
+        }
+        write!(f, " at {} ({} samples)", format_freq(self.freq), self.samples)
+    }
+}
+
+/// Check an impedance sweep of a passive network for results no passive
+/// network can produce, which usually point to a mistake building the
+/// model, such as a sign error or a component added in the wrong place.
+/// Returns one finding per kind of issue, empty if the sweep is consistent.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::consistency::{self, Issue};
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// use sdomain_test::sdomain;
+///
+/// let good = Sweep::of(&sdomain::gen::rcl(5e-3, 22e-6, 1e-9), 1.0, 100e6);
+/// assert!(consistency::check_passive(&good).is_empty());
+///
+/// // A -1Ω resistor, e.g. from subtracting instead of adding a model
+/// let bad = Sweep::of(&(sdomain::gen::rl(1.0, 1e-9) - &sdomain::gen::resistor(2.0)), 1.0, 100e6);
+/// let findings = consistency::check_passive(&bad);
+/// assert_eq!(findings.len(), 1);
+/// assert_eq!(findings[0].issue, Issue::NegativeResistance);
+/// assert!((findings[0].value + 1.0).abs() < 1e-6);
+/// ```
+pub fn check_passive(sweep: &Sweep) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = vec![];
+    let mut note = |issue: Issue, freq: f64, value: f64, badness: f64| {
+        match findings.iter_mut().find(|finding| finding.issue == issue) {
+            Some(finding) => {
+                finding.samples += 1;
+                if badness > severity(finding) {
+                    finding.freq = freq;
+                    finding.value = value;
+                }
+            },
+            None => findings.push(Finding {issue, samples: 1, freq, value}),
+        }
+    };
+    for ((freq, real), mag) in sweep.freqs.iter().zip(sweep.real()).zip(sweep.mags.iter()) {
+        if real < -RESISTANCE_TOLERANCE*mag {
+            note(Issue::NegativeResistance, *freq, real, -real);
+        }
+    }
+    findings
+}
+
+/// How far a finding's worst sample is from being physical, for comparing
+/// samples of the same issue.
+fn severity(finding: &Finding) -> f64 {
+    match finding.issue {
+        Issue::NegativeResistance => -finding.value,
+    }
+}
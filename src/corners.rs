@@ -0,0 +1,103 @@
+use crate::sweep::Sweep;
+
+/// A corner frequency where the slope of a magnitude response changes.
+#[derive(Clone, Copy, Debug)]
+pub struct Corner {
+    /// Corner frequency in Hz.
+    pub freq: f64,
+    /// Asymptotic slope below the corner in dB/decade.
+    pub slope_before: f64,
+    /// Asymptotic slope above the corner in dB/decade.
+    pub slope_after: f64,
+}
+
+/// Find the corner frequencies of a magnitude response. The local slope of
+/// the response is rounded to the nearest multiple of 20 dB/decade, and a
+/// corner is placed wherever the rounded slope changes. For a simple pole or
+/// zero the slope passes halfway between its asymptotes exactly at the
+/// corner frequency, so this recovers the textbook corner.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(points = sweep.freqs.len())))]
+pub fn find_corners(sweep: &Sweep) -> Vec<Corner> {
+    let slopes = sweep.freqs.windows(2).zip(sweep.mags.windows(2))
+        .map(|(f, m)| {
+            let slope = 20.0*(m[1]/m[0]).log10()/(f[1]/f[0]).log10();
+            ((f[0]*f[1]).sqrt(), 20.0*(slope/20.0).round())
+        })
+        .collect::<Vec<(f64, f64)>>();
+
+    let corners = slopes.windows(2)
+        .filter(|pair| pair[0].1 != pair[1].1)
+        .map(|pair| Corner {
+            freq: (pair[0].0*pair[1].0).sqrt(),
+            slope_before: pair[0].1,
+            slope_after: pair[1].1,
+        })
+        .collect::<Vec<Corner>>();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(corners = corners.len(), "corners found");
+    corners
+}
+
+/// One straight-line piece of the asymptotic approximation of a magnitude
+/// response, between two corners or a corner and the end of the sweep.
+#[derive(Clone, Copy, Debug)]
+pub struct Asymptote {
+    /// Frequency in Hz where the piece starts.
+    pub fstart: f64,
+    /// Frequency in Hz where the piece ends.
+    pub fend: f64,
+    /// Magnitude of the asymptote at `fstart`.
+    pub mag_start: f64,
+    /// Slope in dB/decade.
+    pub slope: f64,
+}
+
+impl Asymptote {
+    /// Magnitude of the asymptote extended to `freq`, which may be outside
+    /// the piece.
+    pub fn mag_at(&self, freq: f64) -> f64 {
+        self.mag_start*10f64.powf(self.slope/20.0*(freq/self.fstart).log10())
+    }
+}
+
+/// Build the asymptotic approximation of a magnitude response the way bode
+/// plots are constructed by hand: the first piece starts at the response at
+/// the lowest frequency, and at each corner the next piece continues from
+/// the intersection with the new slope.
+/// Returns the pieces from lowest to highest frequency.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::corners;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// use sdomain_test::sdomain;
+///
+/// // RC low pass filter: flat, then -20 dB/decade above 1.59kHz
+/// let zr = sdomain::gen::resistor(100.0);
+/// let zc = sdomain::gen::capacitor(1e-6);
+/// let lpf = zc.clone() / &(zr + &zc);
+/// let pieces = corners::asymptotes(&Sweep::of(&lpf, 1.0, 10e6));
+/// assert_eq!(pieces.len(), 2);
+/// assert_eq!(pieces[1].slope, -20.0);
+/// assert!((pieces[1].mag_at(159e3)/0.01 - 1.0).abs() < 0.05);
+/// ```
+pub fn asymptotes(sweep: &Sweep) -> Vec<Asymptote> {
+    let (fmin, fmax) = match (sweep.freqs.first(), sweep.freqs.last()) {
+        (Some(fmin), Some(fmax)) => (*fmin, *fmax),
+        _ => return vec![],
+    };
+    let corners = find_corners(sweep);
+    let first_slope = corners.first().map(|corner| corner.slope_before).unwrap_or_else(|| {
+        20.0*(sweep.mags[sweep.mags.len() - 1]/sweep.mags[0]).log10()/(fmax/fmin).log10()
+    });
+
+    let mut pieces = vec![Asymptote {fstart: fmin, fend: fmax, mag_start: sweep.mags[0], slope: first_slope}];
+    for corner in corners.iter() {
+        let last = pieces.last_mut().unwrap();
+        last.fend = corner.freq;
+        let mag_start = last.mag_at(corner.freq);
+        pieces.push(Asymptote {fstart: corner.freq, fend: fmax, mag_start, slope: corner.slope_after});
+    }
+    pieces
+}
@@ -0,0 +1,67 @@
+use sdomain_test::sdomain::{self, Fs};
+
+use crate::two_port::TwoPort;
+
+/// Two rails coupled through an impedance they share, such as a common
+/// plane neck, via, or ground return. The aggressor rail draws its current
+/// through its own path and the shared impedance; the victim rail hangs off
+/// the same shared node through its own path and is decoupled at its load.
+/// ```text
+///   ref ──[ shared ]──┬──[ aggressor ]── aggressor load (port 1)
+///                     └──[ victim ]──┬── victim load (port 2)
+///                                 victim_decap
+///                                    │
+///                                   ref
+/// ```
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::crosstalk::RailCoupling;
+/// use sdomain_test_plotters::plottable::Plottable;
+///
+/// use sdomain_test::sdomain;
+///
+/// let coupling = RailCoupling {
+///     shared: sdomain::gen::rl(0.5e-3, 200e-12),
+///     aggressor: sdomain::gen::rl(1e-3, 500e-12),
+///     victim: sdomain::gen::rl(1e-3, 500e-12),
+///     victim_decap: sdomain::gen::rcl(5e-3, 10e-6, 400e-12),
+/// };
+/// // At low frequency the victim's decoupling is open, so the victim sees
+/// // the full drop across the shared resistance
+/// assert!((coupling.transfer().response(1.0).mag() - 0.5e-3).abs() < 1e-6);
+/// ```
+#[derive(Clone)]
+pub struct RailCoupling {
+    /// Impedance common to both rails, between the reference and the shared node.
+    pub shared: Fs,
+    /// Path of the aggressor rail from the shared node to its load.
+    pub aggressor: Fs,
+    /// Path of the victim rail from the shared node to its load.
+    pub victim: Fs,
+    /// Decoupling at the victim's load, to the reference.
+    pub victim_decap: Fs,
+}
+
+impl RailCoupling {
+    /// Transfer impedance from aggressor load current to victim load
+    /// voltage, in V/A, with the victim's load current held constant.
+    pub fn transfer(&self) -> Fs {
+        // The shared drop, divided between the victim path and its decoupling
+        let loop_total = self.shared.clone() + &self.victim + &self.victim_decap;
+        self.victim_decap.clone() / &(loop_total / &self.shared)
+    }
+
+    /// The coupled rails as a two-port, port 1 at the aggressor load and
+    /// port 2 at the victim load, for use with the two-port plots and
+    /// exports.
+    pub fn two_port(&self) -> TwoPort {
+        let transfer = self.transfer();
+        TwoPort {
+            z11: self.aggressor.clone() + &sdomain::parallel(self.shared.clone(), self.victim.clone() + &self.victim_decap),
+            z12: transfer.clone(),
+            z21: transfer,
+            z22: sdomain::parallel(self.victim_decap.clone(), self.victim.clone() + &self.shared),
+        }
+    }
+}
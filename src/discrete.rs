@@ -0,0 +1,574 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use sdomain_test::range_generators::gen_log_range;
+
+use crate::phasor::Phasor;
+use crate::plottable::Plottable;
+use crate::sweep::Sweep;
+
+/// Highest numerator or denominator order tried when recovering the
+/// coefficients of a model.
+const MAX_ORDER: usize = 8;
+
+/// Largest relative deviation from the model, at any sampled frequency, for
+/// recovered coefficients to be accepted.
+const FIT_TOLERANCE: f64 = 1e-6;
+
+/// Reweighting passes of the coefficient fit.
+const FIT_ITERATIONS: usize = 10;
+
+/// Most Durand-Kerner passes when finding the roots of a polynomial.
+const ROOT_ITERATIONS: usize = 500;
+
+/// Decades below and above the sample rate over which a model is sampled
+/// to recover its coefficients for discretization.
+const FIT_DECADES: (f64, f64) = (6.0, 1.0);
+
+/// Reason a model could not be turned into coefficients or discretized.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiscreteError {
+    /// No ratio of polynomials up to the highest order tried matches the
+    /// model, e.g. because it contains a delay or a transmission line.
+    NoFit {max_order: usize},
+    /// The numerator has a higher order than the denominator, which has no
+    /// zero-order hold equivalent. The bilinear transforms accept it.
+    Improper {num_order: usize, den_order: usize},
+    /// The denominator vanishes where the method needs it, e.g. a pole
+    /// exactly at the bilinear transform's s = 2·fs.
+    Singular,
+}
+
+impl fmt::Display for DiscreteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiscreteError::NoFit {max_order} => write!(f, "the model is not a rational function of order {max_order} or less"),
+            DiscreteError::Improper {num_order, den_order} => write!(f, "numerator order {num_order} exceeds denominator order {den_order}, which has no zero-order hold equivalent"),
+            DiscreteError::Singular => write!(f, "the denominator vanishes at the transform point"),
+        }
+    }
+}
+
+impl std::error::Error for DiscreteError {}
+
+/// A continuous-time transfer function as a ratio of polynomials in s,
+/// with coefficients in ascending powers: num[0] + num[1]·s + …
+///
+/// sdomain_test does not expose the coefficients of an `Fs`, so `fit`
+/// recovers them from the model's response: it finds the lowest orders
+/// whose ratio of polynomials matches the response to within 1 ppm across
+/// the band, which for a rational model is the model itself.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::discrete::Rational;
+///
+/// use sdomain_test::sdomain;
+///
+/// // PI compensator 10·(1 + 2π·100/s) = (10·s + 2π·1000)/s
+/// let pi = sdomain::gen::resistor(10.0) + &(sdomain::gen::capacitor(1.0/(2.0*std::f64::consts::PI*1000.0)));
+/// let rational = Rational::fit(&pi, 1.0, 1e6).unwrap();
+/// assert_eq!((rational.num.len(), rational.den.len()), (2, 2));
+/// // Denominators are scaled so the highest power has a coefficient of 1
+/// assert!((rational.num[1] - 10.0).abs() < 1e-5);
+/// assert!((rational.num[0] - 2.0*std::f64::consts::PI*1000.0).abs() < 1e-2);
+/// assert!(rational.den[0].abs() < 1e-6 && rational.den[1] == 1.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rational {
+    /// Numerator coefficients, constant term first.
+    pub num: Vec<f64>,
+    /// Denominator coefficients, constant term first.
+    pub den: Vec<f64>,
+}
+
+impl Rational {
+    /// Find the coefficients of `model` from its response sampled between
+    /// `fmin` and `fmax` in Hz. The band should cover every pole and zero
+    /// that matters, since features far outside it are not resolved.
+    /// Returns the lowest order ratio of polynomials that matches, or an
+    /// error if none up to order 8 does.
+    pub fn fit<P: Plottable + ?Sized>(model: &P, fmin: f64, fmax: f64) -> Result<Rational, DiscreteError> {
+        let freqs = gen_log_range(fmin, fmax, 10.0, 40);
+        // Fit against s/ω0 so the powers of s stay near 1 across the band
+        let w0 = 2.0*PI*(fmin*fmax).sqrt();
+        let samples = freqs.iter().zip(model.responses(&freqs))
+            .map(|(f, response)| (2.0*PI*f/w0, Phasor::from_polar(response.mag(), response.phase_deg())))
+            .filter(|(_, h)| h.abs() > 0.0 && h.abs().is_finite())
+            .collect::<Vec<(f64, Phasor)>>();
+        if samples.is_empty() {
+            return Err(DiscreteError::NoFit {max_order: MAX_ORDER});
+        }
+
+        // Lowest total order first, allowing up to two more zeros than
+        // poles for derivative terms
+        for total in 0..=2*MAX_ORDER + 2 {
+            for den_order in 0..=total.min(MAX_ORDER) {
+                let num_order = total - den_order;
+                if num_order > den_order + 2 {continue;}
+                if let Some(normalized) = fit_orders(&samples, num_order, den_order) {
+                    let scale = |coeffs: Vec<f64>| coeffs.into_iter().enumerate().map(|(k, c)| c/w0.powi(k as i32)).collect::<Vec<f64>>();
+                    let (num, den) = (scale(normalized.num), scale(normalized.den));
+                    // Keep the leading denominator coefficient at exactly 1
+                    let lead = den[den_order];
+                    return Ok(Rational {
+                        num: num.into_iter().map(|c| c/lead).collect(),
+                        den: den.into_iter().map(|c| c/lead).collect(),
+                    });
+                }
+            }
+        }
+        Err(DiscreteError::NoFit {max_order: MAX_ORDER})
+    }
+
+    /// Response at `freq` in Hz.
+    pub fn response_at(&self, freq: f64) -> Phasor {
+        let s = Phasor::new(0.0, 2.0*PI*freq);
+        polyval(&self.num, s)/polyval(&self.den, s)
+    }
+
+    /// Whether the numerator order is at most the denominator order.
+    pub fn is_proper(&self) -> bool {
+        self.num.len() <= self.den.len()
+    }
+
+    /// Roots of the numerator, the zeros of the model, as values of s in rad/s.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::discrete::Rational;
+    ///
+    /// // (s + 1000)/(s² + 2·600·s + 1000²): a real zero and a complex pole pair
+    /// let rational = Rational {num: vec![1000.0, 1.0], den: vec![1e6, 1200.0, 1.0]};
+    /// let zeros = rational.zeros();
+    /// assert_eq!(zeros.len(), 1);
+    /// assert!((zeros[0].re + 1000.0).abs() < 1e-6 && zeros[0].im.abs() < 1e-6);
+    /// let poles = rational.poles();
+    /// assert!(poles.iter().all(|p| (p.re + 600.0).abs() < 1e-6 && (p.im.abs() - 800.0).abs() < 1e-6));
+    /// ```
+    pub fn zeros(&self) -> Vec<Phasor> {
+        polyroots(&self.num)
+    }
+
+    /// Roots of the denominator, the poles of the model, as values of s in rad/s.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::discrete::Rational;
+    ///
+    /// // 1/s and 1/s²: every pole of an integrator is at the origin
+    /// let integrator = Rational {num: vec![1.0], den: vec![0.0, 1.0]};
+    /// assert_eq!(integrator.poles().len(), 1);
+    /// assert!(integrator.poles()[0].abs() < 1e-12);
+    /// let double = Rational {num: vec![1.0], den: vec![0.0, 0.0, 1.0]};
+    /// assert_eq!(double.poles().len(), 2);
+    /// assert!(double.poles().iter().all(|p| p.abs() < 1e-12));
+    ///
+    /// // 1/(s·(s + 1000)): one pole at the origin, one found by iteration
+    /// let lag = Rational {num: vec![1.0], den: vec![0.0, 1000.0, 1.0]};
+    /// let poles = lag.poles();
+    /// assert_eq!(poles.len(), 2);
+    /// assert!(poles[0].abs() < 1e-12);
+    /// assert!((poles[1].re + 1000.0).abs() < 1e-6 && poles[1].im.abs() < 1e-6);
+    /// ```
+    pub fn poles(&self) -> Vec<Phasor> {
+        polyroots(&self.den)
+    }
+}
+
+/// Method of turning a continuous-time model into a discrete-time one.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Discretization {
+    /// Bilinear (Tustin) transform, s = 2·fs·(z - 1)/(z + 1). Maps the
+    /// whole jω axis onto the unit circle, compressing high frequencies
+    /// towards Nyquist.
+    #[default]
+    Bilinear,
+    /// Bilinear transform prewarped so that the discrete response matches
+    /// the continuous one exactly at the given frequency in Hz, e.g. a
+    /// compensator's crossover or notch.
+    Prewarped(f64),
+    /// Zero-order hold equivalent, the exact discrete model of a plant
+    /// driven through a DAC that holds each sample for one period. Needs a
+    /// proper model, with no more zeros than poles.
+    ZeroOrderHold,
+}
+
+/// A discrete-time transfer function sampled at `sample_rate`, as the
+/// coefficients of a ratio of polynomials in z⁻¹:
+/// `H(z) = (num[0] + num[1]·z⁻¹ + …)/(1 + den[1]·z⁻¹ + …)`. The same
+/// coefficients implement the system as a difference equation, e.g. a
+/// digital compensator, which `filter` runs.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::discrete::{Discrete, Discretization};
+/// use sdomain_test_plotters::phasor::Phasor;
+///
+/// use sdomain_test::sdomain;
+///
+/// // First-order low pass at 1kHz, sampled at 20kHz
+/// let lpf = sdomain::gen::resistor(1.0)/&(sdomain::gen::resistor(1.0) + &sdomain::gen::rl(0.0, 1.0/(2.0*std::f64::consts::PI*1e3)));
+/// let fs = 20e3;
+///
+/// // The exact zero-order hold equivalent is (1 - a)·z⁻¹/(1 - a·z⁻¹) with a = e^(-2π·1kHz/fs)
+/// let zoh = Discrete::new(&lpf, fs, Discretization::ZeroOrderHold).unwrap();
+/// let a = (-2.0*std::f64::consts::PI*1e3/fs).exp();
+/// assert!(zoh.num[0].abs() < 1e-9 && (zoh.num[1] - (1.0 - a)).abs() < 1e-6);
+/// assert!((zoh.den[1] + a).abs() < 1e-6);
+/// let z = Phasor::from_polar(1.0, 360.0*500.0/fs);
+/// let exact = Phasor::from(1.0 - a)/(z - Phasor::from(a));
+/// assert!((zoh.response_at(500.0) - exact).abs() < 1e-6);
+///
+/// // Its step response, run through the difference equation
+/// let step = zoh.filter(&[1.0; 40]);
+/// assert!((step[39] - 1.0).abs() < 1e-3);
+///
+/// // Prewarping matches the magnitude at the chosen frequency
+/// let tustin = Discrete::new(&lpf, fs, Discretization::Prewarped(1e3)).unwrap();
+/// assert!((tustin.response_at(1e3).abs() - 0.5f64.sqrt()).abs() < 1e-6);
+/// assert_eq!(tustin.nyquist(), 10e3);
+///
+/// // A PD compensator has no zero-order hold equivalent, but a bilinear one
+/// let pd = sdomain::gen::rl(1.0, 1e-4);
+/// assert!(Discrete::new(&pd, fs, Discretization::ZeroOrderHold).is_err());
+/// assert!(Discrete::new(&pd, fs, Discretization::Bilinear).is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Discrete {
+    /// Numerator coefficients in ascending powers of z⁻¹.
+    pub num: Vec<f64>,
+    /// Denominator coefficients in ascending powers of z⁻¹, den[0] = 1.
+    pub den: Vec<f64>,
+    /// Sample rate in Hz.
+    pub sample_rate: f64,
+    /// How the model was discretized.
+    pub method: Discretization,
+}
+
+impl Discrete {
+    /// Discretize `model` at `sample_rate` in Hz with `method`. The
+    /// coefficients of the model are recovered with `Rational::fit` over
+    /// six decades below to one decade above the sample rate.
+    pub fn new<P: Plottable + ?Sized>(model: &P, sample_rate: f64, method: Discretization) -> Result<Discrete, DiscreteError> {
+        let band = (sample_rate/10f64.powf(FIT_DECADES.0), sample_rate*10f64.powf(FIT_DECADES.1));
+        let rational = Rational::fit(model, band.0, band.1)?;
+        Discrete::from_rational(&rational, sample_rate, method)
+    }
+
+    /// Discretize a transfer function given by its coefficients in s.
+    pub fn from_rational(rational: &Rational, sample_rate: f64, method: Discretization) -> Result<Discrete, DiscreteError> {
+        let (num, den) = match method {
+            Discretization::Bilinear => bilinear(rational, 2.0*sample_rate)?,
+            Discretization::Prewarped(warp) => bilinear(rational, 2.0*PI*warp/(PI*warp/sample_rate).tan())?,
+            Discretization::ZeroOrderHold => zero_order_hold(rational, 1.0/sample_rate)?,
+        };
+        Ok(Discrete {num, den, sample_rate, method})
+    }
+
+    /// Nyquist frequency in Hz, the highest frequency the discrete system has.
+    pub fn nyquist(&self) -> f64 {
+        self.sample_rate/2.0
+    }
+
+    /// Discrete frequency response at `freq` in Hz, from 0 up to Nyquist,
+    /// evaluated from the coefficients at z = e^(j·2π·f/fs).
+    pub fn response_at(&self, freq: f64) -> Phasor {
+        let z_inv = Phasor::from_polar(1.0, -360.0*freq/self.sample_rate);
+        polyval(&self.num, z_inv)/polyval(&self.den, z_inv)
+    }
+
+    /// Discrete frequency response at each of `freqs` in Hz.
+    pub fn sweep(&self, freqs: Vec<f64>) -> Sweep {
+        let responses = freqs.iter().map(|f| self.response_at(*f)).collect::<Vec<Phasor>>();
+        Sweep {
+            mags: responses.iter().map(Phasor::abs).collect(),
+            phases: responses.iter().map(Phasor::arg_deg).collect(),
+            freqs,
+        }
+    }
+
+    /// Run `input` samples through the difference equation
+    /// `y[n] = Σ num[k]·x[n - k] - Σ den[k]·y[n - k]`, starting from rest.
+    pub fn filter(&self, input: &[f64]) -> Vec<f64> {
+        let mut output: Vec<f64> = Vec::with_capacity(input.len());
+        for n in 0..input.len() {
+            let forward = self.num.iter().enumerate()
+                .filter(|(k, _)| *k <= n)
+                .map(|(k, b)| b*input[n - k])
+                .sum::<f64>();
+            let feedback = self.den.iter().enumerate().skip(1)
+                .filter(|(k, _)| *k <= n)
+                .map(|(k, a)| a*output[n - k])
+                .sum::<f64>();
+            output.push(forward - feedback);
+        }
+        output
+    }
+}
+
+/// Evaluate the polynomial with coefficients `coeffs`, constant first, at `x`.
+fn polyval(coeffs: &[f64], x: Phasor) -> Phasor {
+    coeffs.iter().rev().fold(Phasor::from(0.0), |sum, c| sum*x + Phasor::from(*c))
+}
+
+/// Roots of the polynomial with coefficients `coeffs`, constant first, by
+/// Durand-Kerner iteration from a circle as wide as Fujiwara's bound on the
+/// roots. Roots at the origin are split off first, one per zero low-order
+/// coefficient, since they would collapse the bound to zero.
+fn polyroots(coeffs: &[f64]) -> Vec<Phasor> {
+    let order = match coeffs.iter().rposition(|c| *c != 0.0) {
+        Some(order) if order > 0 => order,
+        _ => return Vec::new(),
+    };
+    let at_origin = coeffs.iter().take_while(|c| **c == 0.0).count();
+    let mut origin_roots = vec![Phasor::from(0.0); at_origin];
+    let coeffs = &coeffs[at_origin..=order];
+    let order = order - at_origin;
+    if order == 0 {return origin_roots;}
+
+    let monic = coeffs.iter().map(|c| c/coeffs[order]).collect::<Vec<f64>>();
+    let radius = 2.0*(1..=order)
+        .map(|k| monic[order - k].abs().powf(1.0/k as f64))
+        .fold(0.0, f64::max);
+    // Start off the real axis so conjugate pairs can separate
+    let mut roots = (0..order)
+        .map(|k| Phasor::from_polar(radius, 360.0*k as f64/order as f64 + 20.0))
+        .collect::<Vec<Phasor>>();
+    for _ in 0..ROOT_ITERATIONS {
+        let mut largest_step = 0.0f64;
+        for i in 0..order {
+            let spread = (0..order)
+                .filter(|j| *j != i)
+                .fold(Phasor::from(1.0), |product, j| product*(roots[i] - roots[j]));
+            let step = polyval(&monic, roots[i])/spread;
+            roots[i] = roots[i] - step;
+            largest_step = largest_step.max(step.abs()/radius);
+        }
+        if largest_step < f64::EPSILON {break;}
+    }
+    origin_roots.extend(roots);
+    origin_roots
+}
+
+/// Multiply two polynomials given constant first.
+fn polymul(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x*y;
+        }
+    }
+    product
+}
+
+/// Substitute s = k·(1 - z⁻¹)/(1 + z⁻¹) and clear the denominators of the
+/// substitution, giving coefficients in z⁻¹ with den[0] = 1.
+fn bilinear(rational: &Rational, k: f64) -> Result<(Vec<f64>, Vec<f64>), DiscreteError> {
+    let order = rational.num.len().max(rational.den.len()) - 1;
+    let substitute = |coeffs: &[f64]| {
+        let mut result = vec![0.0; order + 1];
+        for (power, c) in coeffs.iter().enumerate() {
+            let term = (0..power).fold(vec![c*k.powi(power as i32)], |p, _| polymul(&p, &[1.0, -1.0]));
+            let term = (power..order).fold(term, |p, _| polymul(&p, &[1.0, 1.0]));
+            for (r, t) in result.iter_mut().zip(term.iter()) {
+                *r += t;
+            }
+        }
+        result
+    };
+    let (num, den) = (substitute(&rational.num), substitute(&rational.den));
+    let lead = den[0];
+    if lead == 0.0 || !lead.is_finite() {
+        return Err(DiscreteError::Singular);
+    }
+    Ok((num.iter().map(|c| c/lead).collect(), den.iter().map(|c| c/lead).collect()))
+}
+
+/// Zero-order hold equivalent with sample period `period`, through a
+/// controllable canonical state-space model: Φ = e^(A·T), Γ = ∫e^(A·τ)dτ·B,
+/// and H(z) = C·(zI - Φ)⁻¹·Γ + D expanded with Faddeev-LeVerrier.
+fn zero_order_hold(rational: &Rational, period: f64) -> Result<(Vec<f64>, Vec<f64>), DiscreteError> {
+    let den_order = rational.den.len() - 1;
+    if !rational.is_proper() {
+        return Err(DiscreteError::Improper {num_order: rational.num.len() - 1, den_order});
+    }
+    // Time in sample periods, s = p/T, with a monic denominator in p
+    let lead = rational.den[den_order]*period.powi(-(den_order as i32));
+    let in_p = |coeffs: &[f64]| (0..=den_order)
+        .map(|k| coeffs.get(k).cloned().unwrap_or(0.0)*period.powi(-(k as i32))/lead)
+        .collect::<Vec<f64>>();
+    let (num, den) = (in_p(&rational.num), in_p(&rational.den));
+    let feedthrough = num[den_order];
+    if den_order == 0 {
+        return Ok((vec![feedthrough], vec![1.0]));
+    }
+    let c = (0..den_order).map(|k| num[k] - feedthrough*den[k]).collect::<Vec<f64>>();
+
+    // [[A, B], [0, 0]] over one period holds Φ and Γ in its exponential
+    let size = den_order + 1;
+    let mut augmented = vec![vec![0.0; size]; size];
+    for i in 0..den_order - 1 {
+        augmented[i][i + 1] = 1.0;
+    }
+    for j in 0..den_order {
+        augmented[den_order - 1][j] = -den[j];
+    }
+    augmented[den_order - 1][den_order] = 1.0;
+    let exponential = expm(&augmented);
+    let phi = exponential[..den_order].iter().map(|row| row[..den_order].to_vec()).collect::<Vec<Vec<f64>>>();
+    let gamma = exponential[..den_order].iter().map(|row| row[den_order]).collect::<Vec<f64>>();
+
+    // Faddeev-LeVerrier: det(zI - Φ) = Σ coeffs[k]·z^k and
+    // adj(zI - Φ) = Σ M_k·z^(n - k)
+    let n = den_order;
+    let mut char_poly = vec![0.0; n + 1];
+    char_poly[n] = 1.0;
+    let mut m = vec![vec![0.0; n]; n];
+    let mut num_z = vec![0.0; n + 1];
+    num_z[0] = feedthrough;
+    for k in 1..=n {
+        let mut next = matmul(&phi, &m);
+        for (i, row) in next.iter_mut().enumerate() {
+            row[i] += char_poly[n - k + 1];
+        }
+        m = next;
+        let product = matmul(&phi, &m);
+        let trace = (0..n).map(|i| product[i][i]).sum::<f64>();
+        char_poly[n - k] = -trace/k as f64;
+        // C·M_k·Γ is the coefficient of z^(n - k), i.e. of z^-k
+        let m_gamma = m.iter().map(|row| row.iter().zip(gamma.iter()).map(|(a, b)| a*b).sum::<f64>()).collect::<Vec<f64>>();
+        num_z[k] = c.iter().zip(m_gamma.iter()).map(|(a, b)| a*b).sum::<f64>() + feedthrough*char_poly[n - k];
+    }
+    let den_z = (0..=n).map(|k| char_poly[n - k]).collect::<Vec<f64>>();
+    if num_z.iter().chain(den_z.iter()).any(|c| !c.is_finite()) {
+        return Err(DiscreteError::Singular);
+    }
+    Ok((num_z, den_z))
+}
+
+fn matmul(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    a.iter()
+        .map(|row| (0..b[0].len()).map(|j| row.iter().zip(b.iter()).map(|(x, b_row)| x*b_row[j]).sum()).collect())
+        .collect()
+}
+
+/// Matrix exponential by scaling and squaring a Taylor series.
+fn expm(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let size = a.len();
+    let norm = a.iter().map(|row| row.iter().map(|x| x.abs()).sum::<f64>()).fold(0.0, f64::max);
+    let squarings = if norm > 0.5 {(norm/0.5).log2().ceil() as i32} else {0};
+    let scaled = a.iter().map(|row| row.iter().map(|x| x/2f64.powi(squarings)).collect()).collect::<Vec<Vec<f64>>>();
+    let identity = (0..size).map(|i| (0..size).map(|j| if i == j {1.0} else {0.0}).collect()).collect::<Vec<Vec<f64>>>();
+    let mut result = identity.clone();
+    let mut term = identity;
+    for k in 1..=20 {
+        term = matmul(&term, &scaled).into_iter().map(|row| row.into_iter().map(|x| x/k as f64).collect()).collect();
+        for (r, t) in result.iter_mut().zip(term.iter()) {
+            for (x, y) in r.iter_mut().zip(t.iter()) {
+                *x += y;
+            }
+        }
+    }
+    for _ in 0..squarings {
+        result = matmul(&result, &result);
+    }
+    result
+}
+
+/// Fit polynomials of the given orders in normalized s to `samples` of
+/// (normalized ω, response), by Sanathanan-Koerner iteration: linear least
+/// squares on N - H·D, reweighted by the previous denominator and the
+/// response so the error is relative. Returns the fit if it matches every
+/// sample to within `FIT_TOLERANCE`.
+fn fit_orders(samples: &[(f64, Phasor)], num_order: usize, den_order: usize) -> Option<Rational> {
+    let mut weights = vec![1.0; samples.len()];
+    let mut fit = None;
+    for _ in 0..FIT_ITERATIONS {
+        let mut rows = vec![];
+        let mut rhs = vec![];
+        for ((x, h), weight) in samples.iter().zip(weights.iter()) {
+            let s = Phasor::new(0.0, *x);
+            let powers = (0..=num_order.max(den_order))
+                .scan(Phasor::from(1.0), |power, _| {
+                    let current = *power;
+                    *power = *power*s;
+                    Some(current)
+                })
+                .collect::<Vec<Phasor>>();
+            let w = weight/h.abs();
+            let row = powers[..=num_order].iter().cloned()
+                .chain(powers[..den_order].iter().map(|p| -(*h**p)))
+                .map(|p| p.scale(w))
+                .collect::<Vec<Phasor>>();
+            let target = (*h*powers[den_order]).scale(w);
+            rows.push(row.iter().map(|p| p.re).collect::<Vec<f64>>());
+            rhs.push(target.re);
+            rows.push(row.iter().map(|p| p.im).collect::<Vec<f64>>());
+            rhs.push(target.im);
+        }
+        let solution = least_squares(rows, rhs)?;
+        let num = solution[..=num_order].to_vec();
+        let den = solution[num_order + 1..].iter().cloned().chain(std::iter::once(1.0)).collect::<Vec<f64>>();
+        for ((x, _), weight) in samples.iter().zip(weights.iter_mut()) {
+            *weight = 1.0/polyval(&den, Phasor::new(0.0, *x)).abs();
+        }
+        fit = Some(Rational {num, den});
+    }
+
+    let fit = fit?;
+    let matches = samples.iter().all(|(x, h)| {
+        let s = Phasor::new(0.0, *x);
+        let value = polyval(&fit.num, s)/polyval(&fit.den, s);
+        ((value - *h).abs()/h.abs()) < FIT_TOLERANCE
+    });
+    matches.then_some(fit)
+}
+
+/// Least squares solution of `rows`·x = `rhs` by Householder QR, with the
+/// columns scaled to unit length first. Returns `None` if the columns are
+/// dependent.
+fn least_squares(mut rows: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let cols = rows.first().map_or(0, Vec::len);
+    if rows.len() < cols {
+        return None;
+    }
+    let scales = (0..cols).map(|j| rows.iter().map(|row| row[j]*row[j]).sum::<f64>().sqrt()).collect::<Vec<f64>>();
+    if scales.iter().any(|s| *s == 0.0 || !s.is_finite()) {
+        return None;
+    }
+    for row in rows.iter_mut() {
+        for (x, scale) in row.iter_mut().zip(scales.iter()) {
+            *x /= scale;
+        }
+    }
+
+    for j in 0..cols {
+        let norm = rows[j..].iter().map(|row| row[j]*row[j]).sum::<f64>().sqrt();
+        if norm < 1e-12 {
+            return None;
+        }
+        let alpha = if rows[j][j] > 0.0 {-norm} else {norm};
+        let mut v = rows[j..].iter().map(|row| row[j]).collect::<Vec<f64>>();
+        v[0] -= alpha;
+        let v_norm = v.iter().map(|x| x*x).sum::<f64>();
+        if v_norm == 0.0 {continue;}
+        for k in j..cols {
+            let dot = v.iter().zip(rows[j..].iter()).map(|(a, row)| a*row[k]).sum::<f64>();
+            for (a, row) in v.iter().zip(rows[j..].iter_mut()) {
+                row[k] -= 2.0*dot/v_norm*a;
+            }
+        }
+        let dot = v.iter().zip(rhs[j..].iter()).map(|(a, b)| a*b).sum::<f64>();
+        for (a, b) in v.iter().zip(rhs[j..].iter_mut()) {
+            *b -= 2.0*dot/v_norm*a;
+        }
+    }
+
+    let mut solution = vec![0.0; cols];
+    for j in (0..cols).rev() {
+        let known = (j + 1..cols).map(|k| rows[j][k]*solution[k]).sum::<f64>();
+        solution[j] = (rhs[j] - known)/rows[j][j];
+    }
+    Some(solution.iter().zip(scales.iter()).map(|(x, scale)| x/scale).collect())
+}
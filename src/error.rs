@@ -0,0 +1,79 @@
+use std::fmt;
+
+/// Problems with a plot's inputs, found before anything is drawn so that a
+/// bad input in a batch render job fails that plot instead of aborting.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlotError {
+    /// The frequency range is empty or not positive. Logarithmic axes need
+    /// `0 < fmin < fmax`.
+    InvalidRange {fmin: f64, fmax: f64},
+    /// A time span that is not positive.
+    InvalidDuration(f64),
+    /// Nothing to plot, e.g. an empty list of models.
+    Empty,
+    /// The data has no finite values to scale the axes from.
+    NonFinite,
+    /// A model's response is NaN or infinite at a sampled frequency.
+    NonFiniteSample {model: String, freq: f64},
+    /// An axis break that does not cut out a positive span of magnitudes.
+    InvalidBreak {low: f64, high: f64},
+    /// Font data that could not be parsed.
+    InvalidFont,
+}
+
+impl fmt::Display for PlotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlotError::InvalidRange {fmin, fmax} => write!(f, "invalid frequency range {fmin} Hz to {fmax} Hz"),
+            PlotError::InvalidDuration(duration) => write!(f, "invalid duration {duration} s"),
+            PlotError::Empty => write!(f, "nothing to plot"),
+            PlotError::NonFinite => write!(f, "data has no finite values"),
+            PlotError::NonFiniteSample {model, freq} => write!(f, "response of {model} is not finite at {freq} Hz"),
+            PlotError::InvalidBreak {low, high} => write!(f, "invalid axis break from {low} to {high}"),
+            PlotError::InvalidFont => write!(f, "invalid font data"),
+        }
+    }
+}
+
+impl std::error::Error for PlotError {}
+
+/// Check that `fmin..fmax` can be drawn on a logarithmic axis.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::error::{self, PlotError};
+///
+/// assert!(error::check_range(1.0, 10e6).is_ok());
+/// assert_eq!(error::check_range(1e6, 1e3), Err(PlotError::InvalidRange {fmin: 1e6, fmax: 1e3}));
+/// assert!(error::check_range(0.0, 1e3).is_err());
+/// ```
+pub fn check_range(fmin: f64, fmax: f64) -> Result<(), PlotError> {
+    if fmin.is_finite() && fmax.is_finite() && fmin > 0.0 && fmin < fmax {
+        Ok(())
+    } else {
+        Err(PlotError::InvalidRange {fmin, fmax})
+    }
+}
+
+/// Check that an axis break from `low` to `high` can be cut out of a
+/// logarithmic magnitude axis.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::error::{self, PlotError};
+///
+/// assert!(error::check_break(0.1, 100.0).is_ok());
+/// assert_eq!(error::check_break(100.0, 0.1), Err(PlotError::InvalidBreak {low: 100.0, high: 0.1}));
+/// ```
+pub fn check_break(low: f64, high: f64) -> Result<(), PlotError> {
+    if low.is_finite() && high.is_finite() && low > 0.0 && low < high {
+        Ok(())
+    } else {
+        Err(PlotError::InvalidBreak {low, high})
+    }
+}
+
+/// Check that axis limits found from data are finite.
+pub fn check_limits(min: f64, max: f64) -> Result<(), PlotError> {
+    if min.is_finite() && max.is_finite() {Ok(())} else {Err(PlotError::NonFinite)}
+}
@@ -0,0 +1,569 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::{self, AnalysisReport};
+use crate::axis::{FreqScale, MagScale};
+use crate::config::PlotConfig;
+use crate::error::{self, PlotError};
+#[cfg(feature = "bitmap")]
+use crate::pdn_impedance_plotter::pdn_plotter;
+use crate::plottable::Plottable;
+#[cfg(feature = "bitmap")]
+use crate::render;
+use crate::sweep::Sweep;
+use crate::two_port::TwoPort;
+use crate::units::{format_freq, format_ohms};
+
+/// Format a number the way LTspice writes it, e.g. `1.00000000000000e+003`.
+fn ltspice_number(value: f64) -> String {
+    let text = format!("{value:.14e}");
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => {
+            let exponent = exponent.parse::<i32>().unwrap_or(0);
+            let sign = if exponent < 0 {'-'} else {'+'};
+            format!("{mantissa}e{sign}{:03}", exponent.abs())
+        },
+        None => text,
+    }
+}
+
+/// Write a sweep in the text format LTspice uses when exporting AC analysis
+/// data in polar form: a tab-separated header naming the trace, then one
+/// line per frequency with the magnitude in dB and the phase in degrees.
+/// LTspice's waveform viewer can open the file, so analytic models can be
+/// overlaid on simulations.
+///
+/// # Arguments
+/// * `sweep` - The sampled response.
+/// * `trace` - Name of the trace, e.g. `V(out)` or `Z(pdn)`.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::export;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// let sweep = Sweep {freqs: vec![1e3], mags: vec![0.1], phases: vec![-45.0]};
+/// assert_eq!(
+///     export::to_ltspice(&sweep, "V(out)"),
+///     "Freq.\tV(out)\n1.00000000000000e+003\t(-2.00000000000000e+001dB,-4.50000000000000e+001°)\n"
+/// );
+/// ```
+pub fn to_ltspice(sweep: &Sweep, trace: &str) -> String {
+    let mut text = format!("Freq.\t{trace}\n");
+    for ((freq, mag), phase) in sweep.freqs.iter().zip(sweep.mags.iter()).zip(sweep.phases.iter()) {
+        text.push_str(&format!(
+            "{}\t({}dB,{}°)\n",
+            ltspice_number(*freq),
+            ltspice_number(20.0*mag.log10()),
+            ltspice_number(*phase)
+        ));
+    }
+    text
+}
+
+/// Write `to_ltspice` output to a file at `path`.
+/// Returns a result to indicate if the file was written.
+pub fn write_ltspice(sweep: &Sweep, trace: &str, path: &str) -> io::Result<()> {
+    fs::write(path, to_ltspice(sweep, trace))
+}
+
+/// Write a sweep as an LTspice subcircuit whose output voltage is the input
+/// voltage shaped by the sampled response, using a frequency-table
+/// behavioral source. Pulling the subcircuit in with `.include` puts an
+/// analytic model into an AC simulation, e.g. a regulator's output
+/// impedance driven by a current probe.
+///
+/// # Arguments
+/// * `sweep` - The sampled response.
+/// * `name` - Name of the subcircuit. Its pins are `in` and `out`.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::export;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// let sweep = Sweep {freqs: vec![1e3, 1e6], mags: vec![1.0, 0.01], phases: vec![0.0, -90.0]};
+/// let subckt = export::to_ltspice_subckt(&sweep, "LPF");
+/// assert!(subckt.starts_with(".subckt LPF in out\n"));
+/// assert!(subckt.contains("+ (1.00000000000000e+006,-4.00000000000000e+001,-9.00000000000000e+001)\n"));
+/// assert!(subckt.ends_with(".ends LPF\n"));
+/// ```
+pub fn to_ltspice_subckt(sweep: &Sweep, name: &str) -> String {
+    let mut text = format!(".subckt {name} in out\nE1 out 0 FREQ {{V(in)}} =\n");
+    for ((freq, mag), phase) in sweep.freqs.iter().zip(sweep.mags.iter()).zip(sweep.phases.iter()) {
+        text.push_str(&format!(
+            "+ ({},{},{})\n",
+            ltspice_number(*freq),
+            ltspice_number(20.0*mag.log10()),
+            ltspice_number(*phase)
+        ));
+    }
+    text.push_str(&format!(".ends {name}\n"));
+    text
+}
+
+/// Option line of the Touchstone files written here. Touchstone normalizes Z
+/// parameters to the reference resistance, so a reference of 1 Ω leaves the
+/// values in ohms.
+const TOUCHSTONE_Z_OPTIONS: &str = "# Hz Z MA R 1";
+
+/// Write an impedance sweep as a one-port Touchstone file of Z parameters
+/// (.z1p), with magnitudes in Ω and phases in degrees. Power integrity tools
+/// that take Z parameters can read this without converting from S
+/// parameters against a reference impedance.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::export;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// let sweep = Sweep {freqs: vec![1e6], mags: vec![0.012], phases: vec![45.0]};
+/// let text = export::to_z1p(&sweep);
+/// assert_eq!(text.lines().nth(1), Some("# Hz Z MA R 1"));
+/// assert_eq!(text.lines().nth(2), Some("1.000000e6 1.200000e-2 4.500000e1"));
+/// ```
+pub fn to_z1p(sweep: &Sweep) -> String {
+    let mut text = format!("! Impedance in ohms\n{TOUCHSTONE_Z_OPTIONS}\n");
+    for ((freq, mag), phase) in sweep.freqs.iter().zip(sweep.mags.iter()).zip(sweep.phases.iter()) {
+        text.push_str(&format!("{freq:.6e} {mag:.6e} {phase:.6e}\n"));
+    }
+    text
+}
+
+/// Write `to_z1p` output to a file at `path`.
+/// Returns a result to indicate if the file was written.
+pub fn write_z1p(sweep: &Sweep, path: &str) -> io::Result<()> {
+    fs::write(path, to_z1p(sweep))
+}
+
+/// Write a two-port as a Touchstone file of Z parameters (.z2p) sampled at
+/// `freqs` in Hz, in the Touchstone order Z11, Z21, Z12, Z22, with
+/// magnitudes in Ω and phases in degrees.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::export;
+/// use sdomain_test_plotters::two_port::TwoPort;
+///
+/// use sdomain_test::sdomain;
+///
+/// let model = TwoPort {
+///     z11: sdomain::gen::resistor(60.0),
+///     z12: sdomain::gen::resistor(10.0),
+///     z21: sdomain::gen::resistor(10.0),
+///     z22: sdomain::gen::resistor(60.0),
+/// };
+/// let text = export::to_z2p(&model, &[1e3]);
+/// let values = text.lines().last().unwrap().split_whitespace().collect::<Vec<&str>>();
+/// assert_eq!(values.len(), 9);
+/// assert_eq!(values[1], "6.000000e1");
+/// assert_eq!(values[3], "1.000000e1");
+/// ```
+pub fn to_z2p(model: &TwoPort, freqs: &[f64]) -> String {
+    let mut text = format!("! Impedance in ohms\n{TOUCHSTONE_Z_OPTIONS}\n");
+    for (freq, z) in freqs.iter().zip(model.z_params(freqs)) {
+        text.push_str(&format!("{freq:.6e}"));
+        for param in [z.z11, z.z21, z.z12, z.z22] {
+            text.push_str(&format!(" {:.6e} {:.6e}", param.abs(), param.arg_deg()));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Write `to_z2p` output to a file at `path`.
+/// Returns a result to indicate if the file was written.
+pub fn write_z2p(model: &TwoPort, freqs: &[f64], path: &str) -> io::Result<()> {
+    fs::write(path, to_z2p(model, freqs))
+}
+
+/// Write a sweep as comma-separated values with a header row: frequency in
+/// Hz, linear magnitude, and phase in degrees.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::export;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// let sweep = Sweep {freqs: vec![1e3], mags: vec![0.5], phases: vec![-45.0]};
+/// assert_eq!(export::to_csv(&sweep), "freq_hz,mag,phase_deg\n1000,0.5,-45\n");
+/// ```
+pub fn to_csv(sweep: &Sweep) -> String {
+    let mut text = "freq_hz,mag,phase_deg\n".to_string();
+    for ((freq, mag), phase) in sweep.freqs.iter().zip(sweep.mags.iter()).zip(sweep.phases.iter()) {
+        text.push_str(&format!("{freq},{mag},{phase}\n"));
+    }
+    text
+}
+
+/// Markers found by an analysis as text, one per line, with slopes in the
+/// configured unit.
+fn markers(report: &AnalysisReport, config: &PlotConfig) -> String {
+    let mut text = format!(
+        "min: {} at {}\nmax: {} at {}\n",
+        format_ohms(report.min.1), format_freq(report.min.0),
+        format_ohms(report.max.1), format_freq(report.max.0)
+    );
+    for peak in report.anti_resonances.iter() {
+        text.push_str(&format!(
+            "anti-resonance: {} at {}, Q = {:.2}, damping ESR = {}\n",
+            format_ohms(peak.peak), format_freq(peak.freq), peak.q, format_ohms(peak.damping_esr)
+        ));
+    }
+    for corner in report.corners.iter() {
+        text.push_str(&format!(
+            "corner: {} from {} to {}\n",
+            format_freq(corner.freq), config.slope_unit.format(corner.slope_before), config.slope_unit.format(corner.slope_after)
+        ));
+    }
+    match &report.compliance {
+        Some(Ok(margin)) => text.push_str(&format!("target: met, least margin {margin}\n")),
+        Some(Err(violation)) => text.push_str(&format!("target: failed, {violation}\n")),
+        None => {},
+    }
+    text
+}
+
+/// Write everything needed to review or reproduce an analysis into one
+/// archive at `path`, an uncompressed tar file that any archiver opens, so
+/// a single file documents the run:
+/// * `manifest.txt` - The model name, crate version, and the files in the
+///                    archive.
+/// * `config.toml` - The plot configuration, from `PlotConfig::to_toml`.
+/// * `model.lib` - The sampled model as an LTspice subcircuit, from
+///                 `to_ltspice_subckt`, to rerun the model in a simulator.
+/// * `sweep.csv` - The sampled response, from `to_csv`.
+/// * `markers.txt` - Extremes, anti-resonances, corners, and the target check.
+/// * `impedance.png` - The impedance plot, with the `bitmap` feature.
+///
+/// Returns the names of the files in the archive, or an error if the
+/// analysis, the plot, or writing the archive failed.
+///
+/// # Arguments
+/// * `model` - A power distribution network model, or any model to analyze.
+/// * `config` - Options for the analysis and the plot.
+/// * `path` - Archive to write, e.g. `bundle.tar`.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::config::PlotConfig;
+/// use sdomain_test_plotters::export;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+/// use sdomain_test::pdn::PDNModel;
+/// use sdomain_test::sdomain;
+///
+/// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+/// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+///
+/// let config = PlotConfig {target: Some(0.1), ..PlotConfig::default()};
+/// let files = export::write_bundle(&pdn, &config, "images/bundle.tar").unwrap();
+/// assert!(files.iter().any(|name| name == "config.toml"));
+/// assert!(files.iter().any(|name| name == "model.lib"));
+///
+/// // The first entry is the manifest, named in a standard tar header
+/// let archive = std::fs::read("images/bundle.tar").unwrap();
+/// assert_eq!(&archive[..12], b"manifest.txt");
+/// assert_eq!(&archive[257..262], b"ustar");
+/// assert_eq!(archive.len() % 512, 0);
+/// ```
+pub fn write_bundle<P: Plottable + ?Sized>(model: &P, config: &PlotConfig, path: impl AsRef<Path>) -> Result<Vec<String>, Box <dyn std::error::Error>> {
+    let path = path.as_ref();
+    let report = analysis::analyze(model, config)?;
+
+    let mut files = vec![
+        ("config.toml".to_string(), config.to_toml().into_bytes()),
+        ("model.lib".to_string(), to_ltspice_subckt(&report.sweep, &subckt_name(&report.name)).into_bytes()),
+        ("sweep.csv".to_string(), to_csv(&report.sweep).into_bytes()),
+        ("markers.txt".to_string(), markers(&report, config).into_bytes()),
+    ];
+
+    #[cfg(feature = "bitmap")]
+    {
+        // The bitmap backend only writes files, so render next to the archive
+        // and move the image into it
+        let image = path.with_extension("png.part");
+        render::render_to_png(&image, (960, 720), |area| pdn_plotter::plot_with_config(model, area, config))?;
+        let png = fs::read(&image);
+        fs::remove_file(&image)?;
+        files.push(("impedance.png".to_string(), png?));
+    }
+
+    let names = files.iter().map(|(name, _)| name.clone()).collect::<Vec<String>>();
+    let manifest = format!(
+        "model: {}\ncrate: {} {}\nfiles:\n{}",
+        report.name, env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"),
+        names.iter().map(|name| format!("  {name}\n")).collect::<String>()
+    );
+    files.insert(0, ("manifest.txt".to_string(), manifest.into_bytes()));
+
+    let mut archive = Vec::new();
+    for (name, contents) in files.iter() {
+        append_tar_entry(&mut archive, name, contents);
+    }
+    // Two zero blocks end the archive
+    archive.resize(archive.len() + 1024, 0);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, archive)?;
+    Ok(files.into_iter().map(|(name, _)| name).collect())
+}
+
+/// `name` with everything but letters, digits, and underscores replaced, as
+/// SPICE subcircuit names allow.
+fn subckt_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() {c} else {'_'}).collect()
+}
+
+/// Append a regular file named `name` holding `contents` to a tar archive:
+/// a 512 byte ustar header, then the contents padded to a whole block.
+/// Names must fit the 100 byte name field.
+fn append_tar_entry(archive: &mut Vec<u8>, name: &str, contents: &[u8]) {
+    let mut header = [0u8; 512];
+    let mut field = |offset: usize, value: &[u8]| header[offset..offset + value.len()].copy_from_slice(value);
+    field(0, name.as_bytes());
+    field(100, b"0000644\0");
+    field(108, b"0000000\0");
+    field(116, b"0000000\0");
+    field(124, format!("{:011o}\0", contents.len()).as_bytes());
+    field(136, b"00000000000\0");
+    field(156, b"0");
+    field(257, b"ustar\0");
+    field(263, b"00");
+    // The checksum is taken with its own field filled with spaces
+    field(148, b"        ");
+    let checksum = header.iter().map(|byte| *byte as u32).sum::<u32>();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    archive.extend_from_slice(&header);
+    archive.extend_from_slice(contents);
+    archive.resize(archive.len().div_ceil(512)*512, 0);
+}
+
+/// What the script exporters need to redraw an impedance chart: the labels
+/// and scales from a `PlotConfig` and the sampled data in axis units.
+struct ChartData {
+    title: Option<String>,
+    x_label: String,
+    y_label: String,
+    phase_label: String,
+    log_x: bool,
+    mag_scale: MagScale,
+    target: Option<f64>,
+    phase_range: Option<(f64, f64)>,
+    /// Frequencies in the configured unit.
+    freqs: Vec<f64>,
+    /// Magnitudes in linear units.
+    mags: Vec<f64>,
+    /// Phases in degrees, unwrapped if the phase range calls for it.
+    phases: Vec<f64>,
+}
+
+impl ChartData {
+    /// Sample `model` and collect the labels the impedance plotter would use.
+    fn new<P: Plottable + ?Sized>(model: &P, config: &PlotConfig) -> Result<ChartData, PlotError> {
+        error::check_range(config.fmin, config.fmax)?;
+        let name = model.display_name();
+        let sweep = config.check_finite(Sweep::at(model, config.sample_freqs()), &name)?;
+        Ok(ChartData {
+            title: config.caption_or(&format!("Impedance of {name}")),
+            x_label: config.x_label(),
+            y_label: config.y_label_or(&config.mag_scale.label("Impedance", "Ω")),
+            phase_label: config.phase_label_or("Phase [°]"),
+            log_x: config.freq_scale == FreqScale::Log,
+            mag_scale: config.mag_scale,
+            target: config.target,
+            phase_range: if config.show_phase {Some(config.phase_range)} else {None},
+            freqs: sweep.freqs.iter().map(|f| config.freq_unit.from_hz(*f)).collect(),
+            phases: config.phases(&sweep),
+            mags: sweep.mags,
+        })
+    }
+
+    /// The data as comma-separated values with a header row.
+    fn to_csv(&self) -> String {
+        let mut text = "freq,mag,phase_deg\n".to_string();
+        for ((freq, mag), phase) in self.freqs.iter().zip(self.mags.iter()).zip(self.phases.iter()) {
+            text.push_str(&format!("{freq},{mag},{phase}\n"));
+        }
+        text
+    }
+}
+
+/// Quote `text` as a Python or gnuplot string literal.
+fn quoted(text: &str) -> String {
+    format!("{text:?}")
+}
+
+/// A chart exported for another plotting tool: a script and the data file it
+/// reads.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptExport {
+    /// Contents of the script.
+    pub script: String,
+    /// Contents of the data file, or empty if the script holds its data.
+    pub data: String,
+}
+
+/// Export the impedance chart of `model` as a Python script using
+/// matplotlib and numpy, and the CSV file it reads, `data_file`. The script
+/// reproduces the scales, labels, caption, target, and phase of the chart
+/// drawn by `pdn_plotter::plot_with_config`, ready to be restyled for
+/// publication. Running it saves a PNG next to the data and shows the chart.
+/// Returns the script and data, or an error if the model could not be swept.
+///
+/// # Arguments
+/// * `model` - A power distribution network model, or any model to plot.
+/// * `config` - Options for the chart.
+/// * `data_file` - Name of the CSV file the script reads, relative to where
+///                 it runs.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::config::PlotConfig;
+/// use sdomain_test_plotters::export;
+///
+/// use sdomain_test::sdomain;
+///
+/// let config = PlotConfig {target: Some(0.1), ..PlotConfig::default()};
+/// let export = export::to_matplotlib(&sdomain::gen::rl(52e-3, 1.5e-6), &config, "pdn.csv").unwrap();
+/// assert!(export.script.contains("np.loadtxt(\"pdn.csv\""));
+/// assert!(export.script.contains("ax.set_yscale(\"log\")"));
+/// assert!(export.data.starts_with("freq,mag,phase_deg\n"));
+/// ```
+pub fn to_matplotlib<P: Plottable + ?Sized>(model: &P, config: &PlotConfig, data_file: &str) -> Result<ScriptExport, PlotError> {
+    let chart = ChartData::new(model, config)?;
+    let mut script = format!(
+        "#!/usr/bin/env python3\n# Exported by {} {}\nimport matplotlib.pyplot as plt\nimport numpy as np\n\n",
+        env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")
+    );
+    script.push_str(&format!("freq, mag, phase = np.loadtxt({}, delimiter=\",\", skiprows=1, unpack=True)\n", quoted(data_file)));
+    match chart.mag_scale {
+        MagScale::Log | MagScale::Linear => {},
+        MagScale::Db => script.push_str("mag = 20*np.log10(mag)\n"),
+        MagScale::DbMicrovolt => script.push_str("mag = 20*np.log10(mag/1e-6)\n"),
+        MagScale::Dbm(reference) => script.push_str(&format!("mag = 10*np.log10(mag**2/{reference}/1e-3)\n")),
+    }
+    let to_axis = |m: f64| if chart.mag_scale.is_db() {chart.mag_scale.to_axis(m)} else {m};
+    script.push_str("\nfig, ax = plt.subplots(figsize=(9.6, 7.2))\n");
+    script.push_str("ax.plot(freq, mag, color=\"green\", label=\"Impedance\")\n");
+    if let Some(target) = chart.target {
+        script.push_str(&format!("ax.axhline({}, color=\"purple\", label=\"Target\")\n", to_axis(target)));
+    }
+    if chart.log_x {
+        script.push_str("ax.set_xscale(\"log\")\n");
+    }
+    if chart.mag_scale == MagScale::Log {
+        script.push_str("ax.set_yscale(\"log\")\n");
+    }
+    script.push_str(&format!("ax.set_xlabel({})\nax.set_ylabel({})\n", quoted(&chart.x_label), quoted(&chart.y_label)));
+    if let Some(title) = &chart.title {
+        script.push_str(&format!("ax.set_title({})\n", quoted(title)));
+    }
+    script.push_str("ax.grid(True, which=\"both\", alpha=0.3)\n");
+    script.push_str("handles, labels = ax.get_legend_handles_labels()\n");
+    if let Some((low, high)) = chart.phase_range {
+        script.push_str("\nphase_ax = ax.twinx()\n");
+        script.push_str("phase_ax.plot(freq, phase, color=\"red\", alpha=0.4, label=\"Phase\")\n");
+        script.push_str(&format!("phase_ax.set_ylim({low}, {high})\nphase_ax.set_ylabel({})\n", quoted(&chart.phase_label)));
+        script.push_str("more_handles, more_labels = phase_ax.get_legend_handles_labels()\n");
+        script.push_str("handles, labels = handles + more_handles, labels + more_labels\n");
+    }
+    script.push_str("ax.legend(handles, labels, loc=\"lower right\")\n\n");
+    let image = Path::new(data_file).with_extension("png");
+    script.push_str(&format!("fig.tight_layout()\nfig.savefig({})\nplt.show()\n", quoted(&image.to_string_lossy())));
+
+    Ok(ScriptExport {script, data: chart.to_csv()})
+}
+
+/// Write `to_matplotlib` output into the directory `dir` as `<stem>.py` and
+/// `<stem>.csv`.
+/// Returns the paths written, or an error if the model could not be swept or
+/// a file could not be written.
+pub fn write_matplotlib<P: Plottable + ?Sized>(model: &P, config: &PlotConfig, dir: impl AsRef<Path>, stem: &str) -> Result<Vec<PathBuf>, Box <dyn std::error::Error>> {
+    let dir = dir.as_ref();
+    let data_file = format!("{stem}.csv");
+    let export = to_matplotlib(model, config, &data_file)?;
+    let (script_path, data_path) = (dir.join(format!("{stem}.py")), dir.join(&data_file));
+    fs::write(&script_path, export.script)?;
+    fs::write(&data_path, export.data)?;
+    Ok(vec![script_path, data_path])
+}
+
+/// Export the impedance chart of `model` as a self-contained gnuplot script.
+/// The data is held in a `$data` block inside the script, and the scales,
+/// labels, caption, target, and phase match the chart drawn by
+/// `pdn_plotter::plot_with_config`. Running `gnuplot` on the script writes
+/// the chart to `image_file` as a PNG.
+/// Returns the script, with empty `data`, or an error if the model could not
+/// be swept.
+///
+/// # Arguments
+/// * `model` - A power distribution network model, or any model to plot.
+/// * `config` - Options for the chart.
+/// * `image_file` - PNG file the script writes.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::config::PlotConfig;
+/// use sdomain_test_plotters::export;
+///
+/// use sdomain_test::sdomain;
+///
+/// let export = export::to_gnuplot(&sdomain::gen::rl(52e-3, 1.5e-6), &PlotConfig::default(), "pdn.png").unwrap();
+/// assert!(export.script.contains("$data << EOD\n"));
+/// assert!(export.script.contains("set logscale xy\n"));
+/// assert!(export.data.is_empty());
+/// ```
+pub fn to_gnuplot<P: Plottable + ?Sized>(model: &P, config: &PlotConfig, image_file: &str) -> Result<ScriptExport, PlotError> {
+    let chart = ChartData::new(model, config)?;
+    let mut script = format!("# Exported by {} {}\n$data << EOD\n# freq mag phase_deg\n", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    for ((freq, mag), phase) in chart.freqs.iter().zip(chart.mags.iter()).zip(chart.phases.iter()) {
+        script.push_str(&format!("{freq} {mag} {phase}\n"));
+    }
+    script.push_str("EOD\n\n");
+    script.push_str(&format!("set terminal pngcairo size 960,720\nset output {}\n", quoted(image_file)));
+    if let Some(title) = &chart.title {
+        script.push_str(&format!("set title {}\n", quoted(title)));
+    }
+    script.push_str(&format!("set xlabel {}\nset ylabel {}\n", quoted(&chart.x_label), quoted(&chart.y_label)));
+    match (chart.log_x, chart.mag_scale == MagScale::Log) {
+        (true, true) => script.push_str("set logscale xy\n"),
+        (true, false) => script.push_str("set logscale x\n"),
+        (false, true) => script.push_str("set logscale y\n"),
+        (false, false) => {},
+    }
+    script.push_str("set grid\nset key bottom right box\n");
+
+    let mag_column = match chart.mag_scale {
+        MagScale::Log | MagScale::Linear => "2".to_string(),
+        MagScale::Db => "(20*log10($2))".to_string(),
+        MagScale::DbMicrovolt => "(20*log10($2/1e-6))".to_string(),
+        MagScale::Dbm(reference) => format!("(10*log10($2**2/{reference}/1e-3))"),
+    };
+    let to_axis = |m: f64| if chart.mag_scale.is_db() {chart.mag_scale.to_axis(m)} else {m};
+    let mut plots = vec![format!("$data using 1:{mag_column} with lines lc rgb \"green\" title \"Impedance\"")];
+    if let Some(target) = chart.target {
+        plots.push(format!("{} with lines lc rgb \"purple\" title \"Target\"", to_axis(target)));
+    }
+    if let Some((low, high)) = chart.phase_range {
+        script.push_str(&format!("set ytics nomirror\nset y2tics\nset y2range [{low}:{high}]\nset y2label {}\n", quoted(&chart.phase_label)));
+        plots.push("$data using 1:3 axes x1y2 with lines lc rgb \"#66FF0000\" title \"Phase\"".to_string());
+    }
+    script.push_str(&format!("plot {}\n", plots.join(", \\\n     ")));
+
+    Ok(ScriptExport {script, data: String::new()})
+}
+
+/// Write `to_gnuplot` output into the directory `dir` as `<stem>.gp`, set up
+/// to draw `<stem>.png`.
+/// Returns the path written, or an error if the model could not be swept or
+/// the file could not be written.
+pub fn write_gnuplot<P: Plottable + ?Sized>(model: &P, config: &PlotConfig, dir: impl AsRef<Path>, stem: &str) -> Result<PathBuf, Box <dyn std::error::Error>> {
+    let export = to_gnuplot(model, config, &format!("{stem}.png"))?;
+    let path = dir.as_ref().join(format!("{stem}.gp"));
+    fs::write(&path, export.script)?;
+    Ok(path)
+}
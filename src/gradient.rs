@@ -0,0 +1,72 @@
+use plotters::style::RGBColor;
+
+/// Stops of the viridis colormap from dark purple to yellow, evenly spaced.
+const VIRIDIS: [(u8, u8, u8); 9] = [
+    (68, 1, 84), (71, 44, 122), (59, 81, 139), (44, 113, 142), (33, 144, 141),
+    (39, 173, 129), (92, 200, 99), (170, 220, 50), (253, 231, 37),
+];
+
+/// Stops of the plasma colormap from dark blue to yellow, evenly spaced.
+const PLASMA: [(u8, u8, u8); 8] = [
+    (13, 8, 135), (84, 2, 163), (139, 10, 165), (185, 50, 137),
+    (219, 92, 104), (244, 136, 73), (254, 188, 43), (240, 249, 33),
+];
+
+/// Color gradient for a family of related curves, such as a sweep of
+/// capacitor counts or temperatures, so that neighboring members get
+/// neighboring colors. Viridis and plasma stay distinguishable in grayscale
+/// and for most color vision deficiencies.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Gradient {
+    /// Dark purple through green to yellow.
+    #[default]
+    Viridis,
+    /// Dark blue through magenta to yellow.
+    Plasma,
+    /// Evenly spaced stops given as (red, green, blue).
+    Custom(Vec<(u8, u8, u8)>),
+}
+
+impl Gradient {
+    /// Color at position `t` along the gradient, clamped to 0 to 1 and
+    /// interpolated linearly between stops.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::gradient::Gradient;
+    ///
+    /// use plotters::style::RGBColor;
+    ///
+    /// let gradient = Gradient::Custom(vec![(0, 0, 0), (200, 100, 0)]);
+    /// assert_eq!(gradient.at(0.5), RGBColor(100, 50, 0));
+    /// assert_eq!(gradient.at(2.0), RGBColor(200, 100, 0));
+    /// assert_eq!(Gradient::Viridis.at(0.0), RGBColor(68, 1, 84));
+    /// ```
+    pub fn at(&self, t: f64) -> RGBColor {
+        let stops: &[(u8, u8, u8)] = match self {
+            Gradient::Viridis => &VIRIDIS,
+            Gradient::Plasma => &PLASMA,
+            Gradient::Custom(stops) => stops,
+        };
+        match stops.len() {
+            0 => RGBColor(0, 0, 0),
+            1 => RGBColor(stops[0].0, stops[0].1, stops[0].2),
+            count => {
+                let position = t.clamp(0.0, 1.0)*(count - 1) as f64;
+                let index = (position.floor() as usize).min(count - 2);
+                let frac = position - index as f64;
+                let (a, b) = (stops[index], stops[index + 1]);
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64)*frac).round() as u8;
+                RGBColor(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+            },
+        }
+    }
+
+    /// Colors for `count` curves spread evenly from one end of the gradient
+    /// to the other.
+    pub fn spread(&self, count: usize) -> Vec<RGBColor> {
+        (0..count)
+            .map(|index| self.at(if count > 1 {index as f64/(count - 1) as f64} else {0.0}))
+            .collect()
+    }
+}
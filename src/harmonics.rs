@@ -0,0 +1,105 @@
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::*;
+use plotters::style::full_palette::ORANGE;
+
+use crate::axis::MagScale;
+use crate::config::PlotConfig;
+use crate::plottable::Plottable;
+use crate::sweep::Sweep;
+
+/// Markers at a switching frequency and its harmonics, f_sw, 2·f_sw,
+/// 3·f_sw…, the frequencies at which a converter puts its noise. Each marker
+/// reads out the magnitude of the plotted response at that harmonic.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::harmonics::HarmonicComb;
+///
+/// use sdomain_test::sdomain;
+///
+/// let comb = HarmonicComb::new(500e3, 4);
+/// assert_eq!(comb.freqs(), vec![500e3, 1e6, 1.5e6, 2e6]);
+///
+/// // 1mΩ in series with 1nH is 3.3mΩ at 500kHz
+/// let readings = comb.readings(&sdomain::gen::rl(1e-3, 1e-9));
+/// assert_eq!(readings[0].0, 1);
+/// assert!((readings[0].2 - 3.297e-3).abs() < 1e-6);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct HarmonicComb {
+    /// Switching frequency in Hz.
+    pub f_sw: f64,
+    /// Number of markers, including the one at the fundamental.
+    pub count: usize,
+}
+
+impl HarmonicComb {
+    /// Comb of `count` markers at multiples of `f_sw` in Hz.
+    pub fn new(f_sw: f64, count: usize) -> HarmonicComb {
+        HarmonicComb {f_sw, count}
+    }
+
+    /// Frequency in Hz of each marker, fundamental first.
+    pub fn freqs(&self) -> Vec<f64> {
+        (1..=self.count).map(|n| n as f64*self.f_sw).collect()
+    }
+
+    /// (harmonic number, frequency in Hz, magnitude) of `model` at each
+    /// marker. Harmonics where the response is not finite are left out.
+    pub fn readings<P: Plottable + ?Sized>(&self, model: &P) -> Vec<(usize, f64, f64)> {
+        let sweep = Sweep::at(model, self.freqs());
+        sweep.mag_points()
+            .enumerate()
+            .filter(|(_, (_, m))| m.is_finite())
+            .map(|(index, (f, m))| (index + 1, f, m))
+            .collect()
+    }
+
+    /// Draw the markers on a chart built from `config`, one dotted line per
+    /// harmonic within the frequency axis and a readout of `model`'s
+    /// magnitude in `unit` where the curve crosses it.
+    /// Returns the readings of the harmonics drawn.
+    pub fn draw<DB: DrawingBackend, P: Plottable + ?Sized>(&self, chart: &mut ChartContext<DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>, model: &P, config: &PlotConfig, unit: &str) -> Result<Vec<(usize, f64, f64)>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let x_range = chart.plotting_area().get_x_range();
+        let y_range = chart.plotting_area().get_y_range();
+        let readings = self.readings(model).into_iter()
+            .filter(|(_, f, _)| x_range.contains(&config.freq_to_axis(*f)))
+            .collect::<Vec<(usize, f64, f64)>>();
+
+        for (index, (n, freq, mag)) in readings.iter().enumerate() {
+            let x = config.freq_to_axis(*freq);
+            // Dotted, so the markers stay distinct from the data
+            let steps = 40;
+            let dash = (y_range.end - y_range.start)/steps as f64;
+            let series = chart.draw_series((0..steps).step_by(2).map(|k| {
+                let y = y_range.start + k as f64*dash;
+                PathElement::new(vec![(x, y), (x, y + dash)], ORANGE.mix(0.6))
+            }))?;
+            if index == 0 {
+                series.label(format!("f_sw = {}", config.freq_unit.format(config.freq_unit.from_hz(self.f_sw))))
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &ORANGE));
+            }
+
+            let y = config.mag_scale.to_axis(*mag);
+            if y_range.contains(&y) {
+                let readout = match config.mag_scale {
+                    MagScale::Log | MagScale::Linear => format!("{}: {}", harmonic_name(*n), config.mag_tick(y, unit)),
+                    scale => format!("{}: {} {}", harmonic_name(*n), config.mag_tick(y, unit), scale.unit(unit)),
+                };
+                chart.draw_series(std::iter::once(
+                        EmptyElement::at((x, y))
+                        + TriangleMarker::new((0, 0), 5, ORANGE.filled())
+                        + Text::new(readout, (6, -16), ("Arial", 15))
+                    ))?;
+            }
+        }
+
+        Ok(readings)
+    }
+}
+
+/// Marker name of harmonic `n`, e.g. "f_sw" or "3·f_sw".
+fn harmonic_name(n: usize) -> String {
+    if n == 1 {"f_sw".to_string()} else {format!("{n}·f_sw")}
+}
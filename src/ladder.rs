@@ -0,0 +1,121 @@
+use sdomain_test::sdomain::{self, Fs};
+
+/// A PDN described as a ladder of nodes. Node 0 is fed by the source
+/// impedance; each later node is reached through a series segment such as
+/// plane spreading inductance or via inductance. Any node can carry shunt
+/// elements such as capacitor banks.
+/// ```text
+///   source ──┬──[ segment 1 ]──┬──[ segment 2 ]──┬── ...
+///          shunts            shunts            shunts
+///   node     0                 1                 2
+/// ```
+#[derive(Clone)]
+pub struct Ladder {
+    source: Fs,
+    nodes: Vec<Node>,
+}
+
+/// One node of a ladder and the segment leading to it.
+#[derive(Clone)]
+pub struct Node {
+    /// Name and impedance of the series segment from the previous node, or
+    /// `None` for node 0.
+    pub segment: Option<(String, Fs)>,
+    /// Names and impedances of shunt elements to ground at this node.
+    pub shunts: Vec<(String, Fs)>,
+}
+
+impl Ladder {
+    /// Create a ladder with a single node fed by `source`.
+    pub fn new(source: Fs) -> Ladder {
+        Ladder {source, nodes: vec![Node {segment: None, shunts: vec![]}]}
+    }
+
+    /// Add a shunt element to the last node.
+    pub fn add_shunt(&mut self, name: &str, model: Fs) {
+        if let Some(node) = self.nodes.last_mut() {
+            node.shunts.push((name.to_string(), model));
+        }
+    }
+
+    /// Add a series segment after the last node, creating a new node.
+    pub fn add_segment(&mut self, name: &str, series: Fs) {
+        self.nodes.push(Node {segment: Some((name.to_string(), series)), shunts: vec![]});
+    }
+
+    /// Nodes of the ladder, starting from the source.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Impedance to ground seen at `node`, looking both toward the source
+    /// and toward the far end of the ladder.
+    pub fn impedance_at(&self, node: usize) -> Fs {
+        // Toward the source, including the shunts at `node`
+        let mut toward_source = self.source.clone();
+        for (index, current) in self.nodes.iter().enumerate().take(node + 1) {
+            if index > 0 {
+                if let Some((_, series)) = &current.segment {
+                    toward_source = toward_source + series;
+                }
+            }
+            toward_source = with_shunts(toward_source, current);
+        }
+
+        // Toward the far end, excluding the shunts at `node`
+        let mut toward_end: Option<Fs> = None;
+        for current in self.nodes.iter().skip(node + 1).rev() {
+            let z = current.shunts.iter().map(|(_, shunt)| shunt.clone())
+                .chain(toward_end.take())
+                .reduce(sdomain::parallel);
+            toward_end = match (z, &current.segment) {
+                (Some(inner), Some((_, series))) => Some(inner + series),
+                (z, _) => z,
+            };
+        }
+
+        match toward_end {
+            Some(z) => sdomain::parallel(toward_source, z),
+            None => toward_source,
+        }
+    }
+
+    /// Impedance seen at the last node when only the last `count` nodes of the
+    /// ladder are connected, plus the source if `include_source` is set and
+    /// every node is included. The segment leading into the outermost
+    /// included node is left open. Returns `None` if nothing is connected.
+    pub fn impedance_from_end(&self, count: usize, include_source: bool) -> Option<Fs> {
+        let first = self.nodes.len().saturating_sub(count);
+        let mut z = if include_source && first == 0 {Some(self.source.clone())} else {None};
+        for (index, current) in self.nodes.iter().enumerate().skip(first) {
+            if index > first {
+                z = match (z, &current.segment) {
+                    (Some(inner), Some((_, series))) => Some(inner + series),
+                    (z, _) => z,
+                };
+            }
+            z = current.shunts.iter().map(|(_, shunt)| shunt.clone())
+                .chain(z.take())
+                .reduce(sdomain::parallel);
+        }
+        z
+    }
+
+    /// Copy of the ladder with the segment leading to `node` replaced by a
+    /// short, used to see how much that segment contributes.
+    pub fn without_segment(&self, node: usize) -> Ladder {
+        let mut ladder = self.clone();
+        if let Some(current) = ladder.nodes.get_mut(node) {
+            if node > 0 {
+                let name = current.segment.take().map(|(name, _)| name).unwrap_or_default();
+                current.segment = Some((name, sdomain::gen::resistor(0.0)));
+            }
+        }
+        ladder
+    }
+}
+
+/// Place every shunt at `node` in parallel with `z`.
+fn with_shunts(z: Fs, node: &Node) -> Fs {
+    node.shunts.iter().fold(z, |acc, (_, shunt)| sdomain::parallel(acc, shunt.clone()))
+}
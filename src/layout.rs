@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use plotters::prelude::*;
+use plotters::style::full_palette::GREY;
+
+use crate::config::PlotConfig;
+use crate::error::{self, PlotError};
+use crate::plottable::Plottable;
+use crate::sweep::Sweep;
+type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+/// Height in pixels of the frequency axis labels under the bottom panel.
+const X_LABEL_AREA: i32 = 40;
+/// Width in pixels of the magnitude axis labels left of every panel.
+const Y_LABEL_AREA: i32 = 40;
+/// Width in pixels of the secondary axis labels right of every panel.
+const SECONDARY_LABEL_AREA: i32 = 40;
+
+/// Frequency axis geometry shared by the panels of a multi-panel figure.
+/// Building every panel's chart through the same `SharedX` gives them the
+/// same x range, tick count, label areas, and margins, so the plotting
+/// areas line up and a frequency falls on the same pixel column in every
+/// panel. Only the bottom panel carries the tick labels.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::config::PlotConfig;
+/// use sdomain_test_plotters::layout::{self, SharedX};
+///
+/// use plotters::prelude::*;
+///
+/// let drawing_area = BitMapBackend::new("images/layout_shared_x.png", (960, 720))
+///     .into_drawing_area();
+/// drawing_area.fill(&WHITE).unwrap();
+///
+/// let config = PlotConfig {fmin: 10.0, fmax: 1e6, ..PlotConfig::default()};
+/// let shared = SharedX::new(&config, false);
+/// let panels = layout::panels(&drawing_area, 2);
+/// for (index, panel) in panels.iter().enumerate() {
+///     let is_bottom = index == panels.len() - 1;
+///     let mut chart = shared.builder(panel, is_bottom).build_cartesian_2d(shared.range.clone(), 0.0..1.0).unwrap();
+///     chart.configure_mesh().x_labels(shared.labels).draw().unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedX {
+    /// Frequency axis range in axis units.
+    pub range: Range<f64>,
+    /// Number of tick labels to ask for on the frequency axis.
+    pub labels: usize,
+    /// Whether any panel has a secondary axis. Every panel then leaves room
+    /// for one so the plotting areas keep the same width.
+    pub right_axis: bool,
+}
+
+impl SharedX {
+    /// Shared frequency axis for the sweep range of `config`.
+    pub fn new(config: &PlotConfig, right_axis: bool) -> SharedX {
+        SharedX {range: config.freq_range(), labels: config.freq_label_count(), right_axis}
+    }
+
+    /// Chart builder on `panel` with the shared label areas and margins.
+    /// Only the bottom panel gets room for the frequency axis labels.
+    pub fn builder<'a, 'b, DB: DrawingBackend>(&self, panel: &'a DrawAreaType<DB>, is_bottom: bool) -> ChartBuilder<'a, 'b, DB> {
+        let mut builder = ChartBuilder::on(panel);
+        builder
+            .set_label_area_size(LabelAreaPosition::Left, Y_LABEL_AREA)
+            .set_label_area_size(LabelAreaPosition::Right, if self.right_axis {SECONDARY_LABEL_AREA} else {0})
+            .set_label_area_size(LabelAreaPosition::Bottom, if is_bottom {X_LABEL_AREA} else {0})
+            .margin(10);
+        builder
+    }
+}
+
+/// Split `area` into `count` stacked panels whose plotting areas have the
+/// same height when built with `SharedX::builder`: the bottom panel is
+/// taller by the frequency axis labels.
+pub fn panels<DB: DrawingBackend>(area: &DrawAreaType<DB>, count: usize) -> Vec<DrawAreaType<DB>> {
+    let height = area.dim_in_pixel().1 as i32;
+    let panel_height = (height - X_LABEL_AREA)/count.max(1) as i32;
+    let breaks = (1..count as i32).map(|index| index*panel_height).collect::<Vec<i32>>();
+    area.split_by_breakpoints([] as [i32; 0], breaks)
+}
+
+/// Lay out the responses of several models as vertically stacked panels,
+/// one per model and in the order given, sharing a single frequency axis
+/// labeled under the bottom panel. Each panel scales its own magnitude axis
+/// and, unless the configuration hides it, shows the phase on the right.
+/// Returns a result to indicate if the function executed without error.
+///
+/// # Arguments
+/// * `canvas` - A Plotter's DrawingArea to fill with the panels.
+/// * `models` - The models to plot, top to bottom. Their display names
+///              label the panels.
+/// * `config` - Sweep range, axes, and caption shared by all panels.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::config::PlotConfig;
+/// use sdomain_test_plotters::layout;
+/// use sdomain_test_plotters::plottable::Plottable;
+///
+/// use plotters::prelude::*;
+/// use sdomain_test::sdomain;
+///
+/// let drawing_area = BitMapBackend::new("images/layout_stack.png", (960, 960))
+///     .into_drawing_area();
+/// drawing_area.fill(&WHITE).unwrap();
+///
+/// let zr = sdomain::gen::resistor(100.0);
+/// let zc = sdomain::gen::capacitor(4.7e-6);
+/// let lpf = (zc.clone() / &(zr.clone() + &zc)).named("Low Pass");
+/// let hpf = (zr.clone() / &(zr + &zc)).named("High Pass");
+///
+/// let config = PlotConfig {fmin: 10.0, fmax: 1e6, ..PlotConfig::default()};
+/// layout::stack(&drawing_area, &[&lpf, &hpf], &config).unwrap();
+/// ```
+pub fn stack<DB: DrawingBackend>(canvas: &DrawAreaType<DB>, models: &[&dyn Plottable], config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+where DB::ErrorType: 'static {
+    if models.is_empty() {return Err(PlotError::Empty.into());}
+    error::check_range(config.fmin, config.fmax)?;
+    let sweeps = models.iter()
+        .map(|model| config.check_finite(Sweep::at(*model, config.sample_freqs()), &model.display_name()))
+        .collect::<Result<Vec<Sweep>, PlotError>>()?;
+
+    let area = match config.caption_or("Frequency Response") {
+        Some(caption) => canvas.titled(&caption, ("Arial", 30))?,
+        None => canvas.clone(),
+    };
+    let panels = panels(&area, models.len());
+    let shared = SharedX::new(config, config.show_phase);
+
+    let mag_scale = config.mag_scale;
+    let x_label = config.x_label();
+    let x_ticks = shared.labels;
+    let y_formatter = |y: &f64| config.mag_tick(*y, "");
+    for (index, ((panel, model), sweep)) in panels.iter().zip(models.iter()).zip(sweeps.iter()).enumerate() {
+        let is_bottom = index == models.len() - 1;
+        error::check_limits(sweep.min_mag(), sweep.max_mag())?;
+        let y_range = config.y_range(sweep.min_mag(), sweep.max_mag());
+
+        let mut chart = shared.builder(panel, is_bottom)
+            .build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+        let x_formatter = |x: &f64| if is_bottom {config.freq_tick(*x)} else {String::new()};
+        chart.configure_mesh()
+            .x_labels(x_ticks)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&y_formatter)
+            .x_desc(if is_bottom {x_label.as_str()} else {""})
+            .y_desc(config.y_label_or(&mag_scale.label("Magnitude", "")))
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(
+                sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                &GREEN
+            ))?
+            .label(model.display_name())
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+        if config.show_phase {
+            let mut chart = chart.set_secondary_coord(
+                shared.range.clone(),
+                config.phase_range.0..config.phase_range.1
+            );
+            chart.configure_secondary_axes()
+                .x_labels(x_ticks)
+                .x_label_formatter(&x_formatter)
+                .y_desc(config.phase_label_or("Phase [°]"))
+                .draw()?;
+            chart.draw_secondary_series(LineSeries::new(
+                    sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(sweep)),
+                    &RED.mix(0.4)
+                ))?
+                .label("Phase")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        } else {
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+/// Grouping and ordering of legend entries for plots with many series, such
+/// as one curve per capacitor bank. Groups are listed in the order they are
+/// added, each under a header, with their members in the order given.
+/// Series left out of every group follow under "Other" in the order they
+/// were plotted. Without any groups the legend keeps the plotting order.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::legend::{LegendEntry, LegendGroups};
+///
+/// let groups = LegendGroups::new()
+///     .group("Bulk", &["330uF", "22uF"])
+///     .group("HF", &["100nF"]);
+/// let entries = groups.order(&["100nF", "22uF", "VRM", "330uF"]);
+/// assert_eq!(entries, vec![
+///     LegendEntry::Header("Bulk".to_string()),
+///     LegendEntry::Series(3),
+///     LegendEntry::Series(1),
+///     LegendEntry::Header("HF".to_string()),
+///     LegendEntry::Series(0),
+///     LegendEntry::Header("Other".to_string()),
+///     LegendEntry::Series(2),
+/// ]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LegendGroups {
+    /// (header, member series names) of each group, in legend order.
+    pub groups: Vec<(String, Vec<String>)>,
+}
+
+/// One line of a grouped legend.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LegendEntry {
+    /// A group header, drawn without a line sample.
+    Header(String),
+    /// The series at this index in plotting order.
+    Series(usize),
+}
+
+impl LegendGroups {
+    /// Start with no groups, keeping the plotting order.
+    pub fn new() -> LegendGroups {
+        LegendGroups::default()
+    }
+
+    /// Add a group titled `header` listing the series named in `members`,
+    /// in that order. Names with no matching series are skipped.
+    pub fn group(mut self, header: &str, members: &[&str]) -> LegendGroups {
+        self.groups.push((header.to_string(), members.iter().map(|name| name.to_string()).collect()));
+        self
+    }
+
+    /// Legend lines for series named `names`, given in plotting order. A
+    /// series named in more than one group is listed in the first only.
+    pub fn order(&self, names: &[&str]) -> Vec<LegendEntry> {
+        if self.groups.is_empty() {
+            return (0..names.len()).map(LegendEntry::Series).collect();
+        }
+        let mut listed = vec![false; names.len()];
+        let mut entries = vec![];
+        for (header, members) in self.groups.iter() {
+            let series = members.iter()
+                .filter_map(|member| names.iter().position(|name| *name == member.as_str()))
+                .filter(|index| !std::mem::replace(&mut listed[*index], true))
+                .collect::<Vec<usize>>();
+            if series.is_empty() {continue;}
+            entries.push(LegendEntry::Header(header.clone()));
+            entries.extend(series.into_iter().map(LegendEntry::Series));
+        }
+        let rest = (0..names.len()).filter(|index| !listed[*index]).collect::<Vec<usize>>();
+        if !rest.is_empty() {
+            entries.push(LegendEntry::Header("Other".to_string()));
+            entries.extend(rest.into_iter().map(LegendEntry::Series));
+        }
+        entries
+    }
+}
@@ -1 +1,46 @@
+pub mod capacitor_plotter;
 pub mod pdn_impedance_plotter;
+pub mod sdomain_plotter;
+pub mod spectrum_plotter;
+pub mod stability_plotter;
+pub mod time_domain_plotter;
+pub mod analysis;
+pub mod axis;
+pub mod bands;
+pub mod bom;
+pub mod compliance;
+pub mod config;
+pub mod consistency;
+pub mod corners;
+pub mod crosstalk;
+pub mod discrete;
+pub mod error;
+pub mod export;
+pub mod gradient;
+pub mod harmonics;
+pub mod ladder;
+pub mod layout;
+pub mod legend;
+pub mod loop_gain;
+pub mod mounting;
+pub mod optimizer;
+pub mod phasor;
+pub mod plottable;
+pub mod pole_zero;
+pub mod prelude;
+pub mod provenance;
+pub mod render;
+pub mod report;
+pub mod resonance;
+pub mod spectrum;
+pub mod spice;
+pub mod sweep;
+pub mod synthetic;
+pub mod target;
+pub mod testing;
+pub mod time_domain;
+pub mod touchstone;
+pub mod transmission_line;
+pub mod two_port;
+pub mod units;
+pub mod vrm;
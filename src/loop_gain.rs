@@ -0,0 +1,112 @@
+use std::f64::consts::PI;
+
+use crate::sweep::Sweep;
+
+/// Frequencies where the magnitude of a sweep crosses `level`, in either
+/// direction. Each crossing is interpolated linearly on log-log axes between
+/// the samples on either side.
+///
+/// # Arguments
+/// * `sweep` - The sampled response.
+/// * `level` - Magnitude to look for, in linear units.
+pub fn crossings(sweep: &Sweep, level: f64) -> Vec<f64> {
+    sweep.freqs.windows(2).zip(sweep.mags.windows(2))
+        .filter(|(_, m)| (m[0] - level)*(m[1] - level) < 0.0 || (m[1] == level && m[0] != level))
+        .map(|(f, m)| {
+            let frac = (level/m[0]).ln()/(m[1]/m[0]).ln();
+            f[0]*(f[1]/f[0]).powf(frac)
+        })
+        .collect()
+}
+
+/// Unity-gain (0 dB) crossover frequencies of a loop gain, from lowest to
+/// highest. A resonant loop can cross 0 dB several times.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::loop_gain;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// use sdomain_test::sdomain;
+///
+/// // An integrator 1/(s·C) with C = 1/(2π·1kHz) crosses 0 dB at 1kHz
+/// let integrator = sdomain::gen::capacitor(1.0/(2.0*std::f64::consts::PI*1e3));
+/// let crossovers = loop_gain::unity_gain_crossovers(&Sweep::of(&integrator, 1.0, 1e6));
+/// assert_eq!(crossovers.len(), 1);
+/// assert!((crossovers[0]/1e3 - 1.0).abs() < 1e-3);
+/// ```
+pub fn unity_gain_crossovers(sweep: &Sweep) -> Vec<f64> {
+    crossings(sweep, 1.0)
+}
+
+/// Gain and phase margins of a loop gain, and the frequencies they are
+/// measured at.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StabilityMargins {
+    /// Frequency in Hz where the loop gain crosses 0 dB and the phase
+    /// margin is measured. With several crossovers, the one with the least
+    /// phase margin.
+    pub gain_crossover: Option<f64>,
+    /// 180° plus the phase of the loop gain at the gain crossover.
+    pub phase_margin: Option<f64>,
+    /// Frequency in Hz where the phase of the loop gain crosses -180° and
+    /// the gain margin is measured. With several crossings, the one with
+    /// the least gain margin.
+    pub phase_crossover: Option<f64>,
+    /// Gain below 0 dB at the phase crossover, in dB.
+    pub gain_margin: Option<f64>,
+    /// Extra loop delay in s that would use up the phase margin, PM/ω_gc.
+    /// This is the budget for computation and sampling delay in a digital
+    /// controller.
+    pub delay_margin: Option<f64>,
+}
+
+/// Find the gain and phase margins of a loop gain from its unwrapped phase.
+/// A margin is `None` when its crossover is outside the sweep.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::loop_gain;
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// use sdomain_test::sdomain;
+///
+/// // An integrator has 90° of phase margin and no phase crossover
+/// let integrator = sdomain::gen::capacitor(1.0/(2.0*std::f64::consts::PI*1e3));
+/// let margins = loop_gain::margins(&Sweep::of(&integrator, 1.0, 1e6));
+/// assert!((margins.phase_margin.unwrap() - 90.0).abs() < 0.1);
+/// assert_eq!(margins.gain_margin, None);
+/// // 90° at 1kHz is a quarter period
+/// assert!((margins.delay_margin.unwrap()/250e-6 - 1.0).abs() < 1e-2);
+/// ```
+pub fn margins(sweep: &Sweep) -> StabilityMargins {
+    let phases = sweep.unwrapped_phases();
+    let log_mags = sweep.mags.iter().map(|m| m.log10()).collect::<Vec<f64>>();
+
+    let phase_margin = unity_gain_crossovers(sweep).into_iter()
+        .map(|freq| (freq, 180.0 + value_at(&sweep.freqs, &phases, freq)))
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let gain_margin = sweep.freqs.windows(2).zip(phases.windows(2))
+        .filter(|(_, p)| (p[0] + 180.0)*(p[1] + 180.0) < 0.0 || (p[1] == -180.0 && p[0] != -180.0))
+        .map(|(f, p)| f[0]*(f[1]/f[0]).powf((-180.0 - p[0])/(p[1] - p[0])))
+        .map(|freq| (freq, -20.0*value_at(&sweep.freqs, &log_mags, freq)))
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    StabilityMargins {
+        gain_crossover: phase_margin.map(|(freq, _)| freq),
+        phase_margin: phase_margin.map(|(_, margin)| margin),
+        phase_crossover: gain_margin.map(|(freq, _)| freq),
+        gain_margin: gain_margin.map(|(_, margin)| margin),
+        delay_margin: phase_margin.map(|(freq, margin)| margin.to_radians()/(2.0*PI*freq)),
+    }
+}
+
+/// Value of a sampled quantity at `freq`, interpolated linearly against the
+/// logarithm of frequency.
+fn value_at(freqs: &[f64], values: &[f64], freq: f64) -> f64 {
+    let index = freqs.windows(2).position(|f| freq <= f[1]).unwrap_or(freqs.len().saturating_sub(2));
+    match (freqs.get(index..index + 2), values.get(index..index + 2)) {
+        (Some(f), Some(v)) => v[0] + (v[1] - v[0])*(freq/f[0]).ln()/(f[1]/f[0]).ln(),
+        _ => values.first().cloned().unwrap_or(f64::NAN),
+    }
+}
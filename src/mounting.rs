@@ -0,0 +1,38 @@
+use sdomain_test::pdn::PDNModel;
+use sdomain_test::sdomain::{self, Fs};
+
+/// A capacitor placed in a PDN together with the extra inductance of its
+/// mounting: pads, traces, and vias to the planes.
+#[derive(Clone)]
+pub struct MountedCapacitor {
+    /// Name to list the capacitor under in the PDN.
+    pub name: String,
+    /// The capacitor's own impedance model.
+    pub model: Fs,
+    /// Number of identical capacitors placed.
+    pub count: usize,
+    /// Mounting inductance per capacitor in H.
+    pub mounting_inductance: f64,
+}
+
+impl MountedCapacitor {
+    /// Impedance of one capacitor including its mounting inductance.
+    pub fn mounted_model(&self) -> Fs {
+        self.model.clone() + &sdomain::gen::inductor(self.mounting_inductance)
+    }
+}
+
+/// Build a PDN from a source impedance and a list of mounted capacitors.
+///
+/// # Arguments
+/// * `source` - Impedance of the source feeding the PDN, e.g. a VRM model.
+/// * `capacitors` - Capacitors to place.
+/// * `include_mounting` - Whether to add each capacitor's mounting inductance.
+pub fn build_pdn(source: &Fs, capacitors: &[MountedCapacitor], include_mounting: bool) -> PDNModel {
+    let mut pdn = PDNModel::from(source.clone(), None);
+    for cap in capacitors.iter() {
+        let model = if include_mounting {cap.mounted_model()} else {cap.model.clone()};
+        pdn.add_capacitor(&cap.name, model, cap.count);
+    }
+    pdn
+}
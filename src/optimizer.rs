@@ -0,0 +1,158 @@
+use sdomain_test::passives::capacitor::Capacitor;
+use sdomain_test::pdn::PDNModel;
+use sdomain_test::sdomain::Fs;
+
+use crate::sweep::Sweep;
+use crate::target::TargetMask;
+
+/// Frequency range over which designs are checked against the mask.
+pub const MIN_FREQ: f64 = 1.0;
+pub const MAX_FREQ: f64 = 100e6;
+
+/// A quantity of one capacitor from a library.
+#[derive(Clone)]
+pub struct BomEntry<'a> {
+    pub capacitor: &'a Capacitor,
+    pub count: usize,
+}
+
+/// Outcome of a decoupling optimization.
+pub struct Optimization<'a> {
+    /// Capacitors chosen, including any from the starting design.
+    pub bom: Vec<BomEntry<'a>>,
+    /// The optimized PDN.
+    pub model: PDNModel,
+    /// Largest ratio of impedance to target over the sweep. The design meets
+    /// the mask when this is at most 1.
+    pub worst_ratio: f64,
+}
+
+impl<'a> Optimization<'a> {
+    /// Whether the optimized PDN stays under the mask at every frequency.
+    pub fn meets_target(&self) -> bool {
+        self.worst_ratio <= 1.0
+    }
+
+    /// Total number of capacitors in the design.
+    pub fn component_count(&self) -> usize {
+        self.bom.iter().map(|entry| entry.count).sum()
+    }
+}
+
+/// Build a PDN from a source impedance and a list of capacitors.
+pub fn build_pdn(source: &Fs, bom: &[BomEntry]) -> PDNModel {
+    let mut pdn = PDNModel::from(source.clone(), None);
+    for entry in bom.iter() {
+        pdn.add_capacitor(&format!("{}", entry.capacitor), entry.capacitor.model(), entry.count);
+    }
+    pdn
+}
+
+/// Largest ratio of a PDN's impedance to the mask over the sweep range.
+pub fn worst_ratio(pdn: &PDNModel, mask: &TargetMask) -> f64 {
+    let sweep = Sweep::of(&pdn.model(), MIN_FREQ, MAX_FREQ);
+    sweep.mag_points()
+        .map(|(f, z)| z/mask.limit_at(f))
+        .fold(0.0, f64::max)
+}
+
+/// Greedily add capacitors from a library until a PDN meets a target mask or
+/// the component budget runs out. Each step adds the one capacitor that most
+/// reduces the worst-case impedance-to-target ratio; the search stops early
+/// if no capacitor improves it.
+///
+/// # Arguments
+/// * `source` - Impedance of the source feeding the PDN, e.g. a VRM model.
+/// * `initial` - Capacitors already in the design.
+/// * `library` - Capacitors the optimizer may choose from.
+/// * `mask` - Impedance target to meet.
+/// * `budget` - Maximum total number of capacitors, including `initial`.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::optimizer;
+/// use sdomain_test_plotters::target::TargetMask;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+/// use sdomain_test::sdomain;
+///
+/// let library = vec![
+///     Capacitor::from(22e-6, "0603"),
+///     Capacitor::from(1e-6, "0402"),
+///     Capacitor::from(100e-9, "0201"),
+/// ];
+/// let result = optimizer::optimize(
+///     &sdomain::gen::rl(5e-3, 1.5e-6), vec![], &library, &TargetMask::flat(0.1), 20
+/// );
+/// assert!(result.component_count() <= 20);
+/// ```
+pub fn optimize<'a>(source: &Fs, initial: Vec<BomEntry<'a>>, library: &'a [Capacitor], mask: &TargetMask, budget: usize) -> Optimization<'a> {
+    let mut bom = initial;
+    let mut best = worst_ratio(&build_pdn(source, &bom), mask);
+
+    while best > 1.0 && bom.iter().map(|entry| entry.count).sum::<usize>() < budget {
+        match best_addition(source, &bom, library, mask, best) {
+            Some((capacitor, ratio)) => {
+                bom = with_one_more(&bom, capacitor);
+                best = ratio;
+            },
+            None => break,
+        }
+    }
+
+    Optimization {model: build_pdn(source, &bom), bom, worst_ratio: best}
+}
+
+/// Lowest peak impedance the optimizer achieves for every component budget
+/// from 1 to `max_budget`. Because the greedy search only ever adds parts,
+/// the design for each budget extends the design for the budget before it,
+/// so the whole curve comes from a single search.
+/// Returns (component count, peak |Z| in Ω) pairs. The curve ends early if
+/// adding another capacitor no longer lowers the peak.
+///
+/// # Arguments
+/// * `source` - Impedance of the source feeding the PDN, e.g. a VRM model.
+/// * `library` - Capacitors the optimizer may choose from.
+/// * `max_budget` - Largest total number of capacitors to consider.
+pub fn tradeoff(source: &Fs, library: &[Capacitor], max_budget: usize) -> Vec<(usize, f64)> {
+    // Against a 1Ω flat target the worst-case ratio is the peak impedance
+    let mask = TargetMask::flat(1.0);
+    let mut bom = vec![];
+    let mut peak = worst_ratio(&build_pdn(source, &bom), &mask);
+    let mut curve = vec![];
+
+    for count in 1..=max_budget {
+        match best_addition(source, &bom, library, &mask, peak) {
+            Some((capacitor, ratio)) => {
+                bom = with_one_more(&bom, capacitor);
+                peak = ratio;
+                curve.push((count, peak));
+            },
+            None => break,
+        }
+    }
+    curve
+}
+
+/// The library capacitor whose addition lowers the worst-case ratio the most,
+/// along with the ratio it achieves, or `None` if nothing beats `current`.
+fn best_addition<'a>(source: &Fs, bom: &[BomEntry<'a>], library: &'a [Capacitor], mask: &TargetMask, current: f64) -> Option<(&'a Capacitor, f64)> {
+    let mut step: Option<(&'a Capacitor, f64)> = None;
+    for capacitor in library.iter() {
+        let ratio = worst_ratio(&build_pdn(source, &with_one_more(bom, capacitor)), mask);
+        if ratio < step.map(|s| s.1).unwrap_or(current) {
+            step = Some((capacitor, ratio));
+        }
+    }
+    step
+}
+
+/// Copy of `bom` with one more of `capacitor`.
+fn with_one_more<'a>(bom: &[BomEntry<'a>], capacitor: &'a Capacitor) -> Vec<BomEntry<'a>> {
+    let mut bom = bom.to_vec();
+    match bom.iter_mut().find(|entry| std::ptr::eq(entry.capacitor, capacitor)) {
+        Some(entry) => entry.count += 1,
+        None => bom.push(BomEntry {capacitor, count: 1}),
+    }
+    bom
+}
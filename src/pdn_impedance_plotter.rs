@@ -1,20 +1,74 @@
 pub mod pdn_plotter {
+    use std::ops::Range;
+
     use plotters::prelude::*;
-    use plotters::style::full_palette::{PURPLE, GREY};
+    use plotters::style::full_palette::{ORANGE, PURPLE, GREY};
     
+    use sdomain_test::passives::capacitor::Capacitor;
     use sdomain_test::pdn::PDNModel;
-    use sdomain_test::sdomain::Fs;
-    use sdomain_test::complex::Complex;
-    use sdomain_test::range_generators::gen_log_range;
-    type DrawAreaType<'a> = DrawingArea <BitMapBackend<'a>, plotters::coord::Shift>;
+    use sdomain_test::sdomain::{self, Fs};
+
+    use crate::bom::Decap;
+    use crate::axis::MagScale;
+    use crate::bands;
+    use crate::compliance;
+    use crate::config::{Caption, PlotConfig};
+    use crate::consistency::{self, Finding};
+    use crate::crosstalk::RailCoupling;
+    use crate::error::{self, PlotError};
+    use crate::gradient::Gradient;
+    use crate::ladder::Ladder;
+    use crate::layout::SharedX;
+    use crate::legend::{LegendEntry, LegendGroups};
+    use crate::mounting::{self, MountedCapacitor};
+    use crate::optimizer::{self, Optimization};
+    use crate::plottable::Plottable;
+    use crate::resonance;
+    use crate::sweep::Sweep;
+    use crate::target::TargetMask;
+    use crate::time_domain_plotter::pdn_transient_plotter;
+    use crate::transmission_line::TransmissionLine;
+    use crate::units::{format_eng, format_farads, format_freq, format_ohms, format_plain};
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    const MAX_FREQ: f64 = 100e6;
+
+    /// Return loss requirements in dB drawn as guide lines on return loss plots.
+    const RETURN_LOSS_GUIDES: [f64; 2] = [10.0, 15.0];
+
+    /// VSWR requirements drawn as guide lines on VSWR plots.
+    const VSWR_GUIDES: [f64; 2] = [1.5, 2.0];
+
+    /// Most quarter-wave resonance markers drawn on a transmission line plot.
+    const QUARTER_WAVE_MARKERS: usize = 20;
+
+    /// Frequency ranges shaded behind the curves of a plot.
+    #[derive(Default)]
+    struct Bands {
+        /// (start, end, color) of each shaded range.
+        spans: Vec<(f64, f64, RGBColor)>,
+        /// Legend entries explaining the shading colors.
+        legend: Vec<(String, RGBColor)>,
+    }
+
+    /// One supply rail in a multi-rail figure.
+    pub struct Rail<'a> {
+        /// Rail name to print in the panel title, e.g. "1V0 Core".
+        pub name: &'a str,
+        /// The rail's power distribution network model.
+        pub model: &'a dyn Plottable,
+        /// (Optional) The rail's target impedance.
+        pub impedance_target: Option<f64>,
+    }
 
     /// Plot a PDN's impedance model over frequency. Optionally, an impedance
     /// target can be specified to highlight at what frequencies the PDN
-    /// model exceeds the target.
+    /// model exceeds the target. Any other impedance model can be plotted the
+    /// same way; its display name is printed in the plot title.
     /// Returns a result to indicate if the function executed without error.
     /// 
     /// # Arguments
-    /// * `model` - A power distribution network model.
+    /// * `model` - A power distribution network model, or any impedance model.
     /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
     /// * `impedance_target` - (Optional) If specified, the data is plotted
     ///                                   as an area curve to show at which
@@ -47,89 +101,2343 @@ pub mod pdn_plotter {
     /// // Plot the PDN network and set 100mΩ as the target impedance.
     /// pdn_plotter::plot(&pdn, &drawing_area, Some(0.1/*Ω*/)).unwrap();
     /// ```
-    pub fn plot(model: &PDNModel, canvas: &DrawAreaType, impedance_target: Option<f64>) -> Result<(), Box <dyn std::error::Error>> {
-        draw(canvas, "PDN", model.model(), impedance_target)
+    pub fn plot<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, canvas: &DrawAreaType<DB>, impedance_target: Option<f64>) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let config = PlotConfig {fmax: MAX_FREQ, target: impedance_target, ..PlotConfig::default()};
+        draw(canvas, model, &config)
     }
 
-    /// Plot an s-domain model as impedance over frequency. Optionally, an impedance
-    /// target can be specified to highlight at what frequencies the model exceeds
-    /// the target.
+    /// Plot an impedance model over frequency like `plot`, with the
+    /// frequency range, target, phase curve, caption, and axis labels taken
+    /// from a `PlotConfig`.
     /// Returns a result to indicate if the function executed without error.
-    /// 
+    ///
     /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
     /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
-    /// * `name` - Model name to print in the plot title.
-    /// * `model` - An impedance model in the s-domain.
-    /// * `impedance_target` - (Optional) If specified, the data is plotted
-    ///                                   as an area curve to show at which
-    ///                                   frequencies the impedance exceeds
-    ///                                   the target.
-    fn draw(canvas: &DrawAreaType, name: &str, model: Fs, impedance_target: Option<f64>) -> Result<(), Box <dyn std::error::Error>> {
-        const MAX_FREQ: f64 = 100e6;
-        let freq_data = gen_log_range(1.0, MAX_FREQ, 10.0, 100);
-        let complex_data = freq_data.iter().map(|freq| model.calculate_freq(*freq)).collect::<Vec<Complex>>();
-        let mag_data = complex_data.iter().map(|c| c.mag()).collect::<Vec<f64>>();
-        let phase_data = complex_data.iter().map(|c| c.phase_deg()).collect::<Vec<f64>>();
-
-        let mut min_mag = 1e12;
-        for mag in mag_data.iter() {if min_mag > *mag {min_mag = *mag;}}
-        min_mag *= 1e4;
-
-        let mut chart = ChartBuilder::on(&canvas)
-        .caption(format!("Impedance of {name}"), ("Arial", 30))
-            .set_label_area_size(LabelAreaPosition::Left, 40)
-            .set_label_area_size(LabelAreaPosition::Right, 40)
-            .set_label_area_size(LabelAreaPosition::Bottom, 40)
-            .margin(10)
-            .build_cartesian_2d((1.0f64..MAX_FREQ).log_scale(), (0.0..min_mag).log_scale())
-            .unwrap()
-            .set_secondary_coord((1.0f64..MAX_FREQ).log_scale(), -180.0..180.0);
+    /// * `config` - Options for the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::{Caption, PlotConfig};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_no_caption.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+    ///
+    /// let config = PlotConfig {
+    ///     caption: Caption::Hidden,
+    ///     y_label: Some("|Z| [Ω]".to_string()),
+    ///     show_phase: false,
+    ///     ..PlotConfig::default()
+    /// };
+    /// pdn_plotter::plot_with_config(&pdn, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_with_config<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw(canvas, model, config)
+    }
+
+    /// Plot the reflection coefficient S11 of an impedance model in dB
+    /// against a reference impedance, the view a VNA or an EM solver gives
+    /// of a one-port, so the model can be compared with a measurement
+    /// directly. The magnitude axis is always in dB; the phase follows the
+    /// configuration.
+    /// Returns the S11 sweep, or an error if it could not be plotted.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `z0` - Reference impedance in Ω, usually the 50Ω of the instrument.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the S11 plot.
+    /// * `config` - Sweep range, phase, caption, and axis labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_s11.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // 50Ω termination with 5nH of lead inductance
+    /// let termination = sdomain::gen::rl(50.0, 5e-9);
+    /// let config = PlotConfig {fmin: 1e6, fmax: 1e9, ..PlotConfig::default()};
+    /// let s11 = pdn_plotter::plot_s11(&termination, 50.0, &drawing_area, &config).unwrap();
+    /// // Matched at low frequency, mismatched by the inductance at 1GHz
+    /// assert!(s11.mags[0] < 1e-3);
+    /// assert!(*s11.mags.last().unwrap() > 0.1);
+    /// ```
+    pub fn plot_s11<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, z0: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Sweep, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let name = model.display_name();
+        let s11 = config.check_finite(Sweep::at(model, config.sample_freqs()), &name)?.to_s11(z0);
+        let s11_db = s11.mags.iter().map(|m| MagScale::Db.to_axis(*m)).collect::<Vec<f64>>();
+        draw_reflection(
+            canvas,
+            &format!("S11 of {name}, {} Reference", format_ohms(z0)),
+            &s11,
+            ("S11", &s11_db),
+            &[],
+            false,
+            config
+        )?;
+        Ok(s11)
+    }
+
+    /// Plot the return loss of an impedance model against a reference
+    /// impedance, -20·log10(|S11|), in the positive dB convention of RF
+    /// front-end specifications, with guide lines at the usual 10 dB and
+    /// 15 dB requirements. Higher is a better match.
+    /// Returns the return loss in dB at each frequency of the sweep, or an
+    /// error if it could not be plotted.
+    ///
+    /// # Arguments
+    /// * `model` - An impedance model, such as an antenna or amplifier input.
+    /// * `z0` - Reference impedance in Ω, usually 50Ω.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the return loss plot.
+    /// * `config` - Sweep range, phase, caption, and axis labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_return_loss.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // Series RLC resonant at 100MHz, matched to 50Ω at resonance
+    /// let resonator = sdomain::gen::rcl(50.0, 25.33e-12, 100e-9);
+    /// let config = PlotConfig {fmin: 10e6, fmax: 1e9, show_phase: false, ..PlotConfig::default()};
+    /// let return_loss = pdn_plotter::plot_return_loss(&resonator, 50.0, &drawing_area, &config).unwrap();
+    /// assert!(return_loss.iter().cloned().fold(0.0, f64::max) > 15.0);
+    /// assert!(return_loss[0] < 1.0);
+    /// ```
+    pub fn plot_return_loss<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, z0: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Vec<f64>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let name = model.display_name();
+        let s11 = config.check_finite(Sweep::at(model, config.sample_freqs()), &name)?.to_s11(z0);
+        let return_loss = s11.mags.iter().map(|m| -MagScale::Db.to_axis(*m)).collect::<Vec<f64>>();
+        draw_reflection(
+            canvas,
+            &format!("Return Loss of {name}, {} Reference", format_ohms(z0)),
+            &s11,
+            ("Return Loss", &return_loss),
+            &RETURN_LOSS_GUIDES,
+            false,
+            config
+        )?;
+        Ok(return_loss)
+    }
+
+    /// Plot the voltage standing wave ratio of an impedance model against a
+    /// reference impedance, (1 + |S11|)/(1 - |S11|), on a log axis with
+    /// guide lines at the usual 1.5:1 and 2:1 requirements. A perfect match
+    /// is 1:1; a total reflection has no finite VSWR and is left out.
+    /// Returns the VSWR at each frequency of the sweep, or an error if it
+    /// could not be plotted.
+    ///
+    /// # Arguments
+    /// * `model` - An impedance model, such as an antenna or amplifier input.
+    /// * `z0` - Reference impedance in Ω, usually 50Ω.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the VSWR plot.
+    /// * `config` - Sweep range, phase, caption, and axis labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_vswr.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // Series RLC resonant at 100MHz, matched to 50Ω at resonance
+    /// let resonator = sdomain::gen::rcl(50.0, 25.33e-12, 100e-9);
+    /// let config = PlotConfig {fmin: 10e6, fmax: 1e9, show_phase: false, ..PlotConfig::default()};
+    /// let vswr = pdn_plotter::plot_vswr(&resonator, 50.0, &drawing_area, &config).unwrap();
+    /// assert!(vswr.iter().cloned().fold(f64::INFINITY, f64::min) < 1.5);
+    /// assert!(vswr[0] > 2.0);
+    /// ```
+    pub fn plot_vswr<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, z0: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Vec<f64>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let name = model.display_name();
+        let s11 = config.check_finite(Sweep::at(model, config.sample_freqs()), &name)?.to_s11(z0);
+        let vswr = s11.vswr();
+        draw_reflection(
+            canvas,
+            &format!("VSWR of {name}, {} Reference", format_ohms(z0)),
+            &s11,
+            ("VSWR", &vswr),
+            &VSWR_GUIDES,
+            true,
+            config
+        )?;
+        Ok(vswr)
+    }
+
+    /// Plot the input impedance of a transmission line terminated by a load,
+    /// together with the same line left open and shorted and its
+    /// characteristic impedance. Dotted markers at the odd multiples of the
+    /// quarter-wave frequency show where the open line resonates and the
+    /// shorted line antiresonates; a matched load stays flat at Z0. The
+    /// extremes of a nearly lossless line are clipped to three decades
+    /// around Z0.
+    /// Returns the input impedance of the terminated line, or an error if it
+    /// could not be plotted.
+    ///
+    /// # Arguments
+    /// * `line` - The transmission line.
+    /// * `load` - Impedance model at the far end of the line. Its display
+    ///            name is printed in the legend.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Sweep range, axes, phase, caption, and axis labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::plottable::Plottable;
+    /// use sdomain_test_plotters::transmission_line::TransmissionLine;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_transmission_line.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // 10cm of 50Ω FR-4 microstrip into a 10pF receiver input
+    /// let line = TransmissionLine::rlgc(5.0, 330e-9, 1e-4, 130e-12, 0.1);
+    /// let receiver = sdomain::gen::capacitor(10e-12).named("10pF Input");
+    /// let config = PlotConfig {fmin: 10e6, fmax: 3e9, ..PlotConfig::default()};
+    /// let input = pdn_plotter::plot_line_impedance(&line, &receiver, &drawing_area, &config).unwrap();
+    /// assert_eq!(input.freqs.len(), config.sample_freqs().len());
+    /// ```
+    pub fn plot_line_impedance<DB: DrawingBackend, P: Plottable + ?Sized>(line: &TransmissionLine, load: &P, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Sweep, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let name = load.display_name();
+        let loaded = config.check_finite(line.input_sweep(load, config.sample_freqs()), &name)?;
+        let z0 = loaded.freqs.iter().map(|f| (*f, line.characteristic_impedance(*f).abs())).collect::<Vec<(f64, f64)>>();
+        let curves = [
+            ("Open", line.open_sweep(loaded.freqs.clone()), BLUE),
+            ("Shorted", line.short_sweep(loaded.freqs.clone()), RED),
+            (name.as_str(), loaded.clone(), GREEN),
+        ];
 
-        chart.configure_mesh().x_desc("Frequency [Hz]").y_desc("Impedance [Ω]").draw().unwrap();
-        chart.configure_secondary_axes().x_desc("Frequency [Hz]").y_desc("Phase [°]").draw().unwrap();
+        // A lossless line swings between zero and infinite impedance at its
+        // resonances, so keep only three decades either side of Z0
+        let mag_scale = config.mag_scale;
+        let z0_min = z0.iter().map(|(_, m)| *m).fold(f64::INFINITY, f64::min);
+        let z0_max = z0.iter().map(|(_, m)| *m).fold(f64::NEG_INFINITY, f64::max);
+        let mags = curves.iter().flat_map(|(_, sweep, _)| sweep.mags.iter().cloned()).filter(|m| m.is_finite());
+        let min_mag = mags.clone().fold(f64::INFINITY, f64::min).max(1e-3*z0_min);
+        let max_mag = mags.fold(f64::NEG_INFINITY, f64::max).min(1e3*z0_max);
+        error::check_limits(min_mag, max_mag)?;
+        let y_range = config.y_range(min_mag, max_mag);
+        let clip = |m: f64| mag_scale.to_axis(m).clamp(y_range.start, y_range.end);
 
-        let freq_mag_iter = freq_data.clone().into_iter().zip(mag_data);
-        let freq_phase_iter = freq_data.into_iter().zip(phase_data);
+        let shared = SharedX::new(config, config.show_phase);
+        let mut builder = shared.builder(canvas, true);
+        if let Some(caption) = config.caption_or(&format!("Transmission Line into {name}, {} Delay", format_eng(line.delay(), "s", 3))) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder
+            .build_cartesian_2d(shared.range.clone(), y_range.clone())?
+            .set_secondary_coord(shared.range.clone(), config.phase_range.0..config.phase_range.1);
 
-        match impedance_target {
-            Some(res) => {
-                chart.draw_series(AreaSeries::new(
-                        freq_mag_iter,
-                        res,
-                        &YELLOW.mix(0.3)
-                    )
-                    .border_style(&PURPLE))
-                    .unwrap()
-                    .label("Impedance")
-                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
-            },
-            None => {
-                chart.draw_series(LineSeries::new(
-                        freq_mag_iter,
-                        &GREEN
-                    ))
-                    .unwrap()
-                    .label("Impedance")
-                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
-            },
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        let y_formatter = |y: &f64| config.mag_tick(*y, "Ω");
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&y_formatter)
+            .x_desc(&x_label)
+            .y_desc(config.y_label_or(&mag_scale.label("Input Impedance", "Ω")))
+            .draw()?;
+        if config.show_phase {
+            chart.configure_secondary_axes()
+                .x_labels(shared.labels)
+                .x_label_formatter(&x_formatter)
+                .y_desc(config.phase_label_or("Phase [°]"))
+                .draw()?;
+        }
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+
+        // Dotted, like the harmonic markers, so they stay distinct from the data
+        let quarter = line.quarter_wave_freq();
+        let markers = (0..QUARTER_WAVE_MARKERS)
+            .map(|n| (2*n + 1) as f64*quarter)
+            .map(|f| config.freq_to_axis(f))
+            .filter(|x| shared.range.contains(x))
+            .collect::<Vec<f64>>();
+        for (index, x) in markers.iter().enumerate() {
+            let steps = 40;
+            let dash = (y_range.end - y_range.start)/steps as f64;
+            let series = chart.draw_series((0..steps).step_by(2).map(|k| {
+                let y = y_range.start + k as f64*dash;
+                PathElement::new(vec![(*x, y), (*x, y + dash)], ORANGE.mix(0.6))
+            }))?;
+            if index == 0 {
+                series.label(format!("λ/4 = {}", format_freq(quarter)))
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &ORANGE));
+            }
+        }
+
+        chart.draw_series(LineSeries::new(
+                z0.iter().map(|(f, m)| (config.freq_to_axis(*f), clip(*m))),
+                &PURPLE
+            ))?
+            .label("|Z0|")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+        for (label, sweep, color) in curves.iter() {
+            let color = *color;
+            chart.draw_series(LineSeries::new(
+                    sweep.mag_points().filter(|(_, m)| !m.is_nan()).map(|(f, m)| (config.freq_to_axis(f), clip(m))),
+                    &color
+                ))?
+                .label(*label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+        }
+        if config.show_phase {
+            chart.draw_secondary_series(LineSeries::new(
+                    loaded.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(&loaded)),
+                    &GREEN.mix(0.4)
+                ))?
+                .label(format!("{name} Phase"))
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(loaded)
+    }
+
+    /// Plot an impedance model like `plot_with_config` and check the sweep
+    /// for results no passive network can produce, such as a negative
+    /// resistance. Any findings are printed in a warning banner above the
+    /// plot so a broken model is not mistaken for a real one.
+    /// Returns the findings, empty if the model is consistent.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Options for the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_checked.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // The ESR was subtracted by mistake
+    /// let model = sdomain::gen::rl(1e-3, 1e-9) - &sdomain::gen::resistor(5e-3);
+    /// let config = PlotConfig {fmax: 100e6, ..PlotConfig::default()};
+    /// let findings = pdn_plotter::plot_checked(&model, &drawing_area, &config).unwrap();
+    /// assert!(!findings.is_empty());
+    /// ```
+    pub fn plot_checked<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Vec<Finding>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let sweep = config.sweep(model)?;
+        let findings = consistency::check_passive(&sweep);
+        if findings.is_empty() {
+            draw_sweep(canvas, model, &sweep, config)?;
+            return Ok(findings);
+        }
+
+        // The banner takes its own rows so it never covers the caption
+        let (banner, area) = canvas.split_vertically(10 + 20*findings.len() as i32);
+        banner.fill(&RGBColor(255, 235, 235))?;
+        for (index, finding) in findings.iter().enumerate() {
+            banner.draw(&Text::new(
+                format!("Warning: non-physical {finding}"),
+                (10, 5 + 20*index as i32),
+                ("Arial", 15).into_font().color(&RED)
+            ))?;
+        }
+        draw_sweep(&area, model, &sweep, config)?;
+        Ok(findings)
+    }
+
+    /// Plot the self impedance (Z11) at a PDN's excitation point together with
+    /// the transfer impedance (Z21) to an observation point elsewhere on the
+    /// rail. Noise injected at the excitation point appears at the observation
+    /// point scaled by Z21, so this is the curve that matters for a victim
+    /// location.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// The observation point is modeled as a series path from the excitation
+    /// point followed by a shunt impedance to ground:
+    /// ```text
+    ///   excitation ──[ path ]── observation
+    ///       │                       │
+    ///     [PDN]                [observation]
+    ///       │                       │
+    ///      GND                     GND
+    /// ```
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model seen at the excitation point.
+    /// * `path` - Series impedance between the excitation and observation points,
+    ///            e.g. plane spreading or via inductance.
+    /// * `observation` - Shunt impedance to ground at the observation point,
+    ///                   e.g. local decoupling at the victim.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_transfer_impedance.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.1/*Ω*/), ..PlotConfig::default()};
+    /// // 1nH of plane inductance to a victim decoupled by a single 100nF cap
+    /// pdn_plotter::plot_transfer(
+    ///     &pdn,
+    ///     sdomain::gen::rl(1e-3, 1e-9),
+    ///     Capacitor::from(100e-9, "0201").model(),
+    ///     &drawing_area,
+    ///     &config
+    /// ).unwrap();
+    /// ```
+    pub fn plot_transfer<DB: DrawingBackend>(model: &PDNModel, path: Fs, observation: Fs, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let z_pdn = model.model();
+        let z_branch = path + &observation;
+        let z11 = sdomain::parallel(z_pdn, z_branch.clone());
+        let divider = observation / &z_branch;
+
+        let (self_sweep, divider_sweep) = config.check_finite_pair(
+            Sweep::at(&z11, config.sample_freqs()), "Z11",
+            Sweep::at(&divider, config.sample_freqs()), "Z21/Z11"
+        )?;
+        let transfer_sweep = self_sweep.product(&divider_sweep);
+
+        draw_curves(
+            canvas,
+            "Self and Transfer Impedance of PDN",
+            &[("Z11", self_sweep, GREEN), ("Z21", transfer_sweep, BLUE)],
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )
+    }
+
+    /// Plot the crosstalk between two rails coupled through a shared
+    /// impedance: the transfer impedance from aggressor load current to
+    /// victim load voltage, alongside the self impedance each rail sees at
+    /// its own load. Where the transfer curve approaches the victim's self
+    /// impedance, the aggressor's noise reaches the victim nearly as
+    /// strongly as the victim's own.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `coupling` - The shared, aggressor, and victim paths of the two rails.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, labels, and legend groups
+    ///              of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              allowed transfer impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::crosstalk::RailCoupling;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_crosstalk.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // Core and I/O rails sharing a ground via field
+    /// let coupling = RailCoupling {
+    ///     shared: sdomain::gen::rl(0.2e-3, 150e-12),
+    ///     aggressor: sdomain::gen::rl(1e-3, 1e-9),
+    ///     victim: sdomain::gen::rl(1e-3, 1e-9),
+    ///     victim_decap: Capacitor::from(100e-9, "0201").model(),
+    /// };
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(1e-3/*Ω*/), ..PlotConfig::default()};
+    /// pdn_plotter::plot_crosstalk(&coupling, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_crosstalk<DB: DrawingBackend>(coupling: &RailCoupling, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let two_port = coupling.two_port();
+        draw_curves(
+            canvas,
+            "Rail-to-Rail Crosstalk",
+            &[
+                ("Aggressor Z11", config.sweep(&two_port.z11)?, GREEN),
+                ("Victim Z22", config.sweep(&two_port.z22)?, BLUE),
+                ("Crosstalk Z21", config.sweep(&two_port.z21)?, RED),
+            ],
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )
+    }
+
+    /// Plot a PDN's impedance model together with the output impedance of the
+    /// VRM that sources it, showing where the regulator stops controlling the
+    /// rail and the decoupling has to take over.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model built on the VRM.
+    /// * `vrm` - The VRM output impedance used as the source of `model`.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::vrm::BuckVrm;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_vrm_impedance.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let vrm = BuckVrm {
+    ///     bandwidth: 50e3,
+    ///     inductance: 1e-6,
+    ///     inductor_resistance: 5e-3,
+    ///     capacitance: 200e-6,
+    ///     capacitor_esr: 2e-3,
+    /// };
+    /// let mut pdn = PDNModel::from(vrm.model(), None);
+    /// pdn.add_capacitor("0402 10uF", Capacitor::from(10e-6, "0402").model(), 4);
+    /// pdn.add_capacitor("0201 100nF", Capacitor::from(100e-9, "0201").model(), 8);
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.01/*Ω*/), ..PlotConfig::default()};
+    /// pdn_plotter::plot_with_vrm(&pdn, vrm.model(), &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_with_vrm<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, vrm: Fs, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw_curves(
+            canvas,
+            "Impedance of PDN and VRM",
+            &[("VRM", config.sweep(&vrm)?, BLUE), ("PDN", config.sweep(model)?, GREEN)],
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )
+    }
+
+    /// Plot the impedance seen at one node of a ladder PDN, where series
+    /// segments such as plane spreading or via inductance separate the
+    /// capacitor banks. Each segment between the source and the node is also
+    /// shown shorted out, so the gap between that curve and the node's curve
+    /// is the segment's contribution.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `ladder` - A ladder PDN model.
+    /// * `node` - Index of the node at which to observe the impedance.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::ladder::Ladder;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_ladder.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut ladder = Ladder::new(sdomain::gen::rl(5e-3, 1.5e-6));
+    /// ladder.add_shunt("bulk 22uF", Capacitor::from(22e-6, "0603").model());
+    /// ladder.add_segment("plane spreading", sdomain::gen::rl(0.5e-3, 200e-12));
+    /// ladder.add_shunt("local 100nF", Capacitor::from(100e-9, "0201").model());
+    /// ladder.add_segment("BGA vias", sdomain::gen::rl(1e-3, 100e-12));
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_ladder(&ladder, 2, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_ladder<DB: DrawingBackend>(ladder: &Ladder, node: usize, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let mut curves = vec![(format!("Node {node}"), config.sweep(&ladder.impedance_at(node))?, GREEN)];
+        for (index, current) in ladder.nodes().iter().enumerate().take(node + 1).skip(1) {
+            if let Some((name, _)) = &current.segment {
+                curves.push((
+                    format!("without {name}"),
+                    config.sweep(&ladder.without_segment(index).impedance_at(node))?,
+                    palette(index)
+                ));
+            }
         }
-        chart.draw_secondary_series(LineSeries::new(
-                freq_phase_iter,
-                &RED.mix(0.4)
-            ))
-            .unwrap()
-            .label("Phase")
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+        let curves = curves.iter()
+            .map(|(name, sweep, color)| (name.as_str(), sweep.clone(), *color))
+            .collect::<Vec<_>>();
 
+        draw_curves(
+            canvas,
+            &format!("Impedance at Node {node}"),
+            &curves,
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )
+    }
 
+    /// Plot the impedance looking into the die of a hierarchical PDN, such as
+    /// VRM → board → package → die, as cumulative curves: first the last
+    /// level alone, then with each level further out added in turn, until
+    /// the source is included. The background is shaded in the color of the
+    /// level that dominates each frequency range, i.e. the outermost level
+    /// needed before the curve settles onto the full PDN's impedance.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `ladder` - A ladder PDN whose last node is the die.
+    /// * `level_names` - Names for the source followed by each node, e.g.
+    ///                   `["VRM", "Board", "Package", "Die"]`.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::ladder::Ladder;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_hierarchy.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut ladder = Ladder::new(sdomain::gen::rl(1e-3, 0.5e-6));
+    /// ladder.add_shunt("board bulk", Capacitor::from(22e-6, "0603").model());
+    /// ladder.add_segment("BGA", sdomain::gen::rl(0.2e-3, 50e-12));
+    /// ladder.add_shunt("package caps", Capacitor::from(1e-6, "0201").model());
+    /// ladder.add_segment("bumps", sdomain::gen::rl(0.1e-3, 5e-12));
+    /// ladder.add_shunt("on-die", sdomain::gen::rcl(1e-3, 100e-9, 1e-12));
+    ///
+    /// let config = PlotConfig {fmax: 100e6, ..PlotConfig::default()};
+    /// pdn_plotter::plot_hierarchy(&ladder, &["VRM", "Board", "Package", "Die"], &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_hierarchy<DB: DrawingBackend>(ladder: &Ladder, level_names: &[&str], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let node_count = ladder.nodes().len();
+        let level_name = |level: usize| match level_names.get(level) {
+            Some(name) => name.to_string(),
+            None if level == 0 => "Source".to_string(),
+            None => format!("Node {}", level - 1),
+        };
+
+        // Levels are added from the die outward; the final step adds the source
+        let mut curves = vec![];
+        for count in 1..=node_count + 1 {
+            let model = match ladder.impedance_from_end(count.min(node_count), count > node_count) {
+                Some(model) => model,
+                None => continue,
+            };
+            let outermost = node_count + 1 - count;
+            let name = if count == 1 {level_name(outermost)} else {format!("+ {}", level_name(outermost))};
+            curves.push((name, config.sweep(&model)?, palette(count)));
+        }
+
+        let spans = match curves.last() {
+            Some((_, full, _)) => {
+                let dominant = full.mags.iter().enumerate()
+                    .map(|(i, z_full)| {
+                        curves.iter().position(|(_, sweep, _)| sweep.mags[i] <= 1.12*z_full).unwrap_or(curves.len() - 1)
+                    })
+                    .collect::<Vec<usize>>();
+                full.freqs.windows(2).zip(dominant.iter())
+                    .map(|(f, level)| (f[0], f[1], curves[*level].2))
+                    .collect::<Vec<(f64, f64, RGBColor)>>()
+            },
+            None => vec![],
+        };
+
+        let curves = curves.iter()
+            .map(|(name, sweep, color)| (name.as_str(), sweep.clone(), *color))
+            .collect::<Vec<_>>();
+        draw_curves(
+            canvas,
+            "Cumulative Impedance Looking From the Die",
+            &curves,
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands {spans, legend: vec![]},
+            config
+        )
+    }
+
+    /// Plot the same PDN with ideally mounted capacitors and with each
+    /// capacitor's mounting inductance included, showing how much the
+    /// layout costs. Each capacitor's self-resonant frequency with and
+    /// without mounting is listed in a table beside the plot.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `source` - Impedance of the source feeding the PDN, e.g. a VRM model.
+    /// * `capacitors` - Capacitors placed in the PDN with their mounting inductance.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the plot and the table.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::mounting::MountedCapacitor;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_mounting.png", (1280, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let capacitors = vec![
+    ///     MountedCapacitor {
+    ///         name: "0603 22uF".to_string(),
+    ///         model: Capacitor::from(22e-6, "0603").model(),
+    ///         count: 2,
+    ///         mounting_inductance: 1e-9,
+    ///     },
+    ///     MountedCapacitor {
+    ///         name: "0201 100nF".to_string(),
+    ///         model: Capacitor::from(100e-9, "0201").model(),
+    ///         count: 8,
+    ///         mounting_inductance: 0.6e-9,
+    ///     },
+    /// ];
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_mounting(&sdomain::gen::rl(5e-3, 1.5e-6), &capacitors, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_mounting<DB: DrawingBackend>(source: &Fs, capacitors: &[MountedCapacitor], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let ideal = mounting::build_pdn(source, capacitors, false);
+        let realistic = mounting::build_pdn(source, capacitors, true);
+        let (left, right) = canvas.split_horizontally((70).percent_width());
+        draw_curves(
+            &left,
+            "Impedance of PDN with Mounting Inductance",
+            &[("Ideal mounting", config.sweep(&ideal.model())?, BLUE), ("With mounting", config.sweep(&realistic.model())?, GREEN)],
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )?;
+
+        let (fmin, fmax) = config.freq_limits_hz();
+        let rows = capacitors.iter()
+            .map(|cap| {
+                let ideal_srf = resonance(&Sweep::checked(&cap.model, fmin, 10.0*fmax)?);
+                let mounted_srf = resonance(&Sweep::checked(&cap.mounted_model(), fmin, 10.0*fmax)?);
+                Ok(vec![
+                    cap.name.clone(),
+                    format!("+{:.2} nH", cap.mounting_inductance*1e9),
+                    format_freq(ideal_srf),
+                    format_freq(mounted_srf),
+                ])
+            })
+            .collect::<Result<Vec<Vec<String>>, PlotError>>()?;
+        draw_table(&right, "Mounting", &["Name", "Mount", "SRF", "Mounted"], &rows)
+    }
+
+    /// Plot a PDN's impedance with the background of each frequency range
+    /// shaded by the capacitor bank that has the lowest impedance there,
+    /// showing at a glance which component owns each decade.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model.
+    /// * `banks` - The name, single-part impedance model, and count of each
+    ///             bank to consider. The source can be included as a bank
+    ///             with a count of 1.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_owners.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let source = sdomain::gen::rl(5e-3, 1.5e-6);
+    /// let bulk = Capacitor::from(22e-6, "0603").model();
+    /// let hf = Capacitor::from(100e-9, "0201").model();
+    /// let mut pdn = PDNModel::from(source.clone(), None);
+    /// pdn.add_capacitor("0603 22uF", bulk.clone(), 2);
+    /// pdn.add_capacitor("0201 100nF", hf.clone(), 8);
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_owners(
+    ///     &pdn,
+    ///     &[("VRM", source, 1), ("0603 22uF", bulk, 2), ("0201 100nF", hf, 8)],
+    ///     &drawing_area,
+    ///     &config
+    /// ).unwrap();
+    /// ```
+    pub fn plot_owners<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, banks: &[(&str, Fs, usize)], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let pdn_sweep = config.sweep(model)?;
+        let bank_mags = banks.iter()
+            .map(|(_, bank, count)| Ok(config.sweep(bank)?.mags.into_iter().map(|z| z/(*count).max(1) as f64).collect::<Vec<f64>>()))
+            .collect::<Result<Vec<Vec<f64>>, PlotError>>()?;
+
+        let owners = (0..pdn_sweep.freqs.len())
+            .map(|i| {
+                (0..banks.len())
+                    .min_by(|a, b| bank_mags[*a][i].total_cmp(&bank_mags[*b][i]))
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<usize>>();
+        let bands = Bands {
+            spans: pdn_sweep.freqs.windows(2).zip(owners.iter())
+                .map(|(f, owner)| (f[0], f[1], palette(*owner + 1)))
+                .collect(),
+            legend: banks.iter().enumerate()
+                .map(|(index, (name, _, _))| (name.to_string(), palette(index + 1)))
+                .collect(),
+        };
+
+        draw_curves(
+            canvas,
+            "Impedance of PDN by Owning Bank",
+            &[("PDN", pdn_sweep, GREEN)],
+            config.target.map(TargetMask::flat).as_ref(),
+            &bands,
+            config
+        )
+    }
+
+    /// Plot a PDN's impedance together with the impedance of each capacitor
+    /// bank on its own, i.e. one part's impedance divided by the count. With
+    /// many banks the legend can be grouped, e.g. into bulk, MLCC, and HF
+    /// parts, and ordered explicitly instead of following `banks`, by the
+    /// configuration's `legend` groups naming the banks.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model.
+    /// * `banks` - The name, single-part impedance model, and count of each
+    ///             bank, as for `plot_owners`.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, labels, and legend groups
+    ///              of the plot. If `target` is set, a horizontal line is
+    ///              drawn at the target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::legend::LegendGroups;
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_banks.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let banks = [
+    ///     ("0201 100nF", Capacitor::from(100e-9, "0201").model(), 8),
+    ///     ("1206 100uF", Capacitor::from(100e-6, "1206").model(), 1),
+    ///     ("0402 1uF", Capacitor::from(1e-6, "0402").model(), 4),
+    ///     ("0603 22uF", Capacitor::from(22e-6, "0603").model(), 2),
+    /// ];
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-6), None);
+    /// for (name, model, count) in banks.iter() {
+    ///     pdn.add_capacitor(name, model.clone(), *count);
+    /// }
+    ///
+    /// let config = PlotConfig {
+    ///     fmax: 100e6,
+    ///     target: Some(0.05),
+    ///     legend: LegendGroups::new()
+    ///         .group("Bulk", &["1206 100uF", "0603 22uF"])
+    ///         .group("MLCC", &["0402 1uF"])
+    ///         .group("HF", &["0201 100nF"]),
+    ///     ..PlotConfig::default()
+    /// };
+    /// pdn_plotter::plot_banks(&pdn, &banks, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_banks<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, banks: &[(&str, Fs, usize)], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let labels = banks.iter()
+            .map(|(name, _, count)| format!("{count} × {name}"))
+            .collect::<Vec<String>>();
+        let mut curves = vec![("PDN", config.sweep(model)?, GREEN)];
+        for (index, ((_, bank, count), label)) in banks.iter().zip(labels.iter()).enumerate() {
+            let mut sweep = config.sweep(bank)?;
+            sweep.mags.iter_mut().for_each(|z| *z /= (*count).max(1) as f64);
+            curves.push((label.as_str(), sweep, palette(index + 1)));
+        }
+
+        // The groups name banks, while the curves are labeled with the count
+        let label_of = |member: &String| banks.iter()
+            .position(|(name, _, _)| *name == member.as_str())
+            .map_or_else(|| member.clone(), |index| labels[index].clone());
+        let legend = LegendGroups {
+            groups: config.legend.groups.iter()
+                .map(|(header, members)| (header.clone(), members.iter().map(&label_of).collect()))
+                .collect(),
+        };
+
+        draw_curves(
+            canvas,
+            "Impedance of PDN and Capacitor Banks",
+            &curves,
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            &PlotConfig {legend, ..config.clone()}
+        )
+    }
+
+    /// Plot a gallery of a PDN's capacitor banks: a large panel with the
+    /// combined impedance and every bank as in `plot_banks`, and under it a
+    /// grid of small panels, one per bank, each showing a single part's
+    /// impedance with its self-resonant frequency marked. The small panels
+    /// are captioned with the part and the number fitted, e.g.
+    /// "1 × 0402 1uF (n = 4)".
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model.
+    /// * `banks` - The name, single-part impedance model, and count of each
+    ///             bank, as for `plot_owners`.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the panels.
+    /// * `config` - Frequency range and axes of every panel, and the caption,
+    ///              labels, and legend groups of the combined panel. If
+    ///              `target` is set, a horizontal line is drawn at the
+    ///              target impedance on the combined panel.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_gallery.png", (1280, 1280))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let banks = [
+    ///     ("1206 100uF", Capacitor::from(100e-6, "1206").model(), 1),
+    ///     ("0603 22uF", Capacitor::from(22e-6, "0603").model(), 2),
+    ///     ("0402 1uF", Capacitor::from(1e-6, "0402").model(), 4),
+    ///     ("0201 100nF", Capacitor::from(100e-9, "0201").model(), 8),
+    /// ];
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-6), None);
+    /// for (name, model, count) in banks.iter() {
+    ///     pdn.add_capacitor(name, model.clone(), *count);
+    /// }
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_gallery(&pdn, &banks, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_gallery<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, banks: &[(&str, Fs, usize)], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if banks.is_empty() {return Err(PlotError::Empty.into());}
+        const COLUMNS: usize = 4;
+        let columns = banks.len().min(COLUMNS);
+        let rows = banks.len().div_ceil(columns);
+        // The combined panel keeps at least 60% of the height
+        let grid_share = (0.2*rows as f64).min(0.4);
+        let (_, height) = canvas.dim_in_pixel();
+        let (combined, grid) = canvas.split_vertically(((1.0 - grid_share)*height as f64) as i32);
+
+        plot_banks(model, banks, &combined, config)?;
+
+        let mag_scale = config.mag_scale;
+        let cells = grid.split_evenly((rows, columns));
+        for (index, ((name, bank, count), cell)) in banks.iter().zip(cells.iter()).enumerate() {
+            let sweep = config.sweep(bank)?;
+            error::check_limits(sweep.min_mag(), sweep.max_mag())?;
+            let srf = config.freq_to_axis(resonance(&sweep));
+            let y_range = config.y_range(sweep.min_mag(), sweep.max_mag());
+            let color = palette(index + 1);
+
+            let mut chart = ChartBuilder::on(cell)
+            .caption(format!("1 × {name} (n = {count})"), ("Arial", 15))
+                .set_label_area_size(LabelAreaPosition::Left, 40)
+                .set_label_area_size(LabelAreaPosition::Bottom, 25)
+                .margin(5)
+                .build_cartesian_2d(config.freq_range(), y_range.clone())?;
+
+            chart.configure_mesh()
+                .x_labels(4)
+                .y_labels(4)
+                .x_label_formatter(&|x| config.freq_tick(*x))
+                .y_label_formatter(&|y| config.mag_tick(*y, "Ω"))
+                .draw()?;
+
+            chart.draw_series(LineSeries::new(
+                    sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                    &color
+                ))?;
+            chart.draw_series(LineSeries::new(
+                    vec![(srf, y_range.start), (srf, y_range.end)],
+                    BLACK.mix(0.5).stroke_width(1)
+                ))?;
+            chart.draw_series(std::iter::once(Text::new(
+                    format!("SRF {}", config.freq_tick(srf)),
+                    (srf, y_range.end - 0.1*(y_range.end - y_range.start)),
+                    ("Arial", 13)
+                )))?;
+        }
+
+        Ok(())
+    }
+
+    /// Plot a PDN's impedance with a report panel beside it listing every
+    /// anti-resonance found in the sweep: its frequency, peak impedance, Q,
+    /// and the ESR that would critically damp it.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the plot and the report.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_report.png", (1280, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+    /// pdn.add_capacitor("0201 100nF", Capacitor::from(100e-9, "0201").model(), 3);
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.1), ..PlotConfig::default()};
+    /// pdn_plotter::plot_with_report(&pdn, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_with_report<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let sweep = config.sweep(model)?;
+        let peaks = resonance::find_anti_resonances(&sweep);
+
+        let (left, right) = canvas.split_horizontally((70).percent_width());
+        draw_curves(
+            &left,
+            "Impedance of PDN",
+            &[("PDN", sweep, GREEN)],
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )?;
+
+        let rows = peaks.iter()
+            .map(|peak| vec![format_freq(peak.freq), format_ohms(peak.peak), format!("{:.1}", peak.q), format_ohms(peak.damping_esr)])
+            .collect::<Vec<Vec<String>>>();
+        draw_table(&right, "Anti-resonances", &["Frequency", "|Z|", "Q", "Damp ESR"], &rows)?;
+
+        Ok(())
+    }
+
+    /// Plot a PDN's impedance with its decoupling bill of materials drawn as
+    /// a table in the margin, so the image alone documents the design shown.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model.
+    /// * `bom` - The capacitors placed in `model`.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the plot and the table.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::bom::{self, Decap};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_bom.png", (1280, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let decaps = vec![
+    ///     Decap {name: "C1".to_string(), value: 22e-6, package: "0603".to_string(), count: 2},
+    ///     Decap {name: "C2".to_string(), value: 100e-9, package: "0201".to_string(), count: 8},
+    /// ];
+    /// let pdn = bom::build_pdn(&sdomain::gen::rl(5e-3, 1.5e-6), &decaps);
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_with_bom(&pdn, &decaps, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_with_bom<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, bom: &[Decap], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let (left, right) = canvas.split_horizontally((70).percent_width());
+        draw_curves(
+            &left,
+            "Impedance of PDN",
+            &[("PDN", config.sweep(model)?, GREEN)],
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )?;
+
+        let rows = bom.iter()
+            .map(|decap| vec![decap.name.clone(), format_farads(decap.value), decap.package.clone(), decap.count.to_string(), format_freq(decap.srf())])
+            .collect::<Vec<Vec<String>>>();
+        draw_table(&right, "Decoupling BOM", &["Name", "Value", "Package", "Qty", "SRF"], &rows)
+    }
+
+    /// Plot several supply rails as a column of panels, one per rail, with the
+    /// same frequency axis and styling so the rails can be compared directly.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `rails` - The rails to plot, from top to bottom.
+    /// * `canvas` - A Plotter's DrawingArea to divide among the rails.
+    /// * `config` - Frequency range, axes, and labels shared by the panels.
+    ///              Its `target` is used for rails without their own.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter::{self, Rail};
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_rails.png", (960, 1440))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut core = PDNModel::from(sdomain::gen::rl(1e-3, 0.5e-6), None);
+    /// core.add_capacitor("0402 10uF", Capacitor::from(10e-6, "0402").model(), 8);
+    /// let mut io = PDNModel::from(sdomain::gen::rl(10e-3, 1.5e-6), None);
+    /// io.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 2);
+    ///
+    /// pdn_plotter::plot_rails(&[
+    ///     Rail {name: "1V0 Core", model: &core, impedance_target: Some(0.005)},
+    ///     Rail {name: "1V8 IO", model: &io, impedance_target: Some(0.05)},
+    /// ], &drawing_area, &PlotConfig {fmax: 100e6, ..PlotConfig::default()}).unwrap();
+    /// ```
+    pub fn plot_rails<DB: DrawingBackend>(rails: &[Rail], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if rails.is_empty() {return Err(PlotError::Empty.into());}
+        let panels = canvas.split_evenly((rails.len(), 1));
+        for (rail, panel) in rails.iter().zip(panels.iter()) {
+            draw_curves(
+                panel,
+                &format!("Impedance of {}", rail.name),
+                &[(rail.name, config.sweep(rail.model)?, GREEN)],
+                rail.impedance_target.or(config.target).map(TargetMask::flat).as_ref(),
+                &Bands::default(),
+                config
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Plot a family of named impedance models on one chart, such as a
+    /// sweep of capacitor values or quantities. Each model gets a legend
+    /// entry and a color along `gradient` in the order it is yielded, so
+    /// neighboring members of the family get neighboring colors. To color
+    /// by a parameter value with a color bar, use `plot_family_gradient`.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `models` - (name, model) pairs to plot.
+    /// * `gradient` - Colors to assign from the first to the last model.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::gradient::Gradient;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_family.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let values = [100e-9, 1e-6, 10e-6];
+    /// let config = PlotConfig {fmax: 100e6, ..PlotConfig::default()};
+    /// pdn_plotter::plot_family(
+    ///     values.iter().map(|c| {
+    ///         let cap = Capacitor::from(*c, "0402");
+    ///         (format!("{cap}"), cap.model())
+    ///     }),
+    ///     &Gradient::Viridis,
+    ///     &drawing_area,
+    ///     &config
+    /// ).unwrap();
+    /// ```
+    pub fn plot_family<DB: DrawingBackend>(models: impl IntoIterator<Item = (String, Fs)>, gradient: &Gradient, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let sweeps = models.into_iter()
+            .map(|(name, model)| Ok((name, config.sweep(&model)?)))
+            .collect::<Result<Vec<(String, Sweep)>, PlotError>>()?;
+        let curves = sweeps.iter()
+            .zip(gradient.spread(sweeps.len()))
+            .map(|((name, sweep), color)| (name.as_str(), sweep.clone(), color))
+            .collect::<Vec<(&str, Sweep, RGBColor)>>();
+        draw_curves(
+            canvas,
+            "Impedance Comparison",
+            &curves,
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )
+    }
+
+    /// Plot a family of related models, such as a sweep of capacitor counts
+    /// or a set of temperatures, coloring each curve along `gradient` by its
+    /// parameter value. A color bar beside the chart maps colors to values,
+    /// which stays readable for families too large for a legend.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `models` - Parameter value and impedance of each family member.
+    /// * `parameter` - Name and unit of the parameter for the color bar, e.g.
+    ///                 "Count" or "Temperature [°C]".
+    /// * `gradient` - Colors to assign from the lowest to the highest value.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plot.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::gradient::Gradient;
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_family_gradient.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let family = (1..=12).map(|count| {
+    ///     let mut pdn = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-6), None);
+    ///     pdn.add_capacitor("0402 1uF", Capacitor::from(1e-6, "0402").model(), count);
+    ///     (count as f64, pdn.model())
+    /// });
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_family_gradient(family, "Count", &Gradient::Viridis, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_family_gradient<DB: DrawingBackend>(models: impl IntoIterator<Item = (f64, Fs)>, parameter: &str, gradient: &Gradient, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let sweeps = models.into_iter()
+            .map(|(value, model)| Ok((value, config.sweep(&model)?)))
+            .collect::<Result<Vec<(f64, Sweep)>, PlotError>>()?;
+        if sweeps.is_empty() {return Err(PlotError::Empty.into());}
+        let min_value = sweeps.iter().map(|(value, _)| *value).fold(f64::INFINITY, f64::min);
+        let max_value = sweeps.iter().map(|(value, _)| *value).fold(f64::NEG_INFINITY, f64::max);
+        let position = |value: f64| if max_value > min_value {(value - min_value)/(max_value - min_value)} else {0.0};
+
+        let target = config.target;
+        let min_mag = sweeps.iter().map(|(_, sweep)| sweep.min_mag()).fold(target.unwrap_or(f64::INFINITY), f64::min);
+        let max_mag = sweeps.iter().map(|(_, sweep)| sweep.max_mag()).fold(target.unwrap_or(f64::NEG_INFINITY), f64::max);
+        error::check_limits(min_mag, max_mag)?;
+        let mag_scale = config.mag_scale;
+        let y_range = config.y_range(min_mag, max_mag);
+
+        let (width, _) = canvas.dim_in_pixel();
+        let (plot_area, bar_area) = canvas.split_horizontally(width.saturating_sub(110));
+
+        let shared = SharedX::new(config, false);
+        let mut builder = shared.builder(&plot_area, true);
+        if let Some(caption) = config.caption_or("Impedance Family") {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&|x| config.freq_tick(*x))
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&|y| config.mag_tick(*y, "Ω"))
+            .x_desc(config.x_label())
+            .y_desc(config.y_label_or(&mag_scale.label("Impedance", "Ω")))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+        if let Some(target) = target {
+            chart.draw_series(LineSeries::new(
+                    vec![(shared.range.start, mag_scale.to_axis(target)), (shared.range.end, mag_scale.to_axis(target))],
+                    &PURPLE
+                ))?
+                .label("Target")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+        }
+        for (value, sweep) in sweeps.iter() {
+            chart.draw_series(LineSeries::new(
+                    sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                    &gradient.at(position(*value))
+                ))?;
+        }
+        if target.is_some() || !config.bands.is_empty() {
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        }
+
+        // Color bar, highest value at the top
+        let mut bar = ChartBuilder::on(&bar_area)
+            .set_label_area_size(LabelAreaPosition::Right, 60)
+            .margin_top(50)
+            .margin_bottom(50)
+            .margin_left(10)
+            .build_cartesian_2d(0.0..1.0, min_value..max_value.max(min_value + f64::EPSILON))?;
+        bar.configure_mesh()
+            .disable_x_mesh()
+            .disable_x_axis()
+            .disable_y_mesh()
+            .y_desc(parameter)
+            .y_label_formatter(&|v| format_plain(*v))
+            .draw()?;
+        const STEPS: usize = 100;
+        let span = max_value - min_value;
+        bar.draw_series((0..STEPS).map(|step| {
+                let (low, high) = (step as f64/STEPS as f64, (step + 1) as f64/STEPS as f64);
+                Rectangle::new(
+                    [(0.0, min_value + low*span), (1.0, min_value + high*span)],
+                    gradient.at(low).filled()
+                )
+            }))?;
+
+        Ok(())
+    }
+
+    /// Overlay successive revisions of the same PDN, labeled "rev A", "rev
+    /// B", and so on in the order given, with a changelog beside the plot
+    /// listing what changed in each revision that describes it.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `revisions` - Each revision's model and, optionally, a note on
+    ///                 what changed from the revision before it.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the plot and the changelog.
+    /// * `config` - Frequency range, axes, caption, labels, and legend groups
+    ///              of the plot.
+    ///              If `target` is set, a horizontal line is drawn at the
+    ///              target impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_revisions.png", (1280, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut rev_a = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-6), None);
+    /// rev_a.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 2);
+    /// let mut rev_b = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-6), None);
+    /// rev_b.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 2);
+    /// rev_b.add_capacitor("0201 100nF", Capacitor::from(100e-9, "0201").model(), 4);
+    ///
+    /// let config = PlotConfig {fmax: 100e6, target: Some(0.05), ..PlotConfig::default()};
+    /// pdn_plotter::plot_revisions(&[
+    ///     (&rev_a, None),
+    ///     (&rev_b, Some("added 4 × 0201 100nF")),
+    /// ], &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_revisions<DB: DrawingBackend>(revisions: &[(&dyn Plottable, Option<&str>)], canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let labels = (0..revisions.len()).map(revision_label).collect::<Vec<String>>();
+        let curves = revisions.iter().zip(labels.iter()).enumerate()
+            .map(|(index, ((model, _), label))| Ok((label.as_str(), config.sweep(*model)?, palette(index + 1))))
+            .collect::<Result<Vec<(&str, Sweep, RGBColor)>, PlotError>>()?;
+        let (left, right) = canvas.split_horizontally((70).percent_width());
+        draw_curves(
+            &left,
+            "Impedance of PDN by Revision",
+            &curves,
+            config.target.map(TargetMask::flat).as_ref(),
+            &Bands::default(),
+            config
+        )?;
+
+        let changelog = revisions.iter().zip(labels.iter())
+            .filter_map(|((_, change), label)| change.map(|change| vec![label.clone(), change.to_string()]))
+            .collect::<Vec<Vec<String>>>();
+        draw_table(&right, "Changelog", &["Rev", "Change"], &changelog)
+    }
+
+    /// Plot the result of a decoupling optimization against the design it
+    /// started from, with the chosen capacitors listed beside the plot.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `start` - The PDN before optimization.
+    /// * `result` - The optimization result from `optimizer::optimize`.
+    /// * `mask` - The impedance target the optimization was run against.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the plot and the parts list.
+    /// * `config` - Frequency range, axes, caption, and labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::optimizer;
+    /// use sdomain_test_plotters::target::TargetMask;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_optimized.png", (1280, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let source = sdomain::gen::rl(5e-3, 1.5e-6);
+    /// let library = vec![
+    ///     Capacitor::from(22e-6, "0603"),
+    ///     Capacitor::from(1e-6, "0402"),
+    ///     Capacitor::from(100e-9, "0201"),
+    /// ];
+    /// let mask = TargetMask::from(vec![(1e3, 0.05), (1e6, 0.05), (100e6, 0.5)]);
+    /// let result = optimizer::optimize(&source, vec![], &library, &mask, 30);
+    /// let start = optimizer::build_pdn(&source, &[]);
+    /// let config = PlotConfig {fmin: 1e3, fmax: 100e6, ..PlotConfig::default()};
+    /// pdn_plotter::plot_optimization(&start, &result, &mask, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_optimization<DB: DrawingBackend>(start: &PDNModel, result: &Optimization, mask: &TargetMask, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let (left, right) = canvas.split_horizontally((70).percent_width());
+        draw_curves(
+            &left,
+            "Optimized Decoupling of PDN",
+            &[("Start", config.sweep(&start.model())?, BLUE), ("Optimized", config.sweep(&result.model.model())?, GREEN)],
+            Some(mask),
+            &Bands::default(),
+            config
+        )?;
+
+        let mut rows = result.bom.iter()
+            .map(|entry| vec![entry.count.to_string(), entry.capacitor.to_string()])
+            .collect::<Vec<Vec<String>>>();
+        rows.push(vec![result.component_count().to_string(), "parts in total".to_string()]);
+        rows.push(vec![
+            format!("{:.2}", result.worst_ratio),
+            format!("worst |Z|/target{}", if result.meets_target() {""} else {" (FAILS)"}),
+        ]);
+        draw_table(&right, "Chosen Capacitors", &["Qty", "Capacitor"], &rows)
+    }
+
+    /// Plot the lowest peak PDN impedance the optimizer can reach against the
+    /// number of capacitors it is allowed to use, giving a cost versus
+    /// performance curve for choosing a component budget.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `source` - Impedance of the source feeding the PDN, e.g. a VRM model.
+    /// * `library` - Capacitors the optimizer may choose from.
+    /// * `max_budget` - Largest total number of capacitors to consider.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the trade-off plot.
+    /// * `config` - Magnitude axis, caption, and labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_tradeoff.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let library = vec![
+    ///     Capacitor::from(22e-6, "0603"),
+    ///     Capacitor::from(1e-6, "0402"),
+    ///     Capacitor::from(100e-9, "0201"),
+    /// ];
+    /// let config = PlotConfig::default();
+    /// pdn_plotter::plot_tradeoff(&sdomain::gen::rl(5e-3, 1.5e-6), &library, 20, &drawing_area, &config).unwrap();
+    ///
+    /// // Without capacitors to choose from there is no curve to draw
+    /// assert!(pdn_plotter::plot_tradeoff(&sdomain::gen::rl(5e-3, 1.5e-6), &[], 20, &drawing_area, &config).is_err());
+    /// ```
+    pub fn plot_tradeoff<DB: DrawingBackend>(source: &Fs, library: &[Capacitor], max_budget: usize, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let curve = optimizer::tradeoff(source, library, max_budget);
+        if curve.is_empty() {return Err(PlotError::Empty.into());}
+        let mag_scale = config.mag_scale;
+        let points = curve.iter().map(|(count, peak)| (*count as f64, mag_scale.to_axis(*peak))).collect::<Vec<(f64, f64)>>();
+        let min_peak = curve.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max_peak = curve.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        error::check_limits(min_peak, max_peak)?;
+        // A zero peak has no place on a log or dB axis
+        let y_range = config.y_range(min_peak, max_peak);
+        error::check_limits(y_range.start, y_range.end)?;
+
+        let mut builder = ChartBuilder::on(canvas);
+        if let Some(caption) = config.caption_or("Component Count vs Peak Impedance") {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(0.0..max_budget as f64 + 1.0, y_range.clone())?;
+
+        chart.configure_mesh()
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&|y| config.mag_tick(*y, "Ω"))
+            .x_desc("Capacitor Count")
+            .y_desc(config.y_label_or(&mag_scale.label("Peak Impedance", "Ω")))
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(
+                points.iter().cloned(),
+                &GREEN
+            ))?;
+        chart.draw_series(points.iter().map(|p| Circle::new(*p, 3, GREEN.filled())))?;
+
+        Ok(())
+    }
+
+    /// Plot a review dashboard of a PDN on one canvas: the impedance against
+    /// its target mask across the top, and below it the margin to the mask
+    /// over frequency with the compliance verdict, next to the supply
+    /// voltage response to a load current step.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model. Its display name is
+    ///             printed in the dashboard title.
+    /// * `mask` - The impedance target.
+    /// * `i_step` - Size of the load current step in amps.
+    /// * `rise_time` - Duration of the current edge in seconds.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the panels.
+    /// * `config` - Frequency range, axes, labels, and legend groups of the
+    ///              panels, and the dashboard caption. Hiding the caption
+    ///              hides the panel captions too.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::target::TargetMask;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_dashboard.png", (1280, 960))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    /// pdn.add_capacitor("0201 100nF", Capacitor::from(100e-9, "0201").model(), 8);
+    ///
+    /// let mask = TargetMask::from(vec![(1e3, 0.05), (1e6, 0.05), (100e6, 0.5)]);
+    /// let config = PlotConfig {fmin: 1e3, fmax: 100e6, ..PlotConfig::default()};
+    /// pdn_plotter::plot_dashboard(&pdn, &mask, 1.0, 100e-9, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_dashboard<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, mask: &TargetMask, i_step: f64, rise_time: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let sweep = config.sweep(model)?;
+        let area = match config.caption_or(&format!("PDN Review: {}", model.display_name())) {
+            Some(caption) => canvas.titled(&caption, ("Arial", 30))?,
+            None => canvas.clone(),
+        };
+        let (upper, lower) = area.split_vertically((50).percent_height());
+        let (margin_area, transient_area) = lower.split_horizontally((50).percent_width());
+
+        // The caption titles the whole dashboard, so the panels keep their own
+        // unless captions are hidden altogether
+        let caption = match config.caption {
+            Caption::Hidden => Caption::Hidden,
+            _ => Caption::Default,
+        };
+        let panel = PlotConfig {caption, ..config.clone()};
+        draw_curves(&upper, "Impedance vs Target", &[("PDN", sweep.clone(), GREEN)], Some(mask), &Bands::default(), &panel)?;
+        draw_margin(&margin_area, &sweep, mask, &panel)?;
+        pdn_transient_plotter::plot_load_step(model, i_step, rise_time, &transient_area, &panel)?;
+
+        Ok(())
+    }
+
+    /// Plot the margin of an impedance sweep below a target mask in dB, with
+    /// the frequencies over the target shaded and the verdict of the
+    /// compliance check printed above the curve.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `area` - A Plotter's DrawingArea on which to draw the margin plot.
+    /// * `sweep` - Impedance of the PDN.
+    /// * `mask` - The impedance target.
+    /// * `config` - Options of the panel, for the caption, frequency axis,
+    ///              and shaded bands.
+    fn draw_margin<DB: DrawingBackend>(area: &DrawAreaType<DB>, sweep: &Sweep, mask: &TargetMask, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let margins = sweep.mag_points()
+            .map(|(f, m)| (config.freq_to_axis(f), 20.0*(mask.limit_at(f)/m).log10()))
+            .collect::<Vec<(f64, f64)>>();
+        let min_margin = margins.iter().map(|p| p.1).fold(0.0, f64::min) - 10.0;
+        let max_margin = margins.iter().map(|p| p.1).fold(0.0, f64::max) + 10.0;
+        error::check_limits(min_margin, max_margin)?;
+
+        let shared = SharedX::new(config, false);
+        let mut builder = shared.builder(area, true);
+        if let Some(caption) = config.caption_or("Margin to Target") {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), min_margin..max_margin)?;
+
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&|x| config.freq_tick(*x))
+            .x_desc(config.x_label())
+            .y_desc("Margin [dB]")
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+
+        chart.draw_series(
+                margins.windows(2)
+                    .filter(|pair| pair[0].1 < 0.0 || pair[1].1 < 0.0)
+                    .map(|pair| Rectangle::new([(pair[0].0, min_margin), (pair[1].0, max_margin)], RED.mix(0.2).filled()))
+            )?
+            .label("Over Target")
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], RED.mix(0.2).filled()));
+        chart.draw_series(LineSeries::new(
+                vec![(shared.range.start, 0.0), (shared.range.end, 0.0)],
+                &PURPLE
+            ))?
+            .label("Target")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+        chart.draw_series(LineSeries::new(
+                margins.iter().cloned(),
+                &GREEN
+            ))?
+            .label("Margin")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+        let verdict = match compliance::check_sweep(sweep, mask) {
+            Ok(tightest) => format!("PASS: {:.1} dB least margin at {}", tightest.margin_db(), format_freq(tightest.freq)),
+            Err(violation) => format!(
+                "FAIL: {:.1} dB over at {}, {} band(s) over",
+                -violation.worst.margin_db(), format_freq(violation.worst.freq), violation.bands.len()
+            ),
+        };
+        chart.draw_series(std::iter::once(Text::new(verdict, (shared.range.start, max_margin - 3.0), ("Arial", 15))))?;
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(())
+    }
+
+    /// Plot several impedance sweeps on the same axes. With `show_phase`
+    /// set, each curve's phase is drawn on the secondary axis in a lighter
+    /// shade of its color.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `title` - Text to print as the plot title unless the configuration
+    ///             sets its own caption.
+    /// * `curves` - The name, data, and color of each curve.
+    /// * `target` - (Optional) If specified, the target mask is drawn as a line.
+    /// * `bands` - Frequency ranges to shade behind the curves.
+    /// * `config` - Axes, caption, labels, shaded bands, and legend groups
+    ///              of the plot.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(title = %title, curves = curves.len())))]
+    fn draw_curves<DB: DrawingBackend>(canvas: &DrawAreaType<DB>, title: &str, curves: &[(&str, Sweep, RGBColor)], target: Option<&TargetMask>, bands: &Bands, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if curves.is_empty() {return Err(PlotError::Empty.into());}
+        error::check_range(config.fmin, config.fmax)?;
+        let (fmin, fmax) = config.freq_limits_hz();
+        let mut min_mag = curves.iter().map(|(_, sweep, _)| sweep.min_mag()).fold(f64::INFINITY, f64::min);
+        let mut max_mag = curves.iter().map(|(_, sweep, _)| sweep.max_mag()).fold(f64::NEG_INFINITY, f64::max);
+        if let Some(mask) = target {
+            let (lo, hi) = mask.span(fmin, fmax);
+            min_mag = min_mag.min(lo);
+            max_mag = max_mag.max(hi);
+        }
+        error::check_limits(min_mag, max_mag)?;
+        let mag_scale = config.mag_scale;
+        let y_range = config.y_range(min_mag, max_mag);
+
+        let shared = SharedX::new(config, config.show_phase);
+        let mut builder = shared.builder(canvas, true);
+        if let Some(caption) = config.caption_or(title) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&|y| config.mag_tick(*y, "Ω"))
+            .x_desc(x_label.as_str())
+            .y_desc(config.y_label_or(&mag_scale.label("Impedance", "Ω")))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+        chart.draw_series(bands.spans.iter().map(|(start, end, color)| {
+                Rectangle::new(
+                    [(config.freq_to_axis(*start), y_range.start), (config.freq_to_axis(*end), y_range.end)],
+                    color.mix(0.15).filled()
+                )
+            }))?;
+        for (name, color) in bands.legend.iter() {
+            let color = color.mix(0.15);
+            chart.draw_series(std::iter::empty::<Rectangle<(f64, f64)>>())?
+                .label(name.as_str())
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+        }
+        if let Some(mask) = target {
+            chart.draw_series(LineSeries::new(
+                    mask.outline(fmin, fmax).into_iter().map(|(f, z)| (config.freq_to_axis(f), mag_scale.to_axis(z))),
+                    &PURPLE
+                ))?
+                .label("Target")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+        }
+        // Plotters lists legend entries in drawing order, so the curves are
+        // drawn in legend order with an empty series for each group header
+        let names = curves.iter().map(|(name, _, _)| *name).collect::<Vec<&str>>();
+        let legend = config.legend.order(&names);
+        let indent = if config.legend.groups.is_empty() {""} else {"  "};
+        for entry in legend.iter() {
+            match entry {
+                LegendEntry::Header(header) => {
+                    chart.draw_series(std::iter::empty::<PathElement<(f64, f64)>>())?
+                        .label(header.as_str())
+                        .legend(|(x, y)| EmptyElement::at((x, y)));
+                },
+                LegendEntry::Series(index) => {
+                    let (name, sweep, color) = &curves[*index];
+                    let color = *color;
+                    chart.draw_series(LineSeries::new(
+                            sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                            &color
+                        ))?
+                        .label(format!("{indent}{name}"))
+                        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+                },
+            }
+        }
+
+        if config.show_phase {
+            let mut chart = chart.set_secondary_coord(
+                shared.range.clone(),
+                config.phase_range.0..config.phase_range.1
+            );
+            chart.configure_secondary_axes()
+                .x_labels(shared.labels)
+                .x_label_formatter(&x_formatter)
+                .x_desc(&x_label)
+                .y_desc(config.phase_label_or("Phase [°]"))
+                .draw()?;
+            let ordered = legend.iter().filter_map(|entry| match entry {
+                LegendEntry::Series(index) => Some(&curves[*index]),
+                LegendEntry::Header(_) => None,
+            });
+            for (name, sweep, color) in ordered {
+                let color = color.mix(0.4);
+                chart.draw_secondary_series(LineSeries::new(
+                        sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(sweep)),
+                        &color
+                    ))?
+                    .label(format!("{name} Phase"))
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+            }
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        } else {
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        }
+
+        Ok(())
+    }
+
+    /// Plot a model as impedance over frequency. Optionally, an impedance
+    /// target can be specified in the configuration to highlight at what
+    /// frequencies the model exceeds the target.
+    /// Returns a result to indicate if the function executed without error.
+    /// 
+    /// # Arguments
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `model` - An impedance model. Its display name is printed in the plot title.
+    /// * `config` - Options for the plot.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(model = %model.display_name())))]
+    fn draw<DB: DrawingBackend, P: Plottable + ?Sized>(canvas: &DrawAreaType<DB>, model: &P, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        draw_sweep(canvas, model, &config.sweep(model)?, config)
+    }
+
+    /// Like `draw`, for a sweep of `model` already taken with `config`.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the impedance plot.
+    /// * `model` - The impedance model swept, for its name and the harmonic comb.
+    /// * `sweep` - Impedance of `model` at the configuration's frequencies.
+    /// * `config` - Options for the plot.
+    fn draw_sweep<DB: DrawingBackend, P: Plottable + ?Sized>(canvas: &DrawAreaType<DB>, model: &P, sweep: &Sweep, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let name = model.display_name();
+        error::check_limits(sweep.min_mag(), sweep.max_mag())?;
+        let mag_scale = config.mag_scale;
+        let min = sweep.min_mag().min(config.target.unwrap_or(f64::INFINITY));
+        let max = sweep.max_mag().max(config.target.unwrap_or(f64::NEG_INFINITY));
+        let y_range = config.y_range(min, max);
+
+        if let Some((low, high)) = config.y_break {
+            error::check_break(low, high)?;
+        }
+        if let Some((lower, upper)) = config.y_break_ranges(min, max) {
+            return draw_broken(canvas, &name, sweep, config, lower, upper);
+        }
+
+        // With a ratio panel the caption spans both panels and only the
+        // lower one carries the frequency axis labels
+        let ratio_target = config.target.filter(|t| config.target_ratio && *t > 0.0);
+        let (area, ratio_area) = match ratio_target {
+            Some(_) => {
+                let area = match config.caption_or(&format!("Impedance of {name}")) {
+                    Some(caption) => canvas.titled(&caption, ("Arial", 30))?,
+                    None => canvas.clone(),
+                };
+                let split = (area.dim_in_pixel().1 as f64*0.75) as i32;
+                let (upper, lower) = area.split_vertically(split);
+                (upper, Some(lower))
+            },
+            None => (canvas.clone(), None),
+        };
+
+        let dual_mag = config.dual_mag_axis && mag_scale == MagScale::Log;
+        let shared = SharedX::new(config, dual_mag || config.show_phase);
+        let mut builder = shared.builder(&area, ratio_area.is_none());
+        if ratio_area.is_none() {
+            if let Some(caption) = config.caption_or(&format!("Impedance of {name}")) {
+                builder.caption(caption, ("Arial", 30));
+            }
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+        let x_label = config.x_label();
+        let x_ticks = shared.labels;
+        let x_formatter = |x: &f64| if ratio_area.is_none() {config.freq_tick(*x)} else {String::new()};
+        let y_formatter = |y: &f64| config.mag_tick(*y, "Ω");
+        chart.configure_mesh()
+            .x_labels(x_ticks)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&y_formatter)
+            .x_desc(if ratio_area.is_none() {x_label.as_str()} else {""})
+            .y_desc(config.y_label_or(&mag_scale.label("Impedance", "Ω")))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+
+        let freq_mag_iter = sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m)));
+
+        match config.target {
+            Some(res) => {
+                chart.draw_series(AreaSeries::new(
+                        freq_mag_iter,
+                        mag_scale.to_axis(res),
+                        &YELLOW.mix(0.3)
+                    )
+                    .border_style(&PURPLE))?
+                    .label("Impedance")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+            },
+            None => {
+                chart.draw_series(LineSeries::new(
+                        freq_mag_iter,
+                        &GREEN
+                    ))?
+                    .label("Impedance")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+            },
+        }
+
+        if let Some(comb) = config.harmonic_comb.as_ref() {
+            comb.draw(&mut chart, model, config, "Ω")?;
+        }
+
+        // The secondary axis is only built when something is drawn on it,
+        // leaving the right margin free otherwise
+        if dual_mag {
+            // dBΩ is 20 times the decade position, so asking for the same
+            // number of labels puts the dB ticks on the decade gridlines
+            let mut chart = chart.set_secondary_coord(
+                shared.range.clone(),
+                20.0*y_range.start..20.0*y_range.end
+            );
+            chart.configure_secondary_axes()
+                .x_labels(x_ticks)
+                .x_label_formatter(&x_formatter)
+                .y_labels(mag_scale.label_count(&y_range))
+                .y_label_formatter(&|y| format_plain(*y))
+                .x_desc(&x_label)
+                .y_desc(MagScale::Db.label("Impedance", "Ω"))
+                .draw()?;
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        } else if config.show_phase {
+            let mut chart = chart.set_secondary_coord(
+                shared.range.clone(),
+                config.phase_range.0..config.phase_range.1
+            );
+            chart.configure_secondary_axes()
+                .x_labels(x_ticks)
+                .x_label_formatter(&x_formatter)
+                .x_desc(&x_label)
+                .y_desc(config.phase_label_or("Phase [°]"))
+                .draw()?;
+            chart.draw_secondary_series(LineSeries::new(
+                    sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(sweep)),
+                    &RED.mix(0.4)
+                ))?
+                .label("Phase")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        } else {
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        }
+
+        if let (Some(area), Some(target)) = (ratio_area, ratio_target) {
+            draw_target_ratio(&area, &shared, sweep, target, config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw |Z|/target on a log axis with a line at 1, as a thin panel
+    /// under an impedance chart. Values above 1 violate the target.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `area` - The DrawingArea of the panel.
+    /// * `shared` - Frequency axis of the chart above, so the plots line up.
+    /// * `sweep` - The impedance plotted on the chart above.
+    /// * `target` - The impedance target in Ω.
+    /// * `config` - Options of the chart above, for the frequency axis labels.
+    fn draw_target_ratio<DB: DrawingBackend>(area: &DrawAreaType<DB>, shared: &SharedX, sweep: &Sweep, target: f64, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let ratios = sweep.mags.iter().map(|m| m/target).collect::<Vec<f64>>();
+        let min = ratios.iter().cloned().fold(1.0, f64::min);
+        let max = ratios.iter().cloned().fold(1.0, f64::max);
+        error::check_limits(min, max)?;
+
+        let mut chart = shared.builder(area, true)
+            .build_cartesian_2d(shared.range.clone(), (min/1.5..max*1.5).log_scale())?;
+
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_labels(3)
+            .y_label_formatter(&|y| format_plain(*y))
+            .x_desc(&x_label)
+            .y_desc("|Z|/Target")
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(
+                vec![(config.freq_range().start, 1.0), (config.freq_range().end, 1.0)],
+                &PURPLE
+            ))?;
+        chart.draw_series(LineSeries::new(
+                sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(ratios),
+                &GREEN
+            ))?;
+
+        Ok(())
+    }
+
+    /// Draw the impedance of a sweep on two stacked panels with a break in
+    /// the magnitude axis between them. The panel heights follow the span of
+    /// magnitudes each one covers.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the panels.
+    /// * `name` - Model name to print in the default caption.
+    /// * `sweep` - The impedance to plot.
+    /// * `config` - Options for the plot.
+    /// * `lower` - Magnitude axis range of the lower panel, in axis units.
+    /// * `upper` - Magnitude axis range of the upper panel, in axis units.
+    fn draw_broken<DB: DrawingBackend>(canvas: &DrawAreaType<DB>, name: &str, sweep: &Sweep, config: &PlotConfig, lower: Range<f64>, upper: Range<f64>) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let area = match config.caption_or(&format!("Impedance of {name}")) {
+            Some(caption) => canvas.titled(&caption, ("Arial", 30))?,
+            None => canvas.clone(),
+        };
+        let upper_share = (upper.end - upper.start)/(upper.end - upper.start + lower.end - lower.start);
+        let split = (area.dim_in_pixel().1 as f64*upper_share.clamp(0.25, 0.75)) as i32;
+        let (upper_area, lower_area) = area.split_vertically(split);
+
+        let mag_scale = config.mag_scale;
+        let points = sweep.mag_points()
+            .map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m)))
+            .collect::<Vec<(f64, f64)>>();
+        let x_label = config.x_label();
+        let shared = SharedX::new(config, false);
+        let x_ticks = shared.labels;
+        let y_formatter = |y: &f64| config.mag_tick(*y, "Ω");
+
+        // Only the lower panel carries the frequency axis labels and legend
+        for (panel, y_range, is_lower) in [(upper_area, upper, false), (lower_area, lower, true)] {
+            let mut chart = shared.builder(&panel, is_lower)
+                .build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+            let x_formatter = |x: &f64| if is_lower {config.freq_tick(*x)} else {String::new()};
+            chart.configure_mesh()
+                .x_labels(x_ticks)
+                .x_label_formatter(&x_formatter)
+                .y_labels(mag_scale.label_count(&y_range))
+                .y_label_formatter(&y_formatter)
+                .x_desc(if is_lower {x_label.as_str()} else {""})
+                .y_desc(config.y_label_or(&mag_scale.label("Impedance", "Ω")))
+                .draw()?;
+
+            if let Some(target) = config.target.map(|t| mag_scale.to_axis(t)).filter(|t| y_range.contains(t)) {
+                chart.draw_series(LineSeries::new(
+                        vec![(config.freq_range().start, target), (config.freq_range().end, target)],
+                        &PURPLE
+                    ))?
+                    .label("Target")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+            }
+
+            chart.draw_series(
+                    runs_within(&points, &y_range).into_iter().map(|run| PathElement::new(run, &GREEN))
+                )?
+                .label("Impedance")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+            if is_lower {
+                chart.configure_series_labels()
+                    .position(SeriesLabelPosition::LowerRight)
+                    .border_style(&BLACK)
+                    .background_style(&GREY.mix(0.3))
+                    .draw()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Split `points` into the runs of consecutive points whose y value lies
+    /// inside `range`, so a curve leaving a panel is not drawn across it.
+    fn runs_within(points: &[(f64, f64)], range: &Range<f64>) -> Vec<Vec<(f64, f64)>> {
+        let mut runs = vec![];
+        let mut run = vec![];
+        for point in points {
+            if range.contains(&point.1) {
+                run.push(*point);
+            } else if !run.is_empty() {
+                runs.push(std::mem::take(&mut run));
+            }
+        }
+        if !run.is_empty() {
+            runs.push(run);
+        }
+        runs
+    }
+
+    /// Draw a titled table of right-aligned columns in a monospace font.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `area` - A Plotter's DrawingArea on which to draw the table.
+    /// * `title` - Text to print above the table.
+    /// * `header` - Column headings.
+    /// * `rows` - Cell text of each row, in the same order as `header`.
+    fn draw_table<DB: DrawingBackend>(area: &DrawAreaType<DB>, title: &str, header: &[&str], rows: &[Vec<String>]) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let widths = header.iter().enumerate()
+            .map(|(col, heading)| {
+                rows.iter().filter_map(|row| row.get(col)).map(|cell| cell.chars().count())
+                    .fold(heading.chars().count(), usize::max)
+            })
+            .collect::<Vec<usize>>();
+        let format_row = |cells: Vec<&str>| {
+            cells.iter().zip(widths.iter())
+                .map(|(cell, width)| format!("{cell:>width$}", width = *width))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        let font = ("monospace", 15);
+        area.draw(&Text::new(title.to_string(), (10, 20), ("Arial", 20)))?;
+        area.draw(&Text::new(format_row(header.to_vec()), (10, 50), font))?;
+        for (index, row) in rows.iter().enumerate() {
+            let line = format_row(row.iter().map(|cell| cell.as_str()).collect());
+            area.draw(&Text::new(line, (10, 70 + 18*index as i32), font))?;
+        }
+        if rows.is_empty() {
+            area.draw(&Text::new("None".to_string(), (10, 70), font))?;
+        }
+
+        Ok(())
+    }
+
+    /// Label of the `index`th revision of a design: "rev A" to "rev Z", then
+    /// "rev AA", "rev AB", and so on.
+    fn revision_label(index: usize) -> String {
+        let mut letters = vec![];
+        let mut rest = index + 1;
+        while rest > 0 {
+            rest -= 1;
+            letters.push((b'A' + (rest % 26) as u8) as char);
+            rest /= 26;
+        }
+        format!("rev {}", letters.iter().rev().collect::<String>())
+    }
+
+    /// Frequency of the lowest impedance in a sweep, i.e. the series
+    /// resonance of a capacitor model.
+    fn resonance(sweep: &Sweep) -> f64 {
+        sweep.mag_points().fold((0.0, f64::INFINITY), |best, p| if p.1 < best.1 {p} else {best}).0
+    }
+
+    /// Distinct color for the `index`th extra curve on a plot.
+    fn palette(index: usize) -> RGBColor {
+        let (r, g, b) = Palette99::COLORS[index % Palette99::COLORS.len()];
+        RGBColor(r, g, b)
+    }
+
+    /// Draw a reflection coefficient quantity, such as S11, the return loss,
+    /// or the VSWR, over frequency, with the phase of S11 on the secondary
+    /// axis if the configuration shows it.
+    ///
+    /// # Arguments
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plot.
+    /// * `title` - Default caption of the plot.
+    /// * `s11` - The S11 sweep, for the frequencies and the phase.
+    /// * `curve` - Name and value of the quantity at each frequency, the
+    /// name also giving the default magnitude axis label.
+    /// * `guides` - Values at which to draw guide lines, possibly none.
+    /// * `ratio_axis` - Whether the values are ratios such as the VSWR, drawn
+    /// on a log axis labeled n:1, rather than dB drawn on a linear axis.
+    /// * `config` - Sweep range, phase, caption, and axis labels of the plot.
+    fn draw_reflection<DB: DrawingBackend>(canvas: &DrawAreaType<DB>, title: &str, s11: &Sweep, curve: (&str, &[f64]), guides: &[f64], ratio_axis: bool, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let (label, values) = curve;
+        let scale = if ratio_axis {MagScale::Log} else {MagScale::Linear};
+        let values = values.iter().map(|v| scale.to_axis(*v)).collect::<Vec<f64>>();
+        let guides = guides.iter().map(|g| (scale.to_axis(*g), *g)).collect::<Vec<(f64, f64)>>();
+        let finite = values.iter().cloned().filter(|y| y.is_finite());
+        let min_y = finite.clone().fold(f64::INFINITY, f64::min);
+        let max_y = finite.fold(f64::NEG_INFINITY, f64::max);
+        error::check_limits(min_y, max_y)?;
+        // Keep 0 dB or 1:1 and the guides in view, with a little room around
+        let low = guides.iter().map(|(y, _)| *y).fold(min_y.min(0.0), f64::min);
+        let high = guides.iter().map(|(y, _)| *y).fold(max_y.max(0.0), f64::max);
+        let pad = 0.05*(high - low).max(if ratio_axis {1.0} else {10.0});
+        let y_desc = if ratio_axis {label.to_string()} else {format!("{label} [dB]")};
+        let ratio_text = |ratio: f64| format!("{}:1", format_plain((ratio*100.0).round()/100.0));
+
+        let shared = SharedX::new(config, config.show_phase);
+        let mut builder = shared.builder(canvas, true);
+        if let Some(caption) = config.caption_or(title) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder
+            .build_cartesian_2d(shared.range.clone(), low - pad..high + pad)?
+            .set_secondary_coord(shared.range.clone(), config.phase_range.0..config.phase_range.1);
+
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        let y_formatter = |y: &f64| if ratio_axis {ratio_text(scale.from_axis(*y))} else {format_plain(*y)};
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .x_desc(&x_label)
+            .y_desc(config.y_label_or(&y_desc))
+            .y_label_formatter(&y_formatter)
+            .draw()?;
+        if config.show_phase {
+            chart.configure_secondary_axes()
+                .x_labels(shared.labels)
+                .x_label_formatter(&x_formatter)
+                .y_desc(config.phase_label_or("Phase [°]"))
+                .draw()?;
+        }
+
+        for (index, (y, guide)) in guides.iter().enumerate() {
+            let color = PURPLE.mix(1.0 - 0.5*index as f64/guides.len() as f64);
+            chart.draw_series(LineSeries::new(
+                    vec![(shared.range.start, *y), (shared.range.end, *y)],
+                    color
+                ))?
+                .label(if ratio_axis {ratio_text(*guide)} else {format!("{} dB", format_plain(*guide))})
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], color));
+        }
+        chart.draw_series(LineSeries::new(
+                s11.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(values.iter().cloned()).filter(|(_, y)| y.is_finite()),
+                &GREEN
+            ))?
+            .label(label)
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        if config.show_phase {
+            chart.draw_secondary_series(LineSeries::new(
+                    s11.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(s11)),
+                    &RED.mix(0.4)
+                ))?
+                .label("S11 Phase")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+        }
 
         chart.configure_series_labels()
             .position(SeriesLabelPosition::LowerRight)
             .border_style(&BLACK)
             .background_style(&GREY.mix(0.3))
-            .draw()
-            .unwrap();
+            .draw()?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
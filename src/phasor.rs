@@ -0,0 +1,96 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Rectangular complex number used for arithmetic on sampled responses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Phasor {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Phasor {
+    pub fn new(re: f64, im: f64) -> Phasor {
+        Phasor {re, im}
+    }
+
+    /// Create a phasor from a magnitude and a phase in degrees.
+    pub fn from_polar(mag: f64, phase_deg: f64) -> Phasor {
+        let phase = phase_deg.to_radians();
+        Phasor {re: mag*phase.cos(), im: mag*phase.sin()}
+    }
+
+    pub fn abs(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Phase in degrees.
+    pub fn arg_deg(&self) -> f64 {
+        self.im.atan2(self.re).to_degrees()
+    }
+
+    pub fn conj(&self) -> Phasor {
+        Phasor {re: self.re, im: -self.im}
+    }
+
+    pub fn scale(&self, k: f64) -> Phasor {
+        Phasor {re: self.re*k, im: self.im*k}
+    }
+
+    /// Complex exponential e^(re + j·im).
+    pub fn exp(&self) -> Phasor {
+        let mag = self.re.exp();
+        Phasor {re: mag*self.im.cos(), im: mag*self.im.sin()}
+    }
+
+    /// Principal square root, the one with a non-negative real part.
+    pub fn sqrt(&self) -> Phasor {
+        Phasor::from_polar(self.abs().sqrt(), self.arg_deg()/2.0)
+    }
+}
+
+impl From<f64> for Phasor {
+    fn from(re: f64) -> Phasor {
+        Phasor {re, im: 0.0}
+    }
+}
+
+impl Add for Phasor {
+    type Output = Phasor;
+    fn add(self, rhs: Phasor) -> Phasor {
+        Phasor {re: self.re + rhs.re, im: self.im + rhs.im}
+    }
+}
+
+impl Sub for Phasor {
+    type Output = Phasor;
+    fn sub(self, rhs: Phasor) -> Phasor {
+        Phasor {re: self.re - rhs.re, im: self.im - rhs.im}
+    }
+}
+
+impl Mul for Phasor {
+    type Output = Phasor;
+    fn mul(self, rhs: Phasor) -> Phasor {
+        Phasor {
+            re: self.re*rhs.re - self.im*rhs.im,
+            im: self.re*rhs.im + self.im*rhs.re,
+        }
+    }
+}
+
+impl Div for Phasor {
+    type Output = Phasor;
+    fn div(self, rhs: Phasor) -> Phasor {
+        let den = rhs.re*rhs.re + rhs.im*rhs.im;
+        Phasor {
+            re: (self.re*rhs.re + self.im*rhs.im)/den,
+            im: (self.im*rhs.re - self.re*rhs.im)/den,
+        }
+    }
+}
+
+impl Neg for Phasor {
+    type Output = Phasor;
+    fn neg(self) -> Phasor {
+        Phasor {re: -self.re, im: -self.im}
+    }
+}
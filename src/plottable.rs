@@ -0,0 +1,145 @@
+use sdomain_test::complex::Complex;
+use sdomain_test::passives::capacitor::Capacitor;
+use sdomain_test::pdn::PDNModel;
+use sdomain_test::sdomain::Fs;
+
+/// Anything with a frequency response that the plotters can sweep.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::plottable::Plottable;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+/// use sdomain_test::sdomain;
+///
+/// let resistor = sdomain::gen::resistor(10.0).named("10Ω");
+/// assert_eq!(resistor.display_name(), "10Ω");
+/// assert!((resistor.response(1e3).mag() - 10.0).abs() < 1e-9);
+///
+/// let capacitor = Capacitor::from(22e-6, "0603");
+/// assert_eq!(capacitor.display_name(), format!("{capacitor}"));
+/// ```
+pub trait Plottable {
+    /// Complex response at `freq` in Hz.
+    fn response(&self, freq: f64) -> Complex;
+
+    /// Complex response at each of `freqs` in Hz. Sweeps go through this,
+    /// so models that are expensive to set up, such as a `PDNModel`, can
+    /// set up once per sweep instead of once per frequency.
+    fn responses(&self, freqs: &[f64]) -> Vec<Complex> {
+        freqs.iter().map(|freq| self.response(*freq)).collect()
+    }
+
+    /// Name to print in plot titles and legends.
+    fn display_name(&self) -> String {
+        "Model".to_string()
+    }
+
+    /// Wrap the model with a name to display instead of the default.
+    fn named(self, name: &str) -> Named<Self> where Self: Sized {
+        Named {name: name.to_string(), model: self}
+    }
+}
+
+/// A model with a display name attached.
+#[derive(Clone)]
+pub struct Named<P> {
+    pub name: String,
+    pub model: P,
+}
+
+impl<P: Plottable> Plottable for Named<P> {
+    fn response(&self, freq: f64) -> Complex {
+        self.model.response(freq)
+    }
+
+    fn responses(&self, freqs: &[f64]) -> Vec<Complex> {
+        self.model.responses(freqs)
+    }
+
+    fn display_name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// A closure evaluated as a model, created by `from_fn`.
+#[derive(Clone)]
+pub struct FromFn<F> {
+    f: F,
+}
+
+/// Plot any `Fn(f64) -> Complex` closure, such as a fit to measured data,
+/// without building an `Fs` first.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::plottable::{self, Plottable};
+///
+/// use sdomain_test::sdomain;
+///
+/// // Scale a known model by a correction factor fitted from measurements
+/// let esr = sdomain::gen::resistor(5e-3);
+/// let fit = plottable::from_fn(move |f: f64| {
+///     let correction = 1.0 + (f/1e6).sqrt();
+///     sdomain::gen::resistor(5e-3*correction).calculate_freq(f)
+/// }).named("Fitted ESR");
+/// assert!(fit.response(1e6).mag() > esr.response(1e6).mag());
+/// ```
+pub fn from_fn<F: Fn(f64) -> Complex>(f: F) -> FromFn<F> {
+    FromFn {f}
+}
+
+impl<F: Fn(f64) -> Complex> Plottable for FromFn<F> {
+    fn response(&self, freq: f64) -> Complex {
+        (self.f)(freq)
+    }
+}
+
+impl Plottable for Fs {
+    fn response(&self, freq: f64) -> Complex {
+        self.calculate_freq(freq)
+    }
+}
+
+impl Plottable for PDNModel {
+    fn response(&self, freq: f64) -> Complex {
+        self.model().calculate_freq(freq)
+    }
+
+    /// Combines the PDN into one `Fs` once and evaluates it at every frequency.
+    fn responses(&self, freqs: &[f64]) -> Vec<Complex> {
+        self.model().responses(freqs)
+    }
+
+    fn display_name(&self) -> String {
+        "PDN".to_string()
+    }
+}
+
+impl Plottable for Capacitor {
+    fn response(&self, freq: f64) -> Complex {
+        self.model().calculate_freq(freq)
+    }
+
+    fn responses(&self, freqs: &[f64]) -> Vec<Complex> {
+        self.model().responses(freqs)
+    }
+
+    fn display_name(&self) -> String {
+        format!("{self}")
+    }
+}
+
+impl<P: Plottable + ?Sized> Plottable for &P {
+    fn response(&self, freq: f64) -> Complex {
+        (**self).response(freq)
+    }
+
+    fn responses(&self, freqs: &[f64]) -> Vec<Complex> {
+        (**self).responses(freqs)
+    }
+
+    fn display_name(&self) -> String {
+        (**self).display_name()
+    }
+}
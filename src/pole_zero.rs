@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::corners;
+use crate::discrete::{DiscreteError, Rational};
+use crate::phasor::Phasor;
+use crate::plottable::Plottable;
+use crate::sweep::Sweep;
+use crate::units::format_freq;
+
+/// Whether a root is a pole or a zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RootKind {
+    Pole,
+    Zero,
+}
+
+/// Relative spread below which roots found from a model's coefficients
+/// count as real, or as coincident.
+const ROOT_TOLERANCE: f64 = 1e-3;
+
+/// A pole or zero, or a group of coincident ones, of a model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Root {
+    pub kind: RootKind,
+    /// Natural frequency in Hz.
+    pub freq: f64,
+    /// Number of poles or zeros at `freq`, i.e. the slope change in steps
+    /// of 20 dB/decade.
+    pub order: usize,
+    /// (Optional) Damping ratio ζ of a complex pair. Only given when `order`
+    /// is 2 and the pair is complex; `estimate` can only tell so when the
+    /// response peaks or dips at `freq`.
+    pub damping: Option<f64>,
+    /// Whether the root lies in the right half of the s-plane, found from
+    /// the direction the phase turns across `freq`. A right-half-plane pole
+    /// makes a system unstable.
+    pub right_half_plane: bool,
+}
+
+impl Root {
+    /// Positions of the poles or zeros in the s-plane as (σ, ω) in Hz, i.e.
+    /// the real and imaginary parts of s divided by 2π.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::pole_zero::{Root, RootKind};
+    ///
+    /// let pair = Root {kind: RootKind::Pole, freq: 1e3, order: 2, damping: Some(0.6), right_half_plane: false};
+    /// let locations = pair.locations();
+    /// assert!((locations[0].0 + 600.0).abs() < 1e-9 && (locations[0].1 - 800.0).abs() < 1e-9);
+    /// assert!((locations[1].1 + 800.0).abs() < 1e-9);
+    /// ```
+    pub fn locations(&self) -> Vec<(f64, f64)> {
+        let sign = if self.right_half_plane {1.0} else {-1.0};
+        match self.damping {
+            Some(zeta) if self.order == 2 => {
+                let imag = self.freq*(1.0 - zeta*zeta).sqrt();
+                vec![(sign*zeta*self.freq, imag), (sign*zeta*self.freq, -imag)]
+            },
+            _ => vec![(sign*self.freq, 0.0); self.order],
+        }
+    }
+}
+
+impl fmt::Display for Root {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            RootKind::Pole => "pole",
+            RootKind::Zero => "zero",
+        };
+        write!(f, "{}×{kind} @ {}", self.order, format_freq(self.freq))?;
+        if self.right_half_plane {
+            write!(f, " RHP")?;
+        }
+        if let Some(damping) = self.damping {
+            write!(f, " ζ={damping:.2}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the poles and zeros of `model` from its coefficients, recovered by
+/// `Rational::fit` from the response between `fmin` and `fmax` in Hz. Unlike
+/// `estimate`, the roots are exact for any ratio of polynomials, however
+/// close together they are.
+/// Returns the roots from lowest to highest frequency, or an error if the
+/// model is not a ratio of polynomials of order 8 or less, e.g. because it
+/// contains a delay.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::pole_zero::{self, RootKind};
+///
+/// use sdomain_test::sdomain;
+///
+/// // Series RLC low pass filter with ωn = 1/√(LC) ≈ 5.03kHz and ζ = R/2·√(C/L) ≈ 0.079
+/// let zc = sdomain::gen::capacitor(1e-6);
+/// let lpf = zc.clone() / &(sdomain::gen::rl(5.0, 1e-3) + &zc);
+/// let roots = pole_zero::find(&lpf, 1.0, 10e6).unwrap();
+/// assert_eq!(roots.len(), 1);
+/// assert_eq!((roots[0].kind, roots[0].order), (RootKind::Pole, 2));
+/// assert!((roots[0].freq - 5032.9).abs() < 0.1);
+/// assert!((roots[0].damping.unwrap() - 0.0791).abs() < 1e-3);
+/// assert!(!roots[0].right_half_plane);
+/// ```
+pub fn find<P: Plottable + ?Sized>(model: &P, fmin: f64, fmax: f64) -> Result<Vec<Root>, DiscreteError> {
+    Rational::fit(model, fmin, fmax).map(|rational| from_rational(&rational))
+}
+
+/// The poles and zeros of a ratio of polynomials, each conjugate pair as one
+/// second-order root and coincident real roots grouped.
+/// Returns the roots from lowest to highest frequency.
+pub fn from_rational(rational: &Rational) -> Vec<Root> {
+    let mut roots = group(rational.zeros(), RootKind::Zero);
+    roots.extend(group(rational.poles(), RootKind::Pole));
+    roots.sort_by(|a, b| a.freq.total_cmp(&b.freq));
+    roots
+}
+
+/// Estimate the poles and zeros of a response from its magnitude, for
+/// models `find` cannot fit, such as measurements or ones with delays. Each
+/// corner where the slope falls adds poles and each corner where it rises
+/// adds zeros, one per 20 dB/decade of change. A second-order corner is
+/// treated as a complex pair when the response peaks or dips there, and
+/// its damping is found from the height of the peak above the asymptotes,
+/// |H(fn)| = 1/(2ζ).
+/// Returns the roots from lowest to highest frequency.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::pole_zero::{self, RootKind};
+/// use sdomain_test_plotters::sweep::Sweep;
+///
+/// use sdomain_test::sdomain;
+///
+/// // RC low pass filter with a pole at 1/(2π·100Ω·1µF) ≈ 1.59kHz
+/// let zr = sdomain::gen::resistor(100.0);
+/// let zc = sdomain::gen::capacitor(1e-6);
+/// let lpf = zc.clone() / &(zr + &zc);
+/// let roots = pole_zero::estimate(&Sweep::of(&lpf, 1.0, 10e6));
+/// assert_eq!(roots.len(), 1);
+/// assert_eq!(roots[0].kind, RootKind::Pole);
+/// assert!((roots[0].freq/1.59e3 - 1.0).abs() < 0.05);
+/// ```
+pub fn estimate(sweep: &Sweep) -> Vec<Root> {
+    let phases = sweep.unwrapped_phases();
+    corners::find_corners(sweep).iter()
+        .filter_map(|corner| {
+            let steps = ((corner.slope_after - corner.slope_before)/20.0).round();
+            if steps == 0.0 {return None;}
+            let kind = if steps < 0.0 {RootKind::Pole} else {RootKind::Zero};
+            let order = steps.abs() as usize;
+            let damping = if order == 2 {pair_damping(sweep, corner.freq, corner.slope_before, kind)} else {None};
+            // A left-half-plane pole turns the phase down and a zero turns it
+            // up; right-half-plane roots turn it the other way
+            let turn = phases[nearest(sweep, corner.freq*3.0)] - phases[nearest(sweep, corner.freq/3.0)];
+            let right_half_plane = match kind {
+                RootKind::Pole => turn > 0.0,
+                RootKind::Zero => turn < 0.0,
+            };
+            Some(Root {kind, freq: corner.freq, order, damping, right_half_plane})
+        })
+        .collect()
+}
+
+/// Roots of `kind` at the values of s in `locations`, in rad/s. Only the
+/// upper member of each conjugate pair is kept, as a root of order 2.
+fn group(locations: Vec<Phasor>, kind: RootKind) -> Vec<Root> {
+    let mut roots: Vec<Root> = Vec::new();
+    for s in locations.into_iter().filter(|s| s.im >= -ROOT_TOLERANCE*s.abs()) {
+        let freq = s.abs()/(2.0*PI);
+        let right_half_plane = s.re > 0.0;
+        if s.im > ROOT_TOLERANCE*s.abs() {
+            let damping = Some(s.re.abs()/s.abs());
+            roots.push(Root {kind, freq, order: 2, damping, right_half_plane});
+            continue;
+        }
+        let coincident = roots.iter_mut().find(|root| {
+            root.damping.is_none() && root.right_half_plane == right_half_plane
+                && (root.freq - freq).abs() <= ROOT_TOLERANCE*freq
+        });
+        match coincident {
+            Some(root) => root.order += 1,
+            None => roots.push(Root {kind, freq, order: 1, damping: None, right_half_plane}),
+        }
+    }
+    roots
+}
+
+/// Damping ratio of a complex pair at `freq`, from how far the response
+/// departs from the low-frequency asymptote extended to `freq`. Returns
+/// `None` when the response stays under the asymptotes, as two real roots
+/// or an overdamped pair would.
+fn pair_damping(sweep: &Sweep, freq: f64, slope_before: f64, kind: RootKind) -> Option<f64> {
+    let at = |f: f64| sweep.mags[nearest(sweep, f)];
+    let asymptote = at(freq/10.0)*10f64.powf(slope_before/20.0);
+    let ratio = at(freq)/asymptote;
+    // A pole pair peaks by 1/(2ζ); a zero pair dips by 2ζ
+    let damping = match kind {
+        RootKind::Pole => 0.5/ratio,
+        RootKind::Zero => 0.5*ratio,
+    };
+    if damping < 1.0/2f64.sqrt() {Some(damping)} else {None}
+}
+
+/// Index of the sample closest to `freq` on a logarithmic scale.
+fn nearest(sweep: &Sweep, freq: f64) -> usize {
+    sweep.freqs.iter()
+        .enumerate()
+        .fold((0, f64::INFINITY), |best, (index, f)| {
+            let distance = (f/freq).ln().abs();
+            if distance < best.1 {(index, distance)} else {best}
+        })
+        .0
+}
@@ -0,0 +1,32 @@
+//! Everything a typical plotting script needs in one `use` line:
+//! ```
+//! use sdomain_test_plotters::prelude::*;
+//!
+//! let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+//! pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+//!
+//! render::render_to_png("images/pdn_prelude.png", (960, 720), |area| {
+//!     pdn_plotter::plot(&pdn, area, Some(0.1))
+//! }).unwrap();
+//! ```
+
+pub use crate::capacitor_plotter::library_plotter;
+pub use crate::pdn_impedance_plotter::pdn_plotter::{self, Rail};
+pub use crate::sdomain_plotter::bode_plotter::{self, BodePlot};
+pub use crate::spectrum_plotter::noise_plotter;
+pub use crate::stability_plotter::{middlebrook_plotter, two_port_plotter};
+pub use crate::time_domain_plotter::pdn_transient_plotter;
+
+pub use crate::config::{Caption, PlotConfig};
+pub use crate::error::PlotError;
+pub use crate::plottable::{self, Plottable};
+pub use crate::render;
+pub use crate::spectrum::Spectrum;
+pub use crate::sweep::Sweep;
+pub use crate::target::TargetMask;
+pub use crate::time_domain::Pwl;
+
+pub use sdomain_test::complex::Complex;
+pub use sdomain_test::passives::capacitor::Capacitor;
+pub use sdomain_test::pdn::PDNModel;
+pub use sdomain_test::sdomain::{self, Fs};
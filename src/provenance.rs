@@ -0,0 +1,202 @@
+use crate::bom::Decap;
+use crate::config::PlotConfig;
+use crate::target::TargetMask;
+use crate::units::{escape_markup, format_farads, format_freq, format_ohms, format_si};
+use crate::vrm::BuckVrm;
+
+/// Description of the model behind a figure, embedded in rendered files so
+/// the figure can always be traced back to what produced it. The crate name
+/// and version are always included.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::bom::Decap;
+/// use sdomain_test_plotters::provenance::Provenance;
+///
+/// let provenance = Provenance::new("Core rail PDN, rev B")
+///     .decaps(&[Decap {name: "C1".to_string(), value: 22e-6, package: "0603".to_string(), count: 4}])
+///     .entry("board", "proto-2");
+/// let text = provenance.to_text();
+/// assert!(text.starts_with("Core rail PDN, rev B\n"));
+/// assert!(text.contains("board: proto-2"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Provenance {
+    /// Free-form description, e.g. the design name and revision.
+    pub description: String,
+    /// (key, value) pairs describing the model, in the order added.
+    pub entries: Vec<(String, String)>,
+}
+
+impl Provenance {
+    /// Start a description of a figure.
+    pub fn new(description: &str) -> Provenance {
+        Provenance {
+            description: description.to_string(),
+            entries: vec![("crate".to_string(), format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))],
+        }
+    }
+
+    /// Add a (key, value) pair.
+    pub fn entry(mut self, key: &str, value: &str) -> Provenance {
+        self.entries.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add one entry per line of a decoupling bill of materials.
+    pub fn decaps(mut self, decaps: &[Decap]) -> Provenance {
+        for decap in decaps {
+            self.entries.push((
+                format!("decap {}", decap.name),
+                format!("{} × {} {}", decap.count, format_farads(decap.value), decap.package),
+            ));
+        }
+        self
+    }
+
+    /// Add the parameters of a buck regulator.
+    pub fn vrm(mut self, vrm: &BuckVrm) -> Provenance {
+        self.entries.push((
+            "vrm".to_string(),
+            format!(
+                "bandwidth {}, L = {} ({}), C = {} ({} ESR)",
+                format_freq(vrm.bandwidth), format_si(vrm.inductance, "H"), format_ohms(vrm.inductor_resistance),
+                format_farads(vrm.capacitance), format_ohms(vrm.capacitor_esr)
+            ),
+        ));
+        self
+    }
+
+    /// Add the corners of an impedance target over `fmin` to `fmax` in Hz.
+    pub fn target(mut self, mask: &TargetMask, fmin: f64, fmax: f64) -> Provenance {
+        let corners = mask.outline(fmin, fmax).iter()
+            .map(|(freq, limit)| format!("{} @ {}", format_ohms(*limit), format_freq(*freq)))
+            .collect::<Vec<String>>();
+        self.entries.push(("target".to_string(), corners.join(", ")));
+        self
+    }
+
+    /// Add the sweep range and scale of a plot configuration, and its
+    /// target if it has one.
+    pub fn config(mut self, config: &PlotConfig) -> Provenance {
+        self.entries.push((
+            "sweep".to_string(),
+            format!(
+                "{} to {}, {:?} spacing",
+                config.freq_unit.format(config.fmin), config.freq_unit.format(config.fmax), config.freq_scale
+            ),
+        ));
+        if let Some(target) = config.target {
+            self.entries.push(("target".to_string(), format_ohms(target)));
+        }
+        self
+    }
+
+    /// The description and one `key: value` line per entry.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", self.description);
+        for (key, value) in self.entries.iter() {
+            text.push_str(&format!("{key}: {value}\n"));
+        }
+        text
+    }
+
+    /// Insert `<desc>` and `<metadata>` elements describing the model right
+    /// after the opening `<svg>` tag of an SVG document. The metadata holds
+    /// one `<entry key="...">` element per entry. Text without an `<svg>`
+    /// tag is returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::provenance::Provenance;
+    ///
+    /// let svg = "<svg width=\"10\" height=\"10\"><rect/></svg>";
+    /// let tagged = Provenance::new("R & D").embed_in_svg(svg);
+    /// assert!(tagged.starts_with("<svg width=\"10\" height=\"10\">\n<desc>R &amp; D</desc>\n<metadata>"));
+    /// assert!(tagged.ends_with("<rect/></svg>"));
+    /// ```
+    pub fn embed_in_svg(&self, svg: &str) -> String {
+        let start = match svg.find("<svg") {
+            Some(start) => start,
+            None => return svg.to_string(),
+        };
+        let end = match svg[start..].find('>') {
+            Some(offset) => start + offset + 1,
+            None => return svg.to_string(),
+        };
+        let mut elements = format!("\n<desc>{}</desc>\n<metadata>\n", escape_markup(&self.description));
+        for (key, value) in self.entries.iter() {
+            elements.push_str(&format!("<entry key=\"{}\">{}</entry>\n", escape_markup(key), escape_markup(value)));
+        }
+        elements.push_str("</metadata>\n");
+        format!("{}{elements}{}", &svg[..end], &svg[end..])
+    }
+
+    /// Insert text chunks describing the model before the end of a PNG
+    /// image: the description under the standard `Description` keyword and
+    /// one chunk per entry, with the crate version under `Software`. Text
+    /// that Latin-1 can hold goes in a `tEXt` chunk, anything else, such as
+    /// `Ω`, in a UTF-8 `iTXt` chunk. Data that is not a PNG is returned
+    /// unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::provenance::Provenance;
+    ///
+    /// // The signature and an empty IEND chunk
+    /// let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    /// png.extend_from_slice(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+    ///
+    /// let tagged = Provenance::new("Core rail").entry("target", "10 mΩ").embed_in_png(&png);
+    /// let text = String::from_utf8_lossy(&tagged);
+    /// assert!(text.contains("tEXtDescription\0Core rail"));
+    /// assert!(text.contains("iTXttarget\0\0\0\0\010 mΩ"));
+    /// assert!(tagged.ends_with(&png[8..]));
+    /// ```
+    pub fn embed_in_png(&self, png: &[u8]) -> Vec<u8> {
+        const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        if !png.starts_with(&SIGNATURE) || png.len() < 20 {
+            return png.to_vec();
+        }
+        // IEND is always the last chunk: 4 length, 4 type, 4 CRC bytes
+        let iend = png.len() - 12;
+        if &png[iend + 4..iend + 8] != b"IEND" {
+            return png.to_vec();
+        }
+
+        let mut chunks = vec![];
+        let texts = std::iter::once(("Description", self.description.as_str()))
+            .chain(self.entries.iter().map(|(key, value)| (if key == "crate" {"Software"} else {key.as_str()}, value.as_str())));
+        for (keyword, text) in texts {
+            let keyword = keyword.chars().filter(|c| (*c as u32) < 256).take(79).collect::<String>();
+            let keyword = keyword.trim();
+            if keyword.is_empty() {continue;}
+            let latin1 = |text: &str| text.chars().map(|c| c as u32).map(u8::try_from).collect::<Result<Vec<u8>, _>>();
+            let keyword_bytes = latin1(keyword).unwrap_or_default();
+            let (kind, mut data) = match latin1(text) {
+                Ok(bytes) => (*b"tEXt", [keyword_bytes, vec![0], bytes].concat()),
+                // Not compressed, no language tag or translated keyword
+                Err(_) => (*b"iTXt", [keyword_bytes, vec![0, 0, 0, 0, 0], text.as_bytes().to_vec()].concat()),
+            };
+            let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+            let mut crc_input = kind.to_vec();
+            crc_input.append(&mut data);
+            chunk.extend_from_slice(&crc_input);
+            chunk.extend_from_slice(&crc32(&crc_input).to_be_bytes());
+            chunks.push(chunk);
+        }
+        [&png[..iend], &chunks.concat(), &png[iend..]].concat()
+    }
+}
+
+/// CRC-32 as used by PNG chunks (ISO 3309, reflected, polynomial 0xEDB88320).
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {(crc >> 1) ^ 0xEDB8_8320} else {crc >> 1};
+        }
+    }
+    !crc
+}
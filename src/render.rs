@@ -0,0 +1,268 @@
+#[cfg(any(feature = "bitmap", feature = "svg"))]
+use std::path::Path;
+
+use plotters::prelude::*;
+
+#[cfg(feature = "deterministic")]
+use crate::error::PlotError;
+#[cfg(any(feature = "bitmap", feature = "svg"))]
+use crate::provenance::Provenance;
+
+/// Drawing area handed to the closures of the render helpers.
+pub type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+/// Font families drawn by the plotters, including the default family of
+/// Plotters' axis labels.
+#[cfg(feature = "deterministic")]
+const FONT_FAMILIES: [&str; 4] = ["Arial", "monospace", "sans-serif", "serif"];
+
+/// Pin every font family the plotters draw with to the TrueType or OpenType
+/// font in `bytes`, so bitmaps come out byte-identical on every machine
+/// regardless of which fonts are installed. Call this once before rendering,
+/// e.g. at the start of a golden image test or a documentation build. The
+/// pinned fonts are only used when the `ttf` feature is off.
+/// Returns a result to indicate if the font could be read.
+///
+/// # Arguments
+/// * `bytes` - Contents of a font file, typically from `include_bytes!`.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// let font = std::fs::read("fonts/DejaVuSans.ttf").unwrap();
+/// render::pin_font(Box::leak(font.into_boxed_slice())).unwrap();
+///
+/// // Two renders of the same plot, text included, come out byte for byte
+/// let model = sdomain::gen::rl(52e-3, 1.5e-6);
+/// let render = || render::render_to_buffer((640, 480), |area| pdn_plotter::plot(&model, area, Some(0.1))).unwrap();
+/// let first = render();
+/// assert!(first.iter().any(|byte| *byte != 255));
+/// assert_eq!(first, render());
+/// ```
+#[cfg(feature = "deterministic")]
+pub fn pin_font(bytes: &'static [u8]) -> Result<(), PlotError> {
+    for family in FONT_FAMILIES {
+        for style in [FontStyle::Normal, FontStyle::Oblique, FontStyle::Italic, FontStyle::Bold] {
+            plotters::style::register_font(family, style, bytes).map_err(|_| PlotError::InvalidFont)?;
+        }
+    }
+    Ok(())
+}
+
+/// Create a PNG of `size` pixels at `path`, fill it white, run `draw` on it,
+/// and write the file. Errors from drawing and from writing the file are
+/// both returned instead of being dropped.
+///
+/// # Arguments
+/// * `path` - File to write.
+/// * `size` - Width and height of the image in pixels.
+/// * `draw` - Plotting function to run on the drawing area, e.g. a call to
+///            one of the plotters.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+/// use sdomain_test::pdn::PDNModel;
+/// use sdomain_test::sdomain;
+///
+/// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+/// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+///
+/// render::render_to_png("images/pdn_render.png", (960, 720), |area| {
+///     pdn_plotter::plot(&pdn, area, Some(0.1))
+/// }).unwrap();
+/// ```
+#[cfg(feature = "bitmap")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(size = ?size)))]
+pub fn render_to_png<F>(path: impl AsRef<Path>, size: (u32, u32), draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<BitMapBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    let drawing_area = BitMapBackend::new(&path, size).into_drawing_area();
+    drawing_area.fill(&WHITE)?;
+    draw(&drawing_area)?;
+    drawing_area.present()?;
+    Ok(())
+}
+
+/// Like `render_to_png`, but with text chunks recording the model, sweep,
+/// target, and crate version added to the file, so an image passed around
+/// on its own stays traceable to its inputs.
+///
+/// # Arguments
+/// * `path` - File to write.
+/// * `size` - Width and height of the image in pixels.
+/// * `provenance` - Description of the model to embed.
+/// * `draw` - Plotting function to run on the drawing area.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::config::PlotConfig;
+/// use sdomain_test_plotters::provenance::Provenance;
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// let config = PlotConfig {target: Some(0.1), ..PlotConfig::default()};
+/// let provenance = Provenance::new("Source only").config(&config);
+/// render::render_to_png_with_provenance("images/pdn_provenance.png", (960, 720), &provenance, |area| {
+///     pdn_plotter::plot_with_config(&sdomain::gen::rl(52e-3, 1.5e-6), area, &config)
+/// }).unwrap();
+/// ```
+#[cfg(feature = "bitmap")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(size = ?size)))]
+pub fn render_to_png_with_provenance<F>(path: impl AsRef<Path>, size: (u32, u32), provenance: &Provenance, draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<BitMapBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    render_to_png(&path, size, draw)?;
+    let png = std::fs::read(&path)?;
+    std::fs::write(&path, provenance.embed_in_png(&png))?;
+    Ok(())
+}
+
+/// Draw into an in-memory RGB buffer of `size` pixels instead of a file, for
+/// services and tests that consume the image directly. The background is
+/// filled white before `draw` runs.
+/// Returns the pixels row by row, three bytes (red, green, blue) per pixel.
+///
+/// # Arguments
+/// * `size` - Width and height of the image in pixels.
+/// * `draw` - Plotting function to run on the drawing area.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// let pixels = render::render_to_buffer((640, 480), |area| {
+///     pdn_plotter::plot(&sdomain::gen::rl(52e-3, 1.5e-6), area, None)
+/// }).unwrap();
+/// assert_eq!(pixels.len(), 640*480*3);
+/// ```
+#[cfg(feature = "bitmap")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(size = ?size)))]
+pub fn render_to_buffer<F>(size: (u32, u32), draw: F) -> Result<Vec<u8>, Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<BitMapBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    let mut buffer = vec![0u8; size.0 as usize*size.1 as usize*3];
+    {
+        let drawing_area = BitMapBackend::with_buffer(&mut buffer, size).into_drawing_area();
+        drawing_area.fill(&WHITE)?;
+        draw(&drawing_area)?;
+        drawing_area.present()?;
+    }
+    Ok(buffer)
+}
+
+/// Create an SVG of `size` pixels at `path`, fill it white, run `draw` on it,
+/// and write the file. Text is written as SVG text, so no font rasterization
+/// is needed.
+///
+/// # Arguments
+/// * `path` - File to write.
+/// * `size` - Width and height of the image in pixels.
+/// * `draw` - Plotting function to run on the drawing area.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// render::render_to_svg("images/pdn_render.svg", (960, 720), |area| {
+///     pdn_plotter::plot(&sdomain::gen::rl(52e-3, 1.5e-6), area, None)
+/// }).unwrap();
+/// ```
+#[cfg(feature = "svg")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(size = ?size)))]
+pub fn render_to_svg<F>(path: impl AsRef<Path>, size: (u32, u32), draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<SVGBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    let drawing_area = SVGBackend::new(&path, size).into_drawing_area();
+    drawing_area.fill(&WHITE)?;
+    draw(&drawing_area)?;
+    drawing_area.present()?;
+    Ok(())
+}
+
+/// Like `render_to_svg`, but with `<desc>` and `<metadata>` elements
+/// describing the model embedded in the file, so the provenance of the
+/// figure can be recovered from the SVG itself.
+///
+/// # Arguments
+/// * `path` - File to write.
+/// * `size` - Width and height of the image in pixels.
+/// * `provenance` - Description of the model to embed.
+/// * `draw` - Plotting function to run on the drawing area.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::provenance::Provenance;
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// let provenance = Provenance::new("Source only").entry("source", "52mΩ + 1.5µH");
+/// render::render_to_svg_with_provenance("images/pdn_provenance.svg", (960, 720), &provenance, |area| {
+///     pdn_plotter::plot(&sdomain::gen::rl(52e-3, 1.5e-6), area, None)
+/// }).unwrap();
+/// let svg = std::fs::read_to_string("images/pdn_provenance.svg").unwrap();
+/// assert!(svg.contains("<desc>Source only</desc>"));
+/// ```
+#[cfg(feature = "svg")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(size = ?size)))]
+pub fn render_to_svg_with_provenance<F>(path: impl AsRef<Path>, size: (u32, u32), provenance: &Provenance, draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<SVGBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    let mut svg = String::new();
+    {
+        let drawing_area = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+        drawing_area.fill(&WHITE)?;
+        draw(&drawing_area)?;
+        drawing_area.present()?;
+    }
+    std::fs::write(path, provenance.embed_in_svg(&svg))?;
+    Ok(())
+}
+
+/// Create an animated GIF of `size` pixels at `path` with one frame per item
+/// of `frames`. Each frame is filled white and drawn by `draw`, e.g. to sweep
+/// a component value across the animation.
+///
+/// # Arguments
+/// * `path` - File to write.
+/// * `size` - Width and height of the image in pixels.
+/// * `frame_delay` - Time each frame is shown in milliseconds.
+/// * `frames` - One item per frame, handed to `draw`.
+/// * `draw` - Plotting function to run for each frame.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::render;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::passives::capacitor::Capacitor;
+///
+/// let values = [100e-9, 1e-6, 10e-6];
+/// render::render_to_gif("images/capacitor_sweep.gif", (960, 720), 500, values, |area, value| {
+///     pdn_plotter::plot(&Capacitor::from(value, "0402"), area, None)
+/// }).unwrap();
+/// ```
+#[cfg(feature = "gif")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(size = ?size)))]
+pub fn render_to_gif<T, F>(path: impl AsRef<Path>, size: (u32, u32), frame_delay: u32, frames: impl IntoIterator<Item = T>, mut draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnMut(&DrawAreaType<BitMapBackend>, T) -> Result<(), Box <dyn std::error::Error>> {
+    let drawing_area = BitMapBackend::gif(&path, size, frame_delay)?.into_drawing_area();
+    for frame in frames {
+        drawing_area.fill(&WHITE)?;
+        draw(&drawing_area, frame)?;
+        drawing_area.present()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::{self, AnalysisReport};
+use crate::bom::Decap;
+use crate::config::PlotConfig;
+use crate::pdn_impedance_plotter::pdn_plotter::Rail;
+#[cfg(feature = "bitmap")]
+use crate::pdn_impedance_plotter::pdn_plotter;
+#[cfg(feature = "bitmap")]
+use crate::render;
+use crate::units::{escape_markup, format_farads, format_freq, format_ohms};
+
+/// Document format of a report.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    /// Markdown, e.g. for a repository wiki or a pull request.
+    #[default]
+    Markdown,
+    /// A single HTML page with no external styling.
+    Html,
+}
+
+impl Format {
+    /// File extension of the report document.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+/// A design review document covering one or more rails: for each rail, its
+/// impedance plot, decoupling bill of materials, anti-resonances, and target
+/// compliance.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::bom::{self, Decap};
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter::Rail;
+/// use sdomain_test_plotters::report::{Format, Report};
+///
+/// use sdomain_test::sdomain;
+///
+/// let decaps = vec![Decap {name: "C1".to_string(), value: 10e-6, package: "0402".to_string(), count: 8}];
+/// let core = bom::build_pdn(&sdomain::gen::rl(1e-3, 0.5e-6), &decaps);
+///
+/// let path = Report::new("Board A power integrity")
+///     .rail(Rail {name: "1V0 Core", model: &core, impedance_target: Some(0.005)}, &decaps)
+///     .write("images/report", Format::Markdown)
+///     .unwrap();
+/// let text = std::fs::read_to_string(path).unwrap();
+/// assert!(text.starts_with("# Board A power integrity\n"));
+/// assert!(text.contains("## 1V0 Core"));
+/// ```
+pub struct Report<'a> {
+    title: String,
+    config: PlotConfig,
+    rails: Vec<(Rail<'a>, &'a [Decap])>,
+}
+
+impl<'a> Report<'a> {
+    /// Start a report titled `title`.
+    pub fn new(title: &str) -> Report<'a> {
+        Report {title: title.to_string(), config: PlotConfig::default(), rails: vec![]}
+    }
+
+    /// Use `config` for the sweeps and plots of every rail. Each rail's own
+    /// target replaces the configured one.
+    pub fn config(mut self, config: PlotConfig) -> Report<'a> {
+        self.config = config;
+        self
+    }
+
+    /// Add a rail and its decoupling bill of materials.
+    pub fn rail(mut self, rail: Rail<'a>, bom: &'a [Decap]) -> Report<'a> {
+        self.rails.push((rail, bom));
+        self
+    }
+
+    /// Write the report into the directory `dir`, creating it if needed:
+    /// `report.md` or `report.html`, and with the `bitmap` feature one PNG
+    /// impedance plot per rail, linked from the document.
+    /// Returns the path of the document, or an error if a rail could not be
+    /// analyzed or plotted or a file could not be written.
+    pub fn write(&self, dir: impl AsRef<Path>, format: Format) -> Result<PathBuf, Box <dyn std::error::Error>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let mut document = Document::new(format, &self.title);
+        #[cfg(feature = "bitmap")]
+        let mut images = 0;
+        for (rail, bom) in &self.rails {
+            let config = PlotConfig {target: rail.impedance_target, ..self.config.clone()};
+            let analysis = analysis::analyze(rail.model, &config)?;
+            document.heading(rail.name);
+
+            #[cfg(feature = "bitmap")]
+            {
+                images += 1;
+                let image = format!("rail_{images}.png");
+                render::render_to_png(dir.join(&image), (960, 720), |area| pdn_plotter::plot_with_config(rail.model, area, &config))?;
+                document.image(rail.name, &image);
+            }
+            document.compliance(&analysis);
+            if !bom.is_empty() {
+                document.table(
+                    "Decoupling",
+                    &["Name", "Value", "Package", "Count", "SRF"],
+                    bom.iter().map(|decap| vec![
+                        decap.name.clone(),
+                        format_farads(decap.value),
+                        decap.package.clone(),
+                        decap.count.to_string(),
+                        format_freq(decap.srf()),
+                    ]).collect(),
+                );
+            }
+            if !analysis.anti_resonances.is_empty() {
+                document.table(
+                    "Anti-resonances",
+                    &["Frequency", "|Z|", "Q", "Damping ESR"],
+                    analysis.anti_resonances.iter().map(|peak| vec![
+                        format_freq(peak.freq),
+                        format_ohms(peak.peak),
+                        format!("{:.2}", peak.q),
+                        format_ohms(peak.damping_esr),
+                    ]).collect(),
+                );
+            }
+        }
+
+        let path = dir.join(format!("report.{}", format.extension()));
+        fs::write(&path, document.finish())?;
+        Ok(path)
+    }
+}
+
+/// A report document being assembled in one format.
+struct Document {
+    format: Format,
+    text: String,
+}
+
+impl Document {
+    fn new(format: Format, title: &str) -> Document {
+        let text = match format {
+            Format::Markdown => format!("# {title}\n"),
+            Format::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n",
+                escape_markup(title)
+            ),
+        };
+        Document {format, text}
+    }
+
+    fn heading(&mut self, text: &str) {
+        match self.format {
+            Format::Markdown => self.text.push_str(&format!("\n## {text}\n")),
+            Format::Html => self.text.push_str(&format!("<h2>{}</h2>\n", escape_markup(text))),
+        }
+    }
+
+    #[cfg(feature = "bitmap")]
+    fn image(&mut self, alt: &str, path: &str) {
+        match self.format {
+            Format::Markdown => self.text.push_str(&format!("\n![{alt}]({path})\n")),
+            Format::Html => self.text.push_str(&format!("<img src=\"{path}\" alt=\"{}\">\n", escape_markup(alt))),
+        }
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        match self.format {
+            Format::Markdown => self.text.push_str(&format!("\n{text}\n")),
+            Format::Html => self.text.push_str(&format!("<p>{}</p>\n", escape_markup(text))),
+        }
+    }
+
+    fn compliance(&mut self, analysis: &AnalysisReport) {
+        let text = match &analysis.compliance {
+            Some(Ok(margin)) => format!("Target met: {margin}."),
+            Some(Err(violation)) => format!("Target not met: {violation}."),
+            None => "No target set.".to_string(),
+        };
+        self.paragraph(&text);
+    }
+
+    fn table(&mut self, caption: &str, header: &[&str], rows: Vec<Vec<String>>) {
+        match self.format {
+            Format::Markdown => {
+                self.text.push_str(&format!("\n### {caption}\n\n| {} |\n", header.join(" | ")));
+                self.text.push_str(&format!("|{}\n", "---|".repeat(header.len())));
+                for row in rows {
+                    self.text.push_str(&format!("| {} |\n", row.join(" | ")));
+                }
+            },
+            Format::Html => {
+                self.text.push_str(&format!("<h3>{}</h3>\n<table>\n<tr>", escape_markup(caption)));
+                for cell in header {
+                    self.text.push_str(&format!("<th>{}</th>", escape_markup(cell)));
+                }
+                self.text.push_str("</tr>\n");
+                for row in rows {
+                    self.text.push_str("<tr>");
+                    for cell in row {
+                        self.text.push_str(&format!("<td>{}</td>", escape_markup(&cell)));
+                    }
+                    self.text.push_str("</tr>\n");
+                }
+                self.text.push_str("</table>\n");
+            },
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.format == Format::Html {
+            self.text.push_str("</body>\n</html>\n");
+        }
+        self.text
+    }
+}
@@ -0,0 +1,60 @@
+use crate::sweep::Sweep;
+
+/// A parallel (anti-)resonance found in an impedance sweep.
+#[derive(Clone, Copy, Debug)]
+pub struct AntiResonance {
+    /// Frequency of the impedance peak in Hz.
+    pub freq: f64,
+    /// Impedance magnitude at the peak in Ω.
+    pub peak: f64,
+    /// Quality factor, estimated from the -3 dB bandwidth of the peak.
+    pub q: f64,
+    /// Series resistance that would critically damp the resonance, i.e. the
+    /// characteristic impedance sqrt(L/C) of the resonant tank, in Ω.
+    pub damping_esr: f64,
+}
+
+/// Find every local maximum of |Z| in an impedance sweep and estimate its Q
+/// and ideal damping resistance. Peaks at the edges of the sweep are ignored
+/// since their shape is unknown.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(points = sweep.freqs.len())))]
+pub fn find_anti_resonances(sweep: &Sweep) -> Vec<AntiResonance> {
+    let mags = &sweep.mags;
+    let peaks = (1..mags.len().saturating_sub(1))
+        .filter(|i| mags[*i] > mags[i - 1] && mags[*i] >= mags[i + 1])
+        .map(|i| {
+            let freq = sweep.freqs[i];
+            let peak = mags[i];
+            let q = match (half_power_crossing(sweep, i, -1), half_power_crossing(sweep, i, 1)) {
+                (Some(lo), Some(hi)) => freq/(hi - lo),
+                (Some(lo), None) => freq/(2.0*(freq - lo)),
+                (None, Some(hi)) => freq/(2.0*(hi - freq)),
+                (None, None) => f64::INFINITY,
+            };
+            // At resonance |Z| = Q·sqrt(L/C)
+            AntiResonance {freq, peak, q, damping_esr: peak/q}
+        })
+        .collect::<Vec<AntiResonance>>();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(peaks = peaks.len(), "anti-resonances found");
+    peaks
+}
+
+/// Frequency at which |Z| first falls to 1/√2 of the peak at `index`,
+/// searching in `direction` (-1 toward lower frequencies, 1 toward higher).
+/// The crossing is interpolated linearly on log-log axes.
+fn half_power_crossing(sweep: &Sweep, index: usize, direction: isize) -> Option<f64> {
+    let level = sweep.mags[index]/2f64.sqrt();
+    let mut i = index;
+    loop {
+        let next = i.checked_add_signed(direction)?;
+        let (f0, z0) = (*sweep.freqs.get(i)?, sweep.mags[i]);
+        let (f1, z1) = (*sweep.freqs.get(next)?, sweep.mags[next]);
+        if z1 > z0 {return None;}
+        if z1 <= level {
+            let frac = (level/z0).ln()/(z1/z0).ln();
+            return Some(f0*(f1/f0).powf(frac));
+        }
+        i = next;
+    }
+}
@@ -0,0 +1,1030 @@
+pub mod bode_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{ORANGE, PURPLE, GREY};
+
+    use sdomain_test::range_generators::gen_log_range;
+
+    use crate::axis::{FreqScale, FreqUnit, MagScale, SlopeUnit};
+    use crate::bands::{self, FreqBand};
+    use crate::config::{Caption, NonFinitePolicy, PhaseLayout, PlotConfig};
+    use crate::corners;
+    use crate::discrete::{Discrete, Discretization};
+    use crate::error;
+    use crate::harmonics::HarmonicComb;
+    use crate::layout::SharedX;
+    use crate::loop_gain::{self, StabilityMargins};
+    use crate::plottable::Plottable;
+    use crate::pole_zero::{self, Root};
+    #[cfg(feature = "bitmap")]
+    use crate::render;
+    use crate::sweep::Sweep;
+    use crate::units::{format_eng, format_freq, format_plain};
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Features found in the response while drawing a bode plot, returned by
+    /// `BodePlot::render`.
+    #[derive(Clone, Debug, Default)]
+    pub struct BodeReport {
+        /// Frequencies in Hz where the magnitude crosses 0 dB, lowest first.
+        pub crossovers: Vec<f64>,
+        /// Poles and zeros of the model over the plotted band.
+        pub roots: Vec<Root>,
+        /// Whether `roots` were estimated from the magnitude response
+        /// because the model is not a ratio of polynomials.
+        pub roots_estimated: bool,
+        /// Gain and phase margins, treating the response as a loop gain.
+        pub margins: StabilityMargins,
+        /// (harmonic number, frequency in Hz, magnitude) at each harmonic
+        /// comb marker drawn, empty without a comb.
+        pub harmonics: Vec<(usize, f64, f64)>,
+    }
+
+    /// Fluent builder for a bode plot of any `Plottable` model. Start from
+    /// `BodePlot::new`, chain the options needed, and finish with `render`.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sdomain_plotter::bode_plotter::BodePlot;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/bode_builder.png", (800, 600))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let zr = sdomain::gen::resistor(100.0);
+    /// let zc = sdomain::gen::capacitor(4.7e-6);
+    /// let lpf = zc.clone() / &(zr + &zc);
+    ///
+    /// BodePlot::new(lpf)
+    ///     .name("Low Pass Filter")
+    ///     .freq(10.0, 1e8)
+    ///     .with_phase(false)
+    ///     .target(0.1)
+    ///     .annotate_corners()
+    ///     .render(&drawing_area)
+    ///     .unwrap();
+    /// ```
+    #[derive(Clone)]
+    pub struct BodePlot<P: Plottable> {
+        model: P,
+        name: Option<String>,
+        config: PlotConfig,
+    }
+
+    impl<P: Plottable> BodePlot<P> {
+        /// Start a bode plot of `model` with the default configuration.
+        pub fn new(model: P) -> BodePlot<P> {
+            BodePlot {model, name: None, config: PlotConfig {mag_scale: MagScale::Db, y_padding: 0.05, ..PlotConfig::default()}}
+        }
+
+        /// Model name to print in the plot title, instead of the model's display name.
+        pub fn name(mut self, name: &str) -> BodePlot<P> {
+            self.name = Some(name.to_string());
+            self
+        }
+
+        /// Replace the whole configuration.
+        pub fn config(mut self, config: PlotConfig) -> BodePlot<P> {
+            self.config = config;
+            self
+        }
+
+        /// Sweep from `fmin` to `fmax`, given in the frequency axis unit.
+        pub fn freq(mut self, fmin: f64, fmax: f64) -> BodePlot<P> {
+            self.config.fmin = fmin;
+            self.config.fmax = fmax;
+            self
+        }
+
+        /// Spacing of the frequency axis.
+        pub fn freq_scale(mut self, scale: FreqScale) -> BodePlot<P> {
+            self.config.freq_scale = scale;
+            self
+        }
+
+        /// Unit of the frequency axis, e.g. `FreqUnit::RadPerSec` to sweep
+        /// and label in ω.
+        pub fn freq_unit(mut self, unit: FreqUnit) -> BodePlot<P> {
+            self.config.freq_unit = unit;
+            self
+        }
+
+        /// Scale of the magnitude axis. Bode plots default to dB.
+        pub fn mag_scale(mut self, scale: MagScale) -> BodePlot<P> {
+            self.config.mag_scale = scale;
+            self
+        }
+
+        /// Pin the magnitude axis to `min`..`max` in linear units.
+        pub fn y_limits(mut self, min: f64, max: f64) -> BodePlot<P> {
+            self.config.y_limits = Some((min, max));
+            self
+        }
+
+        /// Significant digits in the magnitude axis tick labels.
+        pub fn y_digits(mut self, digits: usize) -> BodePlot<P> {
+            self.config.y_digits = digits;
+            self
+        }
+
+        /// Limit the phase axis to `min`..`max` degrees. A range wider than
+        /// 360° draws the unwrapped phase.
+        pub fn phase_range(mut self, min: f64, max: f64) -> BodePlot<P> {
+            self.config.phase_range = (min, max);
+            self
+        }
+
+        /// Whether to draw the phase curve.
+        pub fn with_phase(mut self, show: bool) -> BodePlot<P> {
+            self.config.show_phase = show;
+            self
+        }
+
+        /// How to handle samples where the response is NaN or infinite.
+        pub fn non_finite(mut self, policy: NonFinitePolicy) -> BodePlot<P> {
+            self.config.non_finite = policy;
+            self
+        }
+
+        /// Draw a horizontal line at a magnitude target given in linear units.
+        pub fn target(mut self, target: f64) -> BodePlot<P> {
+            self.config.target = Some(target);
+            self
+        }
+
+        /// Mark the corner frequencies of the magnitude response.
+        pub fn annotate_corners(mut self) -> BodePlot<P> {
+            self.config.annotate_corners = true;
+            self
+        }
+
+        /// Draw the straight-line asymptotes of the magnitude response the way
+        /// a bode plot is constructed by hand. Each asymptote is extended
+        /// half a decade past the corners at its ends, the intersections are
+        /// marked, and each piece is labeled with its slope.
+        pub fn asymptotes(mut self) -> BodePlot<P> {
+            self.config.asymptotes = true;
+            self
+        }
+
+        /// Show the group delay on the secondary axis instead of the phase.
+        /// The delay is labeled in engineering units such as µs or ns.
+        pub fn group_delay(mut self) -> BodePlot<P> {
+            self.config.show_phase = true;
+            self.config.group_delay = true;
+            self
+        }
+
+        /// Draw the phase, or the group delay, on its own panel under the
+        /// magnitude instead of on a secondary axis over it.
+        ///
+        /// # Examples
+        /// ```
+        /// use sdomain_test_plotters::sdomain_plotter::bode_plotter::BodePlot;
+        ///
+        /// use plotters::prelude::*;
+        /// use sdomain_test::sdomain;
+        ///
+        /// let drawing_area = BitMapBackend::new("images/bode_stacked.png", (960, 960))
+        ///     .into_drawing_area();
+        /// drawing_area.fill(&WHITE).unwrap();
+        ///
+        /// let zr = sdomain::gen::resistor(100.0);
+        /// let zc = sdomain::gen::capacitor(4.7e-6);
+        /// let lpf = zc.clone() / &(zr + &zc);
+        ///
+        /// BodePlot::new(lpf)
+        ///     .freq(10.0, 1e6)
+        ///     .stacked_phase()
+        ///     .render(&drawing_area)
+        ///     .unwrap();
+        /// ```
+        pub fn stacked_phase(mut self) -> BodePlot<P> {
+            self.config.show_phase = true;
+            self.config.phase_layout = PhaseLayout::Stacked;
+            self
+        }
+
+        /// Label slopes in `unit`, e.g. dB/octave for audio rolloff.
+        pub fn slope_unit(mut self, unit: SlopeUnit) -> BodePlot<P> {
+            self.config.slope_unit = unit;
+            self
+        }
+
+        /// Mark every frequency where the magnitude crosses 0 dB, e.g. the
+        /// unity-gain crossovers of a loop gain.
+        pub fn annotate_crossovers(mut self) -> BodePlot<P> {
+            self.config.annotate_crossovers = true;
+            self
+        }
+
+        /// Mark where the gain and phase margins of a loop gain are measured:
+        /// the gain margin on the magnitude curve at the phase crossover and
+        /// the phase margin on the phase curve at the gain crossover. The
+        /// margins and the delay margin are also listed in the corner of
+        /// the chart.
+        pub fn annotate_margins(mut self) -> BodePlot<P> {
+            self.config.annotate_margins = true;
+            self
+        }
+
+        /// Shade `bands` behind the curves, e.g. `FreqBand::cispr()` or the
+        /// broadcast bands, adding to any bands already set.
+        pub fn bands(mut self, bands: &[FreqBand]) -> BodePlot<P> {
+            self.config.bands.extend_from_slice(bands);
+            self
+        }
+
+        /// Mark `f_sw` in Hz and its harmonics up to `count`·f_sw, each with
+        /// a readout of the magnitude there, e.g. to see how a filter treats
+        /// a converter's switching noise.
+        pub fn harmonic_comb(mut self, f_sw: f64, count: usize) -> BodePlot<P> {
+            self.config.harmonic_comb = Some(HarmonicComb::new(f_sw, count));
+            self
+        }
+
+        /// List the poles and zeros of the model, with the damping of
+        /// complex pairs, in a table in the corner of the chart. Models that
+        /// are not a ratio of polynomials get the roots estimated from the
+        /// response, and the table says so.
+        pub fn pole_zero_table(mut self) -> BodePlot<P> {
+            self.config.pole_zero_table = true;
+            self
+        }
+
+        /// Print `caption` above the chart instead of the default title.
+        pub fn caption(mut self, caption: &str) -> BodePlot<P> {
+            self.config.caption = Caption::Text(caption.to_string());
+            self
+        }
+
+        /// Leave out the caption, e.g. when the document supplies its own.
+        pub fn no_caption(mut self) -> BodePlot<P> {
+            self.config.caption = Caption::Hidden;
+            self
+        }
+
+        /// Replace the frequency, magnitude, and phase axis labels.
+        pub fn labels(mut self, x_label: &str, y_label: &str, phase_label: &str) -> BodePlot<P> {
+            self.config.x_label = Some(x_label.to_string());
+            self.config.y_label = Some(y_label.to_string());
+            self.config.phase_label = Some(phase_label.to_string());
+            self
+        }
+
+        /// Draw the plot.
+        /// Returns the features found in the response, such as the 0 dB
+        /// crossovers, or an error if the plot could not be drawn.
+        ///
+        /// # Arguments
+        /// * `canvas` - A Plotter's DrawingArea on which to draw the bode plot.
+        ///
+        /// # Examples
+        /// ```
+        /// use sdomain_test_plotters::sdomain_plotter::bode_plotter::BodePlot;
+        ///
+        /// use plotters::prelude::*;
+        /// use sdomain_test::sdomain;
+        ///
+        /// let drawing_area = BitMapBackend::new("images/bode_crossovers.png", (960, 720))
+        ///     .into_drawing_area();
+        /// drawing_area.fill(&WHITE).unwrap();
+        ///
+        /// // Loop gain of an integrator crossing over at 10kHz
+        /// let integrator = sdomain::gen::capacitor(1.0/(2.0*std::f64::consts::PI*10e3));
+        /// let report = BodePlot::new(integrator)
+        ///     .freq(100.0, 10e6)
+        ///     .annotate_crossovers()
+        ///     .render(&drawing_area)
+        ///     .unwrap();
+        /// assert_eq!(report.crossovers.len(), 1);
+        /// assert!((report.crossovers[0]/10e3 - 1.0).abs() < 1e-3);
+        /// ```
+        #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(model = %self.model.display_name())))]
+        pub fn render<DB: DrawingBackend>(&self, canvas: &DrawAreaType<DB>) -> Result<BodeReport, Box <dyn std::error::Error>>
+        where DB::ErrorType: 'static {
+            let config = &self.config;
+            error::check_range(config.fmin, config.fmax)?;
+            let name = self.name.clone().unwrap_or_else(|| self.model.display_name());
+            let sweep = config.check_finite(Sweep::at(&self.model, config.sample_freqs()), &name)?;
+            let mag_scale = config.mag_scale;
+            let mag_data = sweep.mags.iter().map(|m| mag_scale.to_axis(*m)).collect::<Vec<f64>>();
+            let target_y = config.target.map(|t| mag_scale.to_axis(t));
+
+            let min = sweep.min_mag().min(config.target.unwrap_or(f64::INFINITY));
+            let max = sweep.max_mag().max(config.target.unwrap_or(f64::NEG_INFINITY));
+            let y_range = config.y_range(min, max);
+            error::check_limits(y_range.start, y_range.end)?;
+            let (min_mag, max_mag) = (y_range.start, y_range.end);
+
+            // A stacked phase panel takes the lower part of the canvas under
+            // a caption spanning both panels
+            let stacked = config.show_phase && config.phase_layout == PhaseLayout::Stacked;
+            let (mag_area, phase_area) = if stacked {
+                let area = match config.caption_or(&format!("Bode Plot for {name}")) {
+                    Some(caption) => canvas.titled(&caption, ("Arial", 30))?,
+                    None => canvas.clone(),
+                };
+                let split = (area.dim_in_pixel().1 as f64*0.6) as i32;
+                let (upper, lower) = area.split_vertically(split);
+                (upper, Some(lower))
+            } else {
+                (canvas.clone(), None)
+            };
+
+            let shared = SharedX::new(config, config.show_phase && !stacked);
+            let mut builder = shared.builder(&mag_area, !stacked);
+            if !stacked {
+                if let Some(caption) = config.caption_or(&format!("Bode Plot for {name}")) {
+                    builder.caption(caption, ("Arial", 30));
+                }
+            }
+            let mut chart = builder.build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+            let x_label = config.x_label();
+            let x_ticks = shared.labels;
+            let x_formatter = |x: &f64| if stacked {String::new()} else {config.freq_tick(*x)};
+            let y_formatter = |y: &f64| config.mag_tick(*y, "");
+            chart.configure_mesh()
+                .x_labels(x_ticks)
+                .x_label_formatter(&x_formatter)
+                .y_labels(mag_scale.label_count(&y_range))
+                .y_label_formatter(&y_formatter)
+                .x_desc(if stacked {""} else {x_label.as_str()})
+                .y_desc(config.y_label_or(&mag_scale.label("Magnitude", "")))
+                .draw()?;
+
+            bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+
+            if let Some(target) = target_y {
+                chart.draw_series(LineSeries::new(
+                        vec![(config.freq_range().start, target), (config.freq_range().end, target)],
+                        &PURPLE
+                    ))?
+                    .label("Target")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+            }
+
+            chart.draw_series(LineSeries::new(
+                    sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(mag_data),
+                    &GREEN
+                ))?
+                .label("Magnitude")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+            if config.annotate_corners {
+                for corner in corners::find_corners(&sweep).iter() {
+                    let x = config.freq_to_axis(corner.freq);
+                    chart.draw_series(LineSeries::new(
+                            vec![(x, min_mag), (x, max_mag)],
+                            BLUE.mix(0.5).stroke_width(1)
+                        ))?;
+                    chart.draw_series(std::iter::once(Text::new(
+                            format!("fc = {}", config.freq_unit.format(config.freq_unit.from_hz(corner.freq))),
+                            (x, max_mag),
+                            ("Arial", 15)
+                        )))?;
+                }
+            }
+
+            if config.asymptotes {
+                let pieces = corners::asymptotes(&sweep);
+                let (fmin, fmax) = (sweep.freqs[0], sweep.freqs[sweep.freqs.len() - 1]);
+                for (index, piece) in pieces.iter().enumerate() {
+                    let line = |from: f64, to: f64| {
+                        gen_log_range(from, to, 10.0, 20).into_iter()
+                            .map(|f| (config.freq_to_axis(f), mag_scale.to_axis(piece.mag_at(f))))
+                            .filter(|(_, y)| y_range.contains(y))
+                            .collect::<Vec<(f64, f64)>>()
+                    };
+                    // Construction lines run half a decade past each corner
+                    let reach = 10f64.sqrt();
+                    let series = chart.draw_series(LineSeries::new(
+                            line((piece.fstart/reach).max(fmin), (piece.fend*reach).min(fmax)),
+                            BLUE.mix(0.25).stroke_width(1)
+                        ))?;
+                    if index == 0 {
+                        series.label("Asymptotes")
+                            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], BLUE.mix(0.6).stroke_width(2)));
+                    }
+                    chart.draw_series(LineSeries::new(line(piece.fstart, piece.fend), BLUE.mix(0.6).stroke_width(2)))?;
+
+                    let middle = (piece.fstart*piece.fend).sqrt();
+                    let label_at = (config.freq_to_axis(middle), mag_scale.to_axis(piece.mag_at(middle)));
+                    if y_range.contains(&label_at.1) {
+                        chart.draw_series(std::iter::once(Text::new(
+                                config.slope_unit.format(piece.slope),
+                                label_at,
+                                ("Arial", 15)
+                            )))?;
+                    }
+                    let corner = (config.freq_to_axis(piece.fstart), mag_scale.to_axis(piece.mag_start));
+                    if index > 0 && y_range.contains(&corner.1) {
+                        chart.draw_series(std::iter::once(Circle::new(corner, 3, BLUE.filled())))?;
+                    }
+                }
+            }
+
+            let crossovers = loop_gain::unity_gain_crossovers(&sweep);
+            let unity = mag_scale.to_axis(1.0);
+            if config.annotate_crossovers && y_range.contains(&unity) {
+                for freq in crossovers.iter() {
+                    chart.draw_series(std::iter::once(
+                            EmptyElement::at((config.freq_to_axis(*freq), unity))
+                            + Circle::new((0, 0), 4, BLUE.filled())
+                            + Text::new(
+                                format!("0 dB @ {}", config.freq_unit.format(config.freq_unit.from_hz(*freq))),
+                                (8, -16),
+                                ("Arial", 15)
+                            )
+                        ))?;
+                }
+            }
+
+            let harmonics = match config.harmonic_comb.as_ref() {
+                Some(comb) => comb.draw(&mut chart, &self.model, config, "")?,
+                None => vec![],
+            };
+
+            let margins = loop_gain::margins(&sweep);
+            if config.annotate_margins {
+                for freq in [margins.gain_crossover, margins.phase_crossover].iter().flatten() {
+                    let x = config.freq_to_axis(*freq);
+                    chart.draw_series(LineSeries::new(
+                            vec![(x, min_mag), (x, max_mag)],
+                            BLACK.mix(0.3).stroke_width(1)
+                        ))?;
+                }
+                // The gain margin is the distance from the curve up to 0 dB
+                if let (Some(freq), Some(gain_margin)) = (margins.phase_crossover, margins.gain_margin) {
+                    let point = (config.freq_to_axis(freq), mag_scale.to_axis(10f64.powf(-gain_margin/20.0)));
+                    chart.draw_series(LineSeries::new(vec![(point.0, unity), point], MAGENTA.stroke_width(2)))?;
+                    chart.draw_series(std::iter::once(
+                            EmptyElement::at(point)
+                            + Circle::new((0, 0), 4, MAGENTA.filled())
+                            + Text::new(format!("GM = {gain_margin:.1} dB"), (8, 8), ("Arial", 15))
+                        ))?;
+                }
+            }
+
+            // The phase axis is only built when the phase is drawn, leaving
+            // the right margin free otherwise
+            if let Some(area) = phase_area.as_ref() {
+                chart.configure_series_labels()
+                    .position(SeriesLabelPosition::LowerRight)
+                    .border_style(&BLACK)
+                    .background_style(&GREY.mix(0.3))
+                    .draw()?;
+                draw_phase_panel(area, &shared, &sweep, config, &margins)?;
+            } else if config.show_phase && config.group_delay {
+                let delays = sweep.group_delays();
+                let finite = delays.iter().cloned().filter(|d| d.is_finite());
+                let min_delay = finite.clone().fold(0.0, f64::min);
+                let max_delay = finite.fold(0.0, f64::max);
+                let pad = (max_delay - min_delay).max(1e-12)*0.1;
+                let mut chart = chart.set_secondary_coord(
+                    config.freq_range(),
+                    min_delay - pad..max_delay + pad
+                );
+                let delay_formatter = |d: &f64| format_eng(*d, "s", 3);
+                chart.configure_secondary_axes()
+                    .x_labels(x_ticks)
+                    .x_label_formatter(&x_formatter)
+                    .y_label_formatter(&delay_formatter)
+                    .x_desc(&x_label)
+                    .y_desc(config.phase_label_or("Group Delay"))
+                    .draw()?;
+                chart.draw_secondary_series(LineSeries::new(
+                        sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(delays),
+                        &RED.mix(0.4)
+                    ))?
+                    .label("Group Delay")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+                chart.configure_series_labels()
+                    .position(SeriesLabelPosition::LowerRight)
+                    .border_style(&BLACK)
+                    .background_style(&GREY.mix(0.3))
+                    .draw()?;
+            } else if config.show_phase {
+                let mut chart = chart.set_secondary_coord(
+                    config.freq_range(),
+                    config.phase_range.0..config.phase_range.1
+                );
+                chart.configure_secondary_axes()
+                    .x_labels(x_ticks)
+                    .x_label_formatter(&x_formatter)
+                    .x_desc(&x_label)
+                    .y_desc(config.phase_label_or("Phase [°]"))
+                    .draw()?;
+                chart.draw_secondary_series(LineSeries::new(
+                        sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(&sweep)),
+                        &RED.mix(0.4)
+                    ))?
+                    .label("Phase")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+                // The phase margin is the distance from -180° up to the curve
+                if let (true, Some(freq), Some(phase_margin)) = (config.annotate_margins, margins.gain_crossover, margins.phase_margin) {
+                    let point = (config.freq_to_axis(freq), config.phase_to_axis(phase_margin - 180.0));
+                    chart.draw_secondary_series(LineSeries::new(vec![(point.0, -180.0), point], MAGENTA.stroke_width(2)))?;
+                    chart.draw_secondary_series(std::iter::once(
+                            EmptyElement::at(point)
+                            + Circle::new((0, 0), 4, MAGENTA.filled())
+                            + Text::new(format!("PM = {phase_margin:.1}°"), (8, 8), ("Arial", 15))
+                        ))?;
+                }
+                chart.configure_series_labels()
+                    .position(SeriesLabelPosition::LowerRight)
+                    .border_style(&BLACK)
+                    .background_style(&GREY.mix(0.3))
+                    .draw()?;
+            } else {
+                chart.configure_series_labels()
+                    .position(SeriesLabelPosition::LowerRight)
+                    .border_style(&BLACK)
+                    .background_style(&GREY.mix(0.3))
+                    .draw()?;
+            }
+
+            let (fmin, fmax) = config.freq_limits_hz();
+            let (roots, roots_estimated) = match pole_zero::find(&self.model, fmin, fmax) {
+                Ok(roots) => (roots, false),
+                Err(_) => (pole_zero::estimate(&sweep), true),
+            };
+            if config.pole_zero_table {
+                draw_root_table(canvas, &roots, roots_estimated, config.show_phase && !stacked)?;
+            }
+
+            if config.annotate_margins {
+                draw_margin_block(canvas, &margins, config)?;
+            }
+
+            Ok(BodeReport {crossovers, roots, roots_estimated, margins, harmonics})
+        }
+
+        /// Draw the plot into a new PNG file at `path` of `size` pixels.
+        /// Returns a result to indicate if the plot was drawn and written.
+        #[cfg(feature = "bitmap")]
+        pub fn render_to_png(&self, path: &str, size: (u32, u32)) -> Result<(), Box <dyn std::error::Error>> {
+            render::render_to_png(path, size, |area| self.render(area).map(|_| ()))
+        }
+
+        /// Draw the plot into an in-memory RGB buffer of `size` pixels.
+        /// Returns the pixels row by row, three bytes per pixel.
+        #[cfg(feature = "bitmap")]
+        pub fn render_to_buffer(&self, size: (u32, u32)) -> Result<Vec<u8>, Box <dyn std::error::Error>> {
+            render::render_to_buffer(size, |area| self.render(area).map(|_| ()))
+        }
+    }
+
+    /// Plot the difference between two responses: 20·log10(|A|/|B|) in dB
+    /// and, unless the configuration hides the phase, the phase of A minus
+    /// the phase of B on the right axis. Small deviations that are lost when
+    /// two curves are overlaid stand out against the 0 dB line.
+    /// Returns the ratio A/B at each frequency, or an error if it could not
+    /// be plotted.
+    ///
+    /// # Arguments
+    /// * `a` - The response to compare, e.g. a detailed model or a measurement.
+    /// * `b` - The reference response.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plot.
+    /// * `config` - Sweep range, axes, and labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::sdomain_plotter::bode_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/bode_diff.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // The same capacitor with and without 1nH of ESL
+    /// let ideal = sdomain::gen::capacitor(1e-6);
+    /// let real = sdomain::gen::rcl(0.0, 1e-6, 1e-9);
+    /// let config = PlotConfig {fmin: 1e3, fmax: 1e6, ..PlotConfig::default()};
+    /// let ratio = bode_plotter::plot_diff(&real, &ideal, &drawing_area, &config).unwrap();
+    /// // Well below resonance the ESL cancels a small part of the reactance
+    /// assert!(20.0*ratio.mags[0].log10() < 0.0);
+    /// assert!(20.0*ratio.mags[0].log10() > -0.01);
+    /// ```
+    pub fn plot_diff<DB: DrawingBackend, A: Plottable + ?Sized, B: Plottable + ?Sized>(a: &A, b: &B, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Sweep, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let (name_a, name_b) = (a.display_name(), b.display_name());
+        let (sweep_a, sweep_b) = config.check_finite_pair(Sweep::at(a, config.sample_freqs()), &name_a, Sweep::at(b, config.sample_freqs()), &name_b)?;
+        let ratio = sweep_a.ratio(&sweep_b);
+        let diff_db = ratio.mags.iter().map(|m| 20.0*m.log10()).collect::<Vec<f64>>();
+
+        // Symmetric about 0 dB so the sign of the difference reads at a glance
+        let extent = diff_db.iter().cloned().filter(|d| d.is_finite()).fold(0.0, |max: f64, d| max.max(d.abs()));
+        let extent = if extent > 0.0 {extent*1.1} else {1.0};
+        error::check_limits(-extent, extent)?;
+
+        let mut builder = ChartBuilder::on(&canvas);
+        if let Some(caption) = config.caption_or(&format!("{name_a} vs {name_b}")) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        if config.show_phase {
+            builder.set_label_area_size(LabelAreaPosition::Right, 40);
+        }
+        let mut chart = builder
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(config.freq_range(), -extent..extent)?;
+
+        let x_label = config.x_label();
+        let x_ticks = config.freq_label_count();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        chart.configure_mesh()
+            .x_labels(x_ticks)
+            .x_label_formatter(&x_formatter)
+            .x_desc(&x_label)
+            .y_desc(config.y_label_or("Difference [dB]"))
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(
+                vec![(config.freq_range().start, 0.0), (config.freq_range().end, 0.0)],
+                BLACK.mix(0.5)
+            ))?;
+        chart.draw_series(LineSeries::new(
+                ratio.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(diff_db),
+                &GREEN
+            ))?
+            .label("Magnitude")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+        if config.show_phase {
+            let phase_extent = ratio.phases.iter().fold(0.0, |max: f64, p| max.max(p.abs()));
+            let phase_extent = if phase_extent > 0.0 {(phase_extent*1.1).min(180.0)} else {1.0};
+            let mut chart = chart.set_secondary_coord(config.freq_range(), -phase_extent..phase_extent);
+            chart.configure_secondary_axes()
+                .x_labels(x_ticks)
+                .x_label_formatter(&x_formatter)
+                .x_desc(&x_label)
+                .y_desc(config.phase_label_or("Phase Difference [°]"))
+                .draw()?;
+            chart.draw_secondary_series(LineSeries::new(
+                    ratio.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(ratio.phases.iter().cloned()),
+                    &RED.mix(0.4)
+                ))?
+                .label("Phase")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        } else {
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        }
+
+        Ok(ratio)
+    }
+
+    /// Overlay the magnitudes of two responses and shade every frequency
+    /// range where they differ by more than `threshold_db`, e.g. to see
+    /// where a simplified model stops matching a detailed one.
+    /// Returns the shaded ranges in Hz, or an error if the plot could not
+    /// be drawn.
+    ///
+    /// # Arguments
+    /// * `a` - The first response, e.g. a detailed model.
+    /// * `b` - The second response, e.g. a simplified model.
+    /// * `threshold_db` - Largest difference in dB that still counts as a match.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plot.
+    /// * `config` - Sweep range, axes, and labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::plottable::Plottable;
+    /// use sdomain_test_plotters::sdomain_plotter::bode_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/bode_divergence.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let detailed = sdomain::gen::rcl(1e-3, 1e-6, 1e-9).named("RLC");
+    /// let simple = sdomain::gen::capacitor(1e-6).named("C only");
+    /// let config = PlotConfig {fmin: 1e3, fmax: 1e8, ..PlotConfig::default()};
+    /// let regions = bode_plotter::plot_divergence(&detailed, &simple, 3.0, &drawing_area, &config).unwrap();
+    /// assert!(!regions.is_empty());
+    /// ```
+    pub fn plot_divergence<DB: DrawingBackend, A: Plottable + ?Sized, B: Plottable + ?Sized>(a: &A, b: &B, threshold_db: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Vec<(f64, f64)>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let (name_a, name_b) = (a.display_name(), b.display_name());
+        let (sweep_a, sweep_b) = config.check_finite_pair(Sweep::at(a, config.sample_freqs()), &name_a, Sweep::at(b, config.sample_freqs()), &name_b)?;
+        let regions = sweep_a.divergence(&sweep_b, threshold_db);
+
+        let mag_scale = config.mag_scale;
+        let y_range = config.y_range(sweep_a.min_mag().min(sweep_b.min_mag()), sweep_a.max_mag().max(sweep_b.max_mag()));
+        error::check_limits(y_range.start, y_range.end)?;
+
+        let shared = SharedX::new(config, false);
+        let mut builder = shared.builder(canvas, true);
+        if let Some(caption) = config.caption_or(&format!("{name_a} vs {name_b}")) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        let y_formatter = |y: &f64| config.mag_tick(*y, "");
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&y_formatter)
+            .x_desc(&x_label)
+            .y_desc(config.y_label_or(&mag_scale.label("Magnitude", "")))
+            .draw()?;
+
+        chart.draw_series(regions.iter().map(|(start, end)| {
+                Rectangle::new(
+                    [(config.freq_to_axis(*start), y_range.start), (config.freq_to_axis(*end), y_range.end)],
+                    ORANGE.mix(0.3).filled()
+                )
+            }))?
+            .label(format!("Differ by > {threshold_db} dB"))
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], ORANGE.mix(0.3).filled()));
+        for (name, sweep, color) in [(&name_a, &sweep_a, GREEN), (&name_b, &sweep_b, BLUE)] {
+            chart.draw_series(LineSeries::new(
+                    sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                    &color
+                ))?
+                .label(name.as_str())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(regions)
+    }
+
+    /// Plot the frequency response of a model discretized at `sample_rate`,
+    /// up to the Nyquist frequency, over the continuous response it came
+    /// from. The gap between the two curves near Nyquist shows the warping of
+    /// the bilinear transform or the lag of the zero-order hold, which a
+    /// digital compensator designed from the analog model has to allow for.
+    /// The discrete curve is evaluated from the z-domain coefficients found by
+    /// `Discrete::new`, the same ones a compensator would be implemented with.
+    /// The sweep starts at `config.fmin` and ends just short of Nyquist,
+    /// whatever `config.fmax` is.
+    /// Returns the discrete response, or an error if the model could not be
+    /// discretized or plotted.
+    ///
+    /// # Arguments
+    /// * `model` - The continuous-time model. Its display name is printed in
+    ///             the plot title.
+    /// * `sample_rate` - Sample rate in Hz.
+    /// * `method` - How the model is discretized.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plot.
+    /// * `config` - Sweep start, axes, and labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::discrete::Discretization;
+    /// use sdomain_test_plotters::sdomain_plotter::bode_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/bode_discrete.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let zr = sdomain::gen::resistor(100.0);
+    /// let zc = sdomain::gen::capacitor(1e-6);
+    /// let lpf = zc.clone() / &(zr + &zc);
+    /// let config = PlotConfig {fmin: 10.0, ..PlotConfig::default()};
+    /// let sweep = bode_plotter::plot_discrete(&lpf, 20e3, Discretization::Bilinear, &drawing_area, &config).unwrap();
+    /// assert!(*sweep.freqs.last().unwrap() < 10e3);
+    /// ```
+    pub fn plot_discrete<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, sample_rate: f64, method: Discretization, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Sweep, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let discrete = Discrete::new(model, sample_rate, method)?;
+        // Both bilinear transforms put Nyquist at infinite analog frequency
+        let config = PlotConfig {fmax: config.freq_unit.from_hz(0.999*discrete.nyquist()), ..config.clone()};
+        error::check_range(config.fmin, config.fmax)?;
+        let name = model.display_name();
+        let analog = config.check_finite(Sweep::at(model, config.sample_freqs()), &name)?;
+        let digital = config.check_finite(discrete.sweep(config.sample_freqs()), &name)?;
+
+        let mag_scale = config.mag_scale;
+        let y_range = config.y_range(analog.min_mag().min(digital.min_mag()), analog.max_mag().max(digital.max_mag()));
+        error::check_limits(y_range.start, y_range.end)?;
+
+        let shared = SharedX::new(&config, config.show_phase);
+        let mut builder = shared.builder(canvas, true);
+        let method_name = match method {
+            Discretization::Bilinear => "Bilinear".to_string(),
+            Discretization::Prewarped(freq) => format!("Bilinear, prewarped at {}", format_freq(freq)),
+            Discretization::ZeroOrderHold => "Zero-Order Hold".to_string(),
+        };
+        if let Some(caption) = config.caption_or(&format!("{name} at {} ({method_name})", format_freq(sample_rate))) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder
+            .build_cartesian_2d(shared.range.clone(), y_range.clone())?
+            .set_secondary_coord(shared.range.clone(), config.phase_range.0..config.phase_range.1);
+
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        let y_formatter = |y: &f64| config.mag_tick(*y, "");
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&y_formatter)
+            .x_desc(&x_label)
+            .y_desc(config.y_label_or(&mag_scale.label("Magnitude", "")))
+            .draw()?;
+        if config.show_phase {
+            chart.configure_secondary_axes()
+                .x_labels(shared.labels)
+                .x_label_formatter(&x_formatter)
+                .y_desc(config.phase_label_or("Phase [°]"))
+                .draw()?;
+        }
+
+        for (label, sweep, color) in [("Continuous", &analog, BLUE), ("Discrete", &digital, GREEN)] {
+            chart.draw_series(LineSeries::new(
+                    sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                    &color
+                ))?
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+            if config.show_phase {
+                let color = color.mix(0.4);
+                chart.draw_secondary_series(LineSeries::new(
+                        sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(sweep)),
+                        &color
+                    ))?
+                    .label(format!("{label} Phase"))
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+            }
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(digital)
+    }
+
+    /// Draw the phase, or the group delay if configured, on its own panel
+    /// under a bode plot's magnitude chart, with the phase margin marked if
+    /// margins are annotated.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `area` - The DrawingArea of the panel.
+    /// * `shared` - Frequency axis of the magnitude chart above.
+    /// * `sweep` - The response plotted above.
+    /// * `config` - Options of the plot.
+    /// * `margins` - Margins of the response, for the phase margin marker.
+    fn draw_phase_panel<DB: DrawingBackend>(area: &DrawAreaType<DB>, shared: &SharedX, sweep: &Sweep, config: &PlotConfig, margins: &StabilityMargins) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let (values, y_range, label, series) = if config.group_delay {
+            let delays = sweep.group_delays();
+            let finite = delays.iter().cloned().filter(|d| d.is_finite());
+            let min_delay = finite.clone().fold(0.0, f64::min);
+            let max_delay = finite.fold(0.0, f64::max);
+            let pad = (max_delay - min_delay).max(1e-12)*0.1;
+            (delays, min_delay - pad..max_delay + pad, config.phase_label_or("Group Delay"), "Group Delay")
+        } else {
+            (config.phases(sweep), config.phase_range.0..config.phase_range.1, config.phase_label_or("Phase [°]"), "Phase")
+        };
+
+        let mut chart = shared.builder(area, true)
+            .build_cartesian_2d(shared.range.clone(), y_range)?;
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+        let y_formatter = |y: &f64| if config.group_delay {format_eng(*y, "s", 3)} else {format_plain(*y)};
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_label_formatter(&y_formatter)
+            .x_desc(&x_label)
+            .y_desc(label)
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(
+                sweep.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(values),
+                &RED
+            ))?
+            .label(series)
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+
+        // The phase margin is the distance from -180° up to the curve
+        if let (false, true, Some(freq), Some(phase_margin)) = (config.group_delay, config.annotate_margins, margins.gain_crossover, margins.phase_margin) {
+            let point = (config.freq_to_axis(freq), config.phase_to_axis(phase_margin - 180.0));
+            chart.draw_series(LineSeries::new(vec![(point.0, -180.0), point], MAGENTA.stroke_width(2)))?;
+            chart.draw_series(std::iter::once(
+                    EmptyElement::at(point)
+                    + Circle::new((0, 0), 4, MAGENTA.filled())
+                    + Text::new(format!("PM = {phase_margin:.1}°"), (8, 8), ("Arial", 15))
+                ))?;
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(())
+    }
+
+    /// Draw the stability margins as a block of text in the upper left
+    /// corner of a bode plot, inside the plotting area.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `canvas` - The DrawingArea the bode plot was drawn on.
+    /// * `margins` - The margins to list.
+    /// * `config` - Options of the plot, for the frequency unit.
+    fn draw_margin_block<DB: DrawingBackend>(canvas: &DrawAreaType<DB>, margins: &StabilityMargins, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let at = |freq: Option<f64>| freq.map(|f| format!(" @ {}", config.freq_unit.format(config.freq_unit.from_hz(f)))).unwrap_or_default();
+        let lines = [
+            match margins.gain_margin {
+                Some(gain_margin) => format!("GM = {gain_margin:.1} dB{}", at(margins.phase_crossover)),
+                None => "GM = ∞".to_string(),
+            },
+            match margins.phase_margin {
+                Some(phase_margin) => format!("PM = {phase_margin:.1}°{}", at(margins.gain_crossover)),
+                None => "PM = n/a (no 0 dB crossover)".to_string(),
+            },
+            match margins.delay_margin {
+                Some(delay_margin) => format!("DM = {}", format_eng(delay_margin, "s", 3)),
+                None => "DM = n/a".to_string(),
+            },
+        ];
+        let (left, top) = (70, 60);
+        let right = left + 8*lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as i32 + 20;
+        let bottom = top + 10 + 18*lines.len() as i32;
+
+        canvas.draw(&Rectangle::new([(left, top), (right, bottom)], WHITE.mix(0.8).filled()))?;
+        canvas.draw(&Rectangle::new([(left, top), (right, bottom)], &BLACK))?;
+        for (index, line) in lines.into_iter().enumerate() {
+            canvas.draw(&Text::new(line, (left + 10, top + 5 + 18*index as i32), ("Arial", 15)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw a compact table of poles and zeros in the upper right corner of
+    /// a bode plot, inside the plotting area.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `canvas` - The DrawingArea the bode plot was drawn on.
+    /// * `roots` - The poles and zeros to list.
+    /// * `estimated` - Whether the roots were estimated from the response,
+    ///                 which the title says.
+    /// * `right_axis` - Whether the chart has a secondary axis on the right
+    ///                  to keep the table clear of.
+    fn draw_root_table<DB: DrawingBackend>(canvas: &DrawAreaType<DB>, roots: &[Root], estimated: bool, right_axis: bool) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let title = if estimated {"Estimated Poles & Zeros"} else {"Poles & Zeros"};
+        let lines = if roots.is_empty() {vec!["No poles or zeros found".to_string()]}
+            else {roots.iter().map(|root| format!("{root}")).collect::<Vec<String>>()};
+        let (width, _) = canvas.dim_in_pixel();
+        let widest = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0).max(title.len());
+        let table_width = 8*widest as i32 + 20;
+        let right = width as i32 - if right_axis {70} else {30};
+        let (left, top) = (right - table_width, 60);
+        let bottom = top + 25 + 18*lines.len() as i32;
+
+        canvas.draw(&Rectangle::new([(left, top), (right, bottom)], WHITE.mix(0.8).filled()))?;
+        canvas.draw(&Rectangle::new([(left, top), (right, bottom)], &BLACK))?;
+        canvas.draw(&Text::new(title.to_string(), (left + 10, top + 5), ("Arial", 15)))?;
+        for (index, line) in lines.into_iter().enumerate() {
+            canvas.draw(&Text::new(line, (left + 10, top + 25 + 18*index as i32), ("monospace", 13)))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,462 @@
+use std::f64::consts::{PI, SQRT_2};
+use std::fmt;
+
+use sdomain_test::range_generators::gen_log_range;
+use sdomain_test::sdomain::{self, Fs};
+
+use crate::axis::MagScale;
+use crate::plottable::Plottable;
+use crate::spice;
+use crate::sweep::Sweep;
+use crate::time_domain::Pwl;
+
+/// Number of samples per period used when transforming a waveform.
+pub const DFT_POINTS: usize = 1024;
+
+/// A line spectrum: the peak amplitude of each discrete frequency component.
+#[derive(Clone, Debug)]
+pub struct Spectrum {
+    /// Component frequencies in Hz.
+    pub freqs: Vec<f64>,
+    /// Peak amplitude of each component.
+    pub amps: Vec<f64>,
+}
+
+/// Reason a spectrum could not be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpectrumError {
+    /// A value could not be parsed as a number. Values are numbered from 1.
+    Value {index: usize, text: String},
+    /// The values do not pair up into (frequency, amplitude) components.
+    Unpaired,
+    /// No components were given.
+    Empty,
+}
+
+impl fmt::Display for SpectrumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpectrumError::Value {index, text} => write!(f, "invalid value {index} \"{text}\""),
+            SpectrumError::Unpaired => write!(f, "odd number of values, expected frequency and amplitude pairs"),
+            SpectrumError::Empty => write!(f, "no components"),
+        }
+    }
+}
+
+impl std::error::Error for SpectrumError {}
+
+impl Spectrum {
+    /// Create a spectrum from a list of (frequency, peak amplitude) harmonics.
+    pub fn from_harmonics(harmonics: &[(f64, f64)]) -> Spectrum {
+        Spectrum {
+            freqs: harmonics.iter().map(|h| h.0).collect(),
+            amps: harmonics.iter().map(|h| h.1).collect(),
+        }
+    }
+
+    /// Read a spectrum from text listing frequency and peak amplitude pairs,
+    /// such as a two-column export of a measured or simulated current
+    /// spectrum. Values may be separated by spaces, commas, or line breaks
+    /// and use SPICE suffixes. Lines starting with `*` or `#` are comments.
+    /// Returns the spectrum, or an error naming the value that could not be
+    /// read.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum::{Spectrum, SpectrumError};
+    ///
+    /// let current = Spectrum::parse("# freq, amps\n500k, 0.6\n1Meg, 0.2\n1.5Meg, 0.12\n").unwrap();
+    /// assert_eq!(current.freqs, vec![500e3, 1e6, 1.5e6]);
+    /// assert_eq!(current.amps[1], 0.2);
+    ///
+    /// assert_eq!(Spectrum::parse("1Meg 0.2 2Meg").unwrap_err(), SpectrumError::Unpaired);
+    /// ```
+    pub fn parse(text: &str) -> Result<Spectrum, SpectrumError> {
+        let values = text.lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('*') && !line.starts_with('#'))
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|field| !field.is_empty())
+            .enumerate()
+            .map(|(index, field)| spice::parse_value(field).ok_or(SpectrumError::Value {index: index + 1, text: field.to_string()}))
+            .collect::<Result<Vec<f64>, SpectrumError>>()?;
+        if values.is_empty() {
+            return Err(SpectrumError::Empty);
+        }
+        if values.len() % 2 != 0 {
+            return Err(SpectrumError::Unpaired);
+        }
+        Ok(Spectrum::from_harmonics(&values.chunks(2).map(|pair| (pair[0], pair[1])).collect::<Vec<(f64, f64)>>()))
+    }
+
+    /// Compute the spectrum of a waveform that repeats every `period` seconds.
+    /// One period starting at t = 0 is sampled and transformed; the DC
+    /// component is discarded.
+    pub fn from_waveform(waveform: &Pwl, period: f64) -> Spectrum {
+        let n = DFT_POINTS;
+        let dt = period/n as f64;
+        let samples = (0..n).map(|k| waveform.value_at(k as f64*dt)).collect::<Vec<f64>>();
+        let (freqs, amps): (Vec<f64>, Vec<f64>) = (1..n/2)
+            .map(|k| {
+                let (mut re, mut im) = (0.0f64, 0.0f64);
+                for (j, x) in samples.iter().enumerate() {
+                    let angle = -2.0*PI*(k*j) as f64/n as f64;
+                    re += x*angle.cos();
+                    im += x*angle.sin();
+                }
+                (k as f64/period, 2.0*(re*re + im*im).sqrt()/n as f64)
+            })
+            .unzip();
+        Spectrum {freqs, amps}
+    }
+
+    /// Scale each component by the magnitude of a transfer function (e.g.
+    /// multiply a current spectrum by an impedance to get a voltage spectrum).
+    pub fn through<P: Plottable + ?Sized>(&self, model: &P) -> Spectrum {
+        let sweep = Sweep::at(model, self.freqs.clone());
+        Spectrum {
+            freqs: self.freqs.clone(),
+            amps: self.amps.iter().zip(sweep.mags.iter()).map(|(a, m)| a*m).collect(),
+        }
+    }
+
+    /// Iterate over (frequency, amplitude) pairs.
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.freqs.iter().cloned().zip(self.amps.iter().cloned())
+    }
+
+    /// Worst-case peak of the waveform: the sum of the component amplitudes,
+    /// reached if every component peaks at the same instant.
+    pub fn peak(&self) -> f64 {
+        self.amps.iter().sum()
+    }
+}
+
+/// Impedance of the CISPR 16 50Ω/50µH + 5Ω line impedance stabilization
+/// network (LISN) seen by the equipment under test on each line: the 50Ω
+/// receiver port in parallel with 50µH and 5Ω to the mains.
+pub fn cispr16_lisn() -> Fs {
+    sdomain::parallel(sdomain::gen::resistor(50.0), sdomain::gen::rl(5.0, 50e-6))
+}
+
+/// Conducted emissions read by the receiver on a LISN: the noise current
+/// of the equipment, a Norton source shunted by its own input impedance
+/// `source`, divides between that impedance and the `lisn`, so the voltage
+/// at the receiver port is I·(Zsource ‖ Zlisn).
+/// Returns the voltage spectrum in V peak.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::spectrum::{self, Spectrum};
+///
+/// use sdomain_test::sdomain;
+///
+/// // 10mA at 1MHz into a converter with a 10uF input capacitor
+/// let current = Spectrum::from_harmonics(&[(1e6, 10e-3)]);
+/// let emissions = spectrum::conducted_emissions(&current, &sdomain::gen::capacitor(10e-6), &spectrum::cispr16_lisn());
+///
+/// // The capacitor's 16mΩ shunts nearly all of the current
+/// let z_cap = 1.0/(2.0*std::f64::consts::PI*1e6*10e-6);
+/// assert!((emissions.amps[0]/(10e-3*z_cap) - 1.0).abs() < 1e-3);
+/// ```
+pub fn conducted_emissions(current: &Spectrum, source: &Fs, lisn: &Fs) -> Spectrum {
+    current.through(&sdomain::parallel(source.clone(), lisn.clone()))
+}
+
+/// Convert a voltage amplitude to dBµV.
+pub fn to_dbuv(volts: f64) -> f64 {
+    20.0*(volts/1e-6).log10()
+}
+
+/// Convert the peak amplitude of a sinusoidal component to its rms value,
+/// the value an EMI receiver reads and emissions limits are given in.
+pub fn peak_to_rms(peak: f64) -> f64 {
+    peak/SQRT_2
+}
+
+/// Unit of a noise density drawn on `scale`: V/√Hz in volts or dBµV,
+/// and dBm/Hz as a power density.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::axis::MagScale;
+/// use sdomain_test_plotters::spectrum;
+///
+/// assert_eq!(spectrum::density_unit(MagScale::Log), "V/√Hz");
+/// assert_eq!(spectrum::density_unit(MagScale::DbMicrovolt), "dBµV/√Hz");
+/// assert_eq!(spectrum::density_unit(MagScale::Dbm(50.0)), "dBm/Hz");
+/// ```
+pub fn density_unit(scale: MagScale) -> String {
+    match scale {
+        MagScale::Dbm(_) => "dBm/Hz".to_string(),
+        _ => format!("{}/√Hz", scale.unit("V")),
+    }
+}
+
+/// A voltage noise density, such as the output noise of a regulator, in
+/// V/√Hz over frequency. Between samples the density is interpolated
+/// linearly on log-log axes, so a few corners describe a flat floor with a
+/// 1/f region.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::spectrum::NoiseDensity;
+///
+/// // 100nV/√Hz white noise with a 1/f corner at 1kHz
+/// let vrm = NoiseDensity::from_points(&[(10.0, 1e-6), (1e3, 100e-9), (10e6, 100e-9)]);
+/// assert!((vrm.at(100.0) - (1e-6*100e-9f64).sqrt()).abs() < 1e-15);
+///
+/// // Integrated over 1kHz to 1MHz the white floor gives 100nV·√999kHz
+/// let floor = NoiseDensity::from_points(&[(1e3, 100e-9), (1e6, 100e-9)]);
+/// assert!((floor.rms() - 100e-9*999e3f64.sqrt()).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoiseDensity {
+    /// Frequencies in Hz, ascending.
+    pub freqs: Vec<f64>,
+    /// Noise density in V/√Hz rms at each frequency.
+    pub densities: Vec<f64>,
+}
+
+impl NoiseDensity {
+    /// Create a density from (frequency, V/√Hz) points. Points are sorted
+    /// by frequency.
+    pub fn from_points(points: &[(f64, f64)]) -> NoiseDensity {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        NoiseDensity {
+            freqs: points.iter().map(|p| p.0).collect(),
+            densities: points.iter().map(|p| p.1).collect(),
+        }
+    }
+
+    /// Density at `freq`, interpolated on log-log axes and held flat
+    /// beyond the first and last points.
+    pub fn at(&self, freq: f64) -> f64 {
+        let (first, last) = match (self.freqs.first(), self.freqs.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 0.0,
+        };
+        if freq <= first {return self.densities[0];}
+        if freq >= last {return self.densities[self.densities.len() - 1];}
+        let index = self.freqs.windows(2).position(|pair| freq <= pair[1]).unwrap_or(0);
+        let (f0, f1) = (self.freqs[index], self.freqs[index + 1]);
+        let (d0, d1) = (self.densities[index], self.densities[index + 1]);
+        if d0 <= 0.0 || d1 <= 0.0 {
+            return d0 + (d1 - d0)*(freq - f0)/(f1 - f0);
+        }
+        d0*(d1/d0).powf((freq/f0).ln()/(f1/f0).ln())
+    }
+
+    /// The density at 100 points per decade over `fmin..fmax` in Hz.
+    pub fn resample(&self, fmin: f64, fmax: f64) -> NoiseDensity {
+        let freqs = gen_log_range(fmin, fmax, 10.0, 100);
+        NoiseDensity {densities: freqs.iter().map(|f| self.at(*f)).collect(), freqs}
+    }
+
+    /// Scale the density by the magnitude of a transfer function, e.g. the
+    /// transfer from a regulator's output to a load on the PDN.
+    pub fn through<P: Plottable + ?Sized>(&self, model: &P) -> NoiseDensity {
+        let sweep = Sweep::at(model, self.freqs.clone());
+        NoiseDensity {
+            freqs: self.freqs.clone(),
+            densities: self.densities.iter().zip(sweep.mags.iter()).map(|(d, m)| d*m).collect(),
+        }
+    }
+
+    /// Total RMS noise in volts over the frequencies of the density, the
+    /// square root of the integrated power density.
+    pub fn rms(&self) -> f64 {
+        self.freqs.windows(2).zip(self.densities.windows(2))
+            .map(|(f, d)| 0.5*(d[0]*d[0] + d[1]*d[1])*(f[1] - f[0]))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Iterate over (frequency, density) pairs, the density as a position
+    /// along an axis on `scale`. Densities are rms, so they are taken as
+    /// given by the rms voltage scales.
+    pub fn points(&self, scale: MagScale) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.freqs.iter().cloned().zip(self.densities.iter().map(move |d| scale.to_axis(*d)))
+    }
+}
+
+/// An emissions limit line in dBµV over a frequency range, such as a
+/// conducted emissions limit of CISPR 32. Between corners the limit is
+/// linear in dB against log frequency. A step is given as two corners at
+/// the same frequency, and the lower limit applies at the step itself.
+/// Outside its frequency range a limit line does not apply. Limits are rms
+/// readings, so the peak amplitudes of a spectrum are converted to rms
+/// before they are compared.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::spectrum::{EmiLimit, Spectrum};
+///
+/// let limit = EmiLimit::cispr32_class_b_qp();
+/// assert_eq!(limit.limit_at(100e3), None);
+/// // Falls with log frequency from 66 dBµV at 150kHz to 56 dBµV at 500kHz
+/// assert!((limit.limit_at((150e3f64*500e3).sqrt()).unwrap() - 61.0).abs() < 1e-9);
+/// assert_eq!(limit.limit_at(5e6), Some(56.0));
+/// assert_eq!(limit.limit_at(10e6), Some(60.0));
+///
+/// // 1.41mV peak at 1MHz is 1mV rms or 60 dBµV, 4 dB over the limit
+/// let noise = Spectrum::from_harmonics(&[(1e6, 2f64.sqrt()*1e-3), (10e6, 1e-4)]);
+/// let (freq, margin) = limit.worst_margin(&noise).unwrap();
+/// assert_eq!(freq, 1e6);
+/// assert!((margin + 4.0).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmiLimit {
+    /// Name to print in plot legends.
+    pub name: String,
+    points: Vec<(f64, f64)>,
+}
+
+impl EmiLimit {
+    /// A custom limit line from (frequency in Hz, limit in dBµV) corners.
+    /// Corners are sorted by frequency, keeping the order of corners at the
+    /// same frequency.
+    pub fn custom(name: &str, mut points: Vec<(f64, f64)>) -> EmiLimit {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        EmiLimit {name: name.to_string(), points}
+    }
+
+    /// CISPR 32 Class B conducted emissions limit on AC mains, quasi-peak.
+    pub fn cispr32_class_b_qp() -> EmiLimit {
+        EmiLimit::custom("CISPR 32 Class B QP", vec![(150e3, 66.0), (500e3, 56.0), (5e6, 56.0), (5e6, 60.0), (30e6, 60.0)])
+    }
+
+    /// CISPR 32 Class B conducted emissions limit on AC mains, average.
+    pub fn cispr32_class_b_avg() -> EmiLimit {
+        EmiLimit::custom("CISPR 32 Class B AV", vec![(150e3, 56.0), (500e3, 46.0), (5e6, 46.0), (5e6, 50.0), (30e6, 50.0)])
+    }
+
+    /// CISPR 32 Class A conducted emissions limit on AC mains, quasi-peak.
+    pub fn cispr32_class_a_qp() -> EmiLimit {
+        EmiLimit::custom("CISPR 32 Class A QP", vec![(150e3, 79.0), (500e3, 79.0), (500e3, 73.0), (30e6, 73.0)])
+    }
+
+    /// CISPR 32 Class A conducted emissions limit on AC mains, average.
+    pub fn cispr32_class_a_avg() -> EmiLimit {
+        EmiLimit::custom("CISPR 32 Class A AV", vec![(150e3, 66.0), (500e3, 66.0), (500e3, 60.0), (30e6, 60.0)])
+    }
+
+    /// Limit in dBµV at `freq`, or `None` outside the frequency range of
+    /// the line.
+    pub fn limit_at(&self, freq: f64) -> Option<f64> {
+        let (first, last) = (self.points.first()?, self.points.last()?);
+        if freq < first.0 || freq > last.0 {return None;}
+        let at_corner = self.points.iter().filter(|(f, _)| *f == freq).map(|(_, db)| *db).reduce(f64::min);
+        if at_corner.is_some() {return at_corner;}
+        self.points.windows(2)
+            .find(|pair| pair[0].0 < freq && freq < pair[1].0)
+            .map(|pair| {
+                let ((f0, db0), (f1, db1)) = (pair[0], pair[1]);
+                db0 + (db1 - db0)*(freq/f0).ln()/(f1/f0).ln()
+            })
+    }
+
+    /// Frequency range in Hz covered by the line.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        Some((self.points.first()?.0, self.points.last()?.0))
+    }
+
+    /// Corners of the line, suitable for drawing as a line series.
+    pub fn outline(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Component of a voltage spectrum closest to or furthest over the
+    /// limit, as (frequency, margin in dB), negative when over the limit.
+    /// The spectrum is in V peak and each component is compared as rms.
+    /// Returns `None` if no component falls within the range of the line.
+    pub fn worst_margin(&self, noise: &Spectrum) -> Option<(f64, f64)> {
+        noise.points()
+            .filter(|(_, v)| *v > 0.0)
+            .filter_map(|(f, v)| self.limit_at(f).map(|limit| (f, limit - to_dbuv(peak_to_rms(v)))))
+            .reduce(|worst, point| if point.1 < worst.1 {point} else {worst})
+    }
+}
+
+/// Spread-spectrum dithering of a switching frequency between `f_low` and
+/// `f_high`, as seen by a receiver of resolution bandwidth `rbw`. Each
+/// harmonic of the center frequency is smeared over a band n times as wide
+/// as the dithering range. Once the band is wider than the receiver
+/// bandwidth, the receiver only catches the fraction rbw/width of its power,
+/// so the reading drops by 10·log10(width/rbw) dB. Where the bands of
+/// neighboring harmonics overlap their powers add. Dithering narrower than
+/// the receiver bandwidth brings no benefit.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::spectrum::{Spectrum, SpreadSpectrum};
+///
+/// // ±5% around 1MHz, read with the 9kHz CISPR bandwidth
+/// let dither = SpreadSpectrum::new(950e3, 1.05e6);
+/// assert_eq!(dither.center(), 1e6);
+///
+/// // The fundamental spreads over 100kHz, 10.4 dB down
+/// assert!((dither.reduction_db(1e6) - 10.0*(100e3f64/9e3).log10()).abs() < 1e-9);
+///
+/// let comb = Spectrum::from_harmonics(&[(1e6, 1e-3), (2e6, 0.5e-3)]);
+/// let bands = dither.smooth(&comb);
+/// let (from, to, amp) = bands[0];
+/// assert!((from - 950e3).abs() < 1e-6 && (to - 1.05e6).abs() < 1e-6);
+/// assert!((amp - 1e-3*(9e3f64/100e3).sqrt()).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpreadSpectrum {
+    /// Lowest switching frequency of the dithering in Hz.
+    pub f_low: f64,
+    /// Highest switching frequency of the dithering in Hz.
+    pub f_high: f64,
+    /// Resolution bandwidth of the receiver in Hz.
+    pub rbw: f64,
+}
+
+impl SpreadSpectrum {
+    /// Dithering between `f_low` and `f_high` in Hz, read with the 9kHz
+    /// bandwidth CISPR 16 uses from 150kHz to 30MHz.
+    pub fn new(f_low: f64, f_high: f64) -> SpreadSpectrum {
+        SpreadSpectrum {f_low, f_high, rbw: 9e3}
+    }
+
+    /// Center switching frequency in Hz.
+    pub fn center(&self) -> f64 {
+        (self.f_low + self.f_high)/2.0
+    }
+
+    /// Drop in dB of a lone spectral line at `freq`, a harmonic of the
+    /// center frequency, once dithered.
+    pub fn reduction_db(&self, freq: f64) -> f64 {
+        let width = freq*(self.f_high - self.f_low)/self.center();
+        10.0*(width/self.rbw).max(1.0).log10()
+    }
+
+    /// Smoothed envelope of a spectrum of harmonics of the center frequency,
+    /// as (from, to, amplitude) bands in Hz and the units of `spectrum`,
+    /// sorted by frequency. Gaps between the bands of neighboring
+    /// harmonics are left out.
+    pub fn smooth(&self, spectrum: &Spectrum) -> Vec<(f64, f64, f64)> {
+        let center = self.center();
+        // (band start, band end, power caught by the receiver) of each line
+        let spread = spectrum.points()
+            .filter(|(f, a)| *f > 0.0 && *a > 0.0)
+            .map(|(f, a)| (f*self.f_low/center, f*self.f_high/center, a*a*10f64.powf(-self.reduction_db(f)/10.0)))
+            .collect::<Vec<(f64, f64, f64)>>();
+        let mut edges = spread.iter().flat_map(|(from, to, _)| [*from, *to]).collect::<Vec<f64>>();
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+        edges.windows(2)
+            .filter_map(|pair| {
+                let middle = (pair[0] + pair[1])/2.0;
+                let power = spread.iter()
+                    .filter(|(from, to, _)| *from <= middle && middle <= *to)
+                    .map(|(_, _, power)| power)
+                    .sum::<f64>();
+                (power > 0.0).then_some((pair[0], pair[1], power.sqrt()))
+            })
+            .collect()
+    }
+}
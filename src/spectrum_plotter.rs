@@ -0,0 +1,536 @@
+pub mod noise_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{PURPLE, GREY};
+    use sdomain_test::range_generators::gen_log_range;
+    use sdomain_test::sdomain::{self, Fs};
+
+    use crate::axis::MagScale;
+    use crate::bands::{self, FreqBand};
+    use crate::error::{self, PlotError};
+    use crate::plottable::Plottable;
+    use crate::spectrum::{self, EmiLimit, NoiseDensity, Spectrum, SpreadSpectrum};
+    use crate::units::{format_plain, format_si};
+    use crate::vrm::BuckRipple;
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Caption of the PDN voltage noise plots.
+    const PDN_TITLE: &str = "Predicted Voltage Noise Spectrum of PDN";
+
+    /// Plot the predicted voltage noise spectrum of a PDN, found by scaling
+    /// each component of a load current spectrum by the PDN impedance at that
+    /// frequency. The result is drawn as rms spectral lines on the chosen
+    /// scale, which can be compared directly against a spectrum analyzer
+    /// measurement.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `current` - Load current spectrum in amps peak.
+    /// * `scale` - Scale of the noise axis, usually `MagScale::DbMicrovolt`
+    ///             like the limits or `MagScale::Dbm(50.0)` like a spectrum
+    ///             analyzer. Components are drawn as rms voltages.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the noise plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::spectrum::Spectrum;
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_noise.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 1A trapezoidal load repeating at 1MHz
+    /// let load = Pwl::from(vec![(0.0, 0.0), (10e-9, 1.0), (490e-9, 1.0), (500e-9, 0.0)]);
+    /// let current = Spectrum::from_waveform(&load, 1e-6);
+    /// noise_plotter::plot(&pdn, &current, MagScale::DbMicrovolt, &drawing_area).unwrap();
+    /// ```
+    pub fn plot<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, current: &Spectrum, scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw(model, current, &[], &[], None, PDN_TITLE, scale, canvas).map(|_| ())
+    }
+
+    /// Plot the predicted voltage noise spectrum of a PDN against emissions
+    /// limit lines, such as the CISPR 32 conducted limits or custom masks,
+    /// so the prediction can be judged directly against compliance. Spectral
+    /// lines over any limit are drawn in red.
+    /// Returns, for each limit in order, the frequency of the component with
+    /// the least margin and that margin in dB, negative when over the limit,
+    /// or `None` if no component falls within the range of the limit.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `current` - Load current spectrum in amps peak.
+    /// * `limits` - Limit lines to overlay.
+    /// * `scale` - Scale of the noise axis, usually `MagScale::DbMicrovolt`
+    ///             like the limits or `MagScale::Dbm(50.0)` like a spectrum
+    ///             analyzer. Components are drawn as rms voltages.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the noise plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::spectrum::{EmiLimit, Spectrum};
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_noise_limits.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 1A trapezoidal load repeating at 500kHz
+    /// let load = Pwl::from(vec![(0.0, 0.0), (10e-9, 1.0), (990e-9, 1.0), (1e-6, 0.0)]);
+    /// let current = Spectrum::from_waveform(&load, 2e-6);
+    /// let limits = [
+    ///     EmiLimit::cispr32_class_b_qp(),
+    ///     EmiLimit::cispr32_class_b_avg(),
+    ///     EmiLimit::custom("Internal", vec![(1e6, 70.0), (100e6, 40.0)]),
+    /// ];
+    /// let margins = noise_plotter::plot_with_limits(&pdn, &current, &limits, MagScale::DbMicrovolt, &drawing_area).unwrap();
+    /// assert_eq!(margins.len(), 3);
+    /// assert!(margins[0].is_some());
+    /// ```
+    pub fn plot_with_limits<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, current: &Spectrum, limits: &[EmiLimit], scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<Vec<Option<(f64, f64)>>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw(model, current, limits, &[], None, PDN_TITLE, scale, canvas)
+    }
+
+    /// Plot the predicted voltage noise spectrum of a PDN over shaded
+    /// frequency bands of concern, such as the broadcast bands or the CISPR
+    /// measurement bands, optionally against emissions limits.
+    /// Returns the worst margin to each limit, as for `plot_with_limits`.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `current` - Load current spectrum in amps peak.
+    /// * `limits` - Limit lines to overlay, possibly none.
+    /// * `bands` - Frequency bands to shade behind the spectral lines.
+    /// * `scale` - Scale of the noise axis, usually `MagScale::DbMicrovolt`
+    ///             like the limits or `MagScale::Dbm(50.0)` like a spectrum
+    ///             analyzer. Components are drawn as rms voltages.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the noise plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::bands::FreqBand;
+    /// use sdomain_test_plotters::spectrum::Spectrum;
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_noise_bands.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 1A trapezoidal load repeating at 1MHz
+    /// let load = Pwl::from(vec![(0.0, 0.0), (10e-9, 1.0), (490e-9, 1.0), (500e-9, 0.0)]);
+    /// let current = Spectrum::from_waveform(&load, 1e-6);
+    /// let bands = [FreqBand::am_broadcast(), FreqBand::fm_broadcast()];
+    /// noise_plotter::plot_with_bands(&pdn, &current, &[], &bands, MagScale::Dbm(50.0), &drawing_area).unwrap();
+    /// ```
+    pub fn plot_with_bands<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, current: &Spectrum, limits: &[EmiLimit], bands: &[FreqBand], scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<Vec<Option<(f64, f64)>>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw(model, current, limits, bands, None, PDN_TITLE, scale, canvas)
+    }
+
+    /// Plot the predicted voltage noise spectrum of a PDN as it would read
+    /// with the switching frequency dithered, to evaluate the benefit of
+    /// spread-spectrum clocking. The undithered comb of spectral lines is
+    /// drawn over the smoothed envelope the receiver sees once every
+    /// harmonic is spread over its band, optionally against emissions
+    /// limits. The current spectrum is taken as harmonics of the dithering's
+    /// center frequency.
+    /// Returns the smoothed envelope of the voltage noise as (from, to,
+    /// amplitude in V) bands.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `current` - Undithered load current spectrum in amps peak.
+    /// * `dither` - The dithering range and the receiver bandwidth.
+    /// * `limits` - Limit lines to overlay, possibly none.
+    /// * `scale` - Scale of the noise axis, usually `MagScale::DbMicrovolt`
+    ///             like the limits or `MagScale::Dbm(50.0)` like a spectrum
+    ///             analyzer. Components are drawn as rms voltages.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the noise plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::spectrum::{EmiLimit, Spectrum, SpreadSpectrum};
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_noise_sscg.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 1A trapezoidal load switching at 500kHz, dithered by ±10%
+    /// let load = Pwl::from(vec![(0.0, 0.0), (10e-9, 1.0), (990e-9, 1.0), (1e-6, 0.0)]);
+    /// let current = Spectrum::from_waveform(&load, 2e-6);
+    /// let dither = SpreadSpectrum::new(450e3, 550e3);
+    /// let bands = noise_plotter::plot_spread_spectrum(&pdn, &current, &dither, &[EmiLimit::cispr32_class_b_avg()], MagScale::DbMicrovolt, &drawing_area).unwrap();
+    ///
+    /// // The envelope peaks below the tallest undithered line
+    /// let noise = current.through(&pdn);
+    /// let line_peak = noise.amps.iter().cloned().fold(0.0, f64::max);
+    /// let envelope_peak = bands.iter().map(|b| b.2).fold(0.0, f64::max);
+    /// assert!(envelope_peak < line_peak);
+    /// ```
+    pub fn plot_spread_spectrum<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, current: &Spectrum, dither: &SpreadSpectrum, limits: &[EmiLimit], scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<Vec<(f64, f64, f64)>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw(model, current, limits, &[], Some(dither), PDN_TITLE, scale, canvas)?;
+        Ok(dither.smooth(&current.through(model)))
+    }
+
+    /// Plot an estimate of the conducted emissions of a piece of equipment
+    /// as read on a LISN, from its noise current spectrum, e.g. imported
+    /// with `Spectrum::parse`, and the impedances the current divides
+    /// between. The voltage at the receiver port is drawn as rms spectral
+    /// lines against emissions limit lines, with lines over any limit in
+    /// red.
+    /// Returns, for each limit in order, the frequency of the component with
+    /// the least margin and that margin in dB, negative when over the limit,
+    /// or `None` if no component falls within the range of the limit.
+    ///
+    /// # Arguments
+    /// * `current` - Noise current spectrum of the equipment in amps peak.
+    /// * `source` - Impedance shunting the noise current inside the
+    ///              equipment, e.g. its input filter capacitance.
+    /// * `lisn` - Impedance of the LISN, e.g. `spectrum::cispr16_lisn()`.
+    /// * `limits` - Limit lines to overlay, possibly none.
+    /// * `scale` - Scale of the noise axis, usually `MagScale::DbMicrovolt`
+    ///             like the limits or `MagScale::Dbm(50.0)` like a spectrum
+    ///             analyzer. Components are drawn as rms voltages.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the emissions plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::spectrum::{self, EmiLimit, Spectrum};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/conducted_emissions.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // Input current harmonics of a 400kHz converter behind 2x 4.7uF
+    /// let current = Spectrum::parse("400k 1.2\n800k 0.3\n1.2Meg 0.13\n1.6Meg 0.07\n2Meg 0.05").unwrap();
+    /// let input = sdomain::gen::rcl(5e-3, 9.4e-6, 1e-9);
+    /// let margins = noise_plotter::plot_conducted_emissions(
+    ///     &current,
+    ///     &input,
+    ///     &spectrum::cispr16_lisn(),
+    ///     &[EmiLimit::cispr32_class_b_qp(), EmiLimit::cispr32_class_b_avg()],
+    ///     MagScale::DbMicrovolt,
+    ///     &drawing_area
+    /// ).unwrap();
+    /// assert!(margins.iter().all(Option::is_some));
+    /// ```
+    pub fn plot_conducted_emissions<DB: DrawingBackend>(current: &Spectrum, source: &Fs, lisn: &Fs, limits: &[EmiLimit], scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<Vec<Option<(f64, f64)>>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let model = sdomain::parallel(source.clone(), lisn.clone());
+        draw(&model, current, limits, &[], None, "Estimated Conducted Emissions", scale, canvas)
+    }
+
+    /// Plot how a supply noise density at the regulator propagates to a load
+    /// on the PDN: the input density and the density at the load, the input
+    /// scaled by the magnitude of the transfer from the regulator's output
+    /// to the load node. The total RMS noise of each over the band is
+    /// printed in the legend.
+    /// Returns the noise density at the load, or an error if it could not be
+    /// plotted.
+    ///
+    /// # Arguments
+    /// * `input` - Noise density at the regulator's output, over the band to plot.
+    /// * `transfer` - Voltage transfer from the regulator's output to the load node.
+    /// * `scale` - Scale of the density axis, e.g. `MagScale::Log` for
+    ///             V/√Hz or `MagScale::DbMicrovolt` for dBµV/√Hz. Densities
+    ///             are rms.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the noise plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::axis::MagScale;
+    /// use sdomain_test_plotters::spectrum::NoiseDensity;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_noise_psd.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // 50nV/√Hz regulator noise with a 1/f corner at 100Hz, filtered by a
+    /// // 10mΩ, 1nH trace into 22uF at the load
+    /// let vrm = NoiseDensity::from_points(&[(10.0, 500e-9), (100.0, 50e-9), (100e6, 50e-9)]);
+    /// let series = sdomain::gen::rl(10e-3, 1e-9);
+    /// let shunt = sdomain::gen::rcl(2e-3, 22e-6, 0.5e-9);
+    /// let transfer = shunt.clone() / &(series + &shunt);
+    /// let load = noise_plotter::plot_psd(&vrm, &transfer, MagScale::DbMicrovolt, &drawing_area).unwrap();
+    /// assert!(load.rms() < vrm.resample(10.0, 100e6).rms());
+    /// ```
+    pub fn plot_psd<DB: DrawingBackend, P: Plottable + ?Sized>(input: &NoiseDensity, transfer: &P, scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<NoiseDensity, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let (fmin, fmax) = match (input.freqs.first(), input.freqs.last()) {
+            (Some(fmin), Some(fmax)) if fmin < fmax => (*fmin, *fmax),
+            _ => return Err(PlotError::Empty.into()),
+        };
+        let input = input.resample(fmin, fmax);
+        let output = input.through(transfer);
+
+        let values = input.densities.iter().chain(output.densities.iter()).cloned().filter(|v| v.is_finite() && *v > 0.0);
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        error::check_limits(min, max)?;
+        let y_range = scale.padded_range(min, max, 0.3);
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption("Supply Noise Density at Load", ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((fmin..fmax).log_scale(), y_range.clone())?;
+
+        chart.configure_mesh()
+            .x_desc("Frequency [Hz]")
+            .y_desc(format!("Noise Density [{}]", spectrum::density_unit(scale)))
+            .y_labels(scale.label_count(&y_range))
+            .y_label_formatter(&|v| if scale.is_db() {format_plain(*v)} else {format_si(scale.from_axis(*v), "V")})
+            .draw()?;
+        for (name, density, color) in [("Regulator", &input, BLUE), ("Load", &output, GREEN)] {
+            chart.draw_series(LineSeries::new(density.points(scale), &color))?
+                .label(format!("{name} ({} rms)", format_si(density.rms(), "V")))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+        }
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(output)
+    }
+
+    /// Plot the ripple a buck converter leaves at the load against its
+    /// switching frequency, to guide the choice of f_sw. At each switching
+    /// frequency the inductor ripple current and its harmonics are passed
+    /// through the PDN impedance, and the worst-case peak of the resulting
+    /// voltage ripple is drawn together with the fundamental alone. Bumps in
+    /// the curve mark switching frequencies whose harmonics land on PDN
+    /// resonances.
+    /// Returns the (switching frequency, worst-case peak ripple in V) points.
+    ///
+    /// # Arguments
+    /// * `model` - Transfer impedance from the inductor current to the load
+    ///             voltage, e.g. the PDN impedance.
+    /// * `buck` - The converter's operating point and harmonics to keep.
+    /// * `fsw_min` - Lowest switching frequency to consider in Hz.
+    /// * `fsw_max` - Highest switching frequency to consider in Hz.
+    /// * `ripple_limit` - (Optional) If specified, a horizontal line is
+    ///                               drawn at the allowed peak ripple in V.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the ripple plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::spectrum_plotter::noise_plotter;
+    /// use sdomain_test_plotters::vrm::BuckRipple;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_switching_ripple.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(2e-3, 5e-9), None);
+    /// pdn.add_capacitor("0805 47uF", Capacitor::from(47e-6, "0805").model(), 4);
+    /// pdn.add_capacitor("0402 1uF", Capacitor::from(1e-6, "0402").model(), 8);
+    ///
+    /// let buck = BuckRipple {vin: 12.0, vout: 1.0, inductance: 470e-9, harmonics: 9};
+    /// let ripple = noise_plotter::plot_switching_ripple(&pdn, &buck, 200e3, 5e6, Some(10e-3/*V*/), &drawing_area).unwrap();
+    /// assert!(ripple.iter().all(|(_, v)| *v > 0.0));
+    /// ```
+    pub fn plot_switching_ripple<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, buck: &BuckRipple, fsw_min: f64, fsw_max: f64, ripple_limit: Option<f64>, canvas: &DrawAreaType<DB>) -> Result<Vec<(f64, f64)>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(fsw_min, fsw_max)?;
+        if buck.harmonics == 0 {return Err(PlotError::Empty.into());}
+        let (worst, fundamental): (Vec<(f64, f64)>, Vec<(f64, f64)>) = gen_log_range(fsw_min, fsw_max, 10.0, 100).into_iter()
+            .map(|f_sw| {
+                let ripple = buck.at_load(model, f_sw);
+                ((f_sw, ripple.peak()), (f_sw, ripple.amps[0]))
+            })
+            .unzip();
+
+        let values = worst.iter().chain(fundamental.iter()).map(|p| p.1).chain(ripple_limit);
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        error::check_limits(min, max)?;
+        if min <= 0.0 {return Err(PlotError::NonFinite.into());}
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("Switching Ripple at Load, {} to {}", format_si(buck.vin, "V"), format_si(buck.vout, "V")), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((fsw_min..fsw_max).log_scale(), (min*0.5..max*2.0).log_scale())?;
+
+        chart.configure_mesh()
+            .x_desc("Switching Frequency [Hz]")
+            .y_desc("Peak Ripple")
+            .x_label_formatter(&|f| format_si(*f, "Hz"))
+            .y_label_formatter(&|v| format_si(*v, "V"))
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(worst.iter().cloned(), &GREEN))?
+            .label(format!("{} harmonics, worst case", buck.harmonics))
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        chart.draw_series(LineSeries::new(fundamental.iter().cloned(), &BLUE))?
+            .label("Fundamental")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+        if let Some(limit) = ripple_limit {
+            chart.draw_series(LineSeries::new(vec![(fsw_min, limit), (fsw_max, limit)], &PURPLE))?
+                .label(format!("Limit ({})", format_si(limit, "V")))
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(worst)
+    }
+
+    /// Plot a PDN's voltage noise spectrum with optional emissions limits.
+    /// Returns the worst margin to each limit, as for `plot_with_limits`.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `current` - Load current spectrum in amps peak.
+    /// * `limits` - Limit lines to overlay, possibly none.
+    /// * `bands` - Frequency bands to shade behind the lines, possibly none.
+    /// * `dither` - (Optional) Spread-spectrum dithering whose smoothed
+    ///              envelope is shaded under the undithered lines.
+    /// * `title` - Caption of the plot.
+    /// * `scale` - Scale of the noise axis.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the noise plot.
+    fn draw<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, current: &Spectrum, limits: &[EmiLimit], bands: &[FreqBand], dither: Option<&SpreadSpectrum>, title: &str, scale: MagScale, canvas: &DrawAreaType<DB>) -> Result<Vec<Option<(f64, f64)>>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        // Levels are found and checked against the limits in dBµV rms, then
+        // placed on the axis through `y`
+        let y = |db: f64| scale.to_axis(MagScale::DbMicrovolt.from_axis(db));
+        let noise = current.through(model);
+        let lines = noise.points()
+            .filter(|(f, v)| *f > 0.0 && *v > 0.0)
+            .map(|(f, v)| (f, spectrum::to_dbuv(spectrum::peak_to_rms(v))))
+            .collect::<Vec<(f64, f64)>>();
+        if lines.is_empty() {return Err(PlotError::Empty.into());}
+        let envelope = dither.map(|dither| dither.smooth(&noise)).unwrap_or_default().into_iter()
+            .map(|(from, to, v)| (from, to, spectrum::to_dbuv(spectrum::peak_to_rms(v))))
+            .collect::<Vec<(f64, f64, f64)>>();
+
+        let corners = limits.iter().flat_map(|limit| limit.outline().iter().cloned());
+        let edges = envelope.iter().flat_map(|(from, to, _)| [*from, *to]);
+        let min_freq = lines.iter().map(|l| l.0).chain(corners.clone().map(|c| c.0)).chain(edges.clone()).fold(f64::INFINITY, f64::min);
+        let max_freq = lines.iter().map(|l| l.0).chain(corners.clone().map(|c| c.0)).chain(edges).fold(0.0, f64::max);
+        let max_db = lines.iter().map(|l| l.1).chain(corners.map(|c| c.1)).fold(f64::NEG_INFINITY, f64::max);
+        let min_db = lines.iter().map(|l| l.1).chain(envelope.iter().map(|b| b.2)).fold(f64::INFINITY, f64::min);
+        let floor = min_db.max(max_db - 120.0) - 10.0;
+        let over = |(f, db): &(f64, f64)| limits.iter().any(|limit| limit.limit_at(*f).is_some_and(|max| *db > max));
+        let y_range = y(floor)..y(max_db + 10.0);
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(title, ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d((min_freq*0.5..max_freq*2.0).log_scale(), y_range.clone())?;
+
+        chart.configure_mesh()
+            .x_desc("Frequency [Hz]")
+            .y_desc(match scale {
+                MagScale::Dbm(_) => format!("Noise [{}]", scale.unit("V")),
+                _ => format!("Noise [{} rms]", scale.unit("V")),
+            })
+            .y_labels(scale.label_count(&y_range))
+            .y_label_formatter(&|v| if scale.is_db() {format_plain(*v)} else {format_si(scale.from_axis(*v), "V")})
+            .draw()?;
+        bands::draw(&mut chart, bands, |f| f)?;
+        let floor = y(floor);
+
+        if let Some(dither) = dither {
+            let spread = 100.0*(dither.f_high - dither.f_low)/(2.0*dither.center());
+            chart.draw_series(envelope.iter().map(|(from, to, db)| Rectangle::new([(*from, floor), (*to, y(*db))], BLUE.mix(0.3).filled())))?
+                .label(format!("Dithered ±{spread:.1}%, {} RBW", format_si(dither.rbw, "Hz")))
+                .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], BLUE.mix(0.3).filled()));
+        }
+
+        chart.draw_series(lines.iter().filter(|l| !over(*l)).map(|(f, db)| PathElement::new(vec![(*f, floor), (*f, y(*db))], &GREEN)))?
+            .label("Voltage Noise")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        chart.draw_series(lines.iter().filter(|l| !over(*l)).map(|(f, db)| Circle::new((*f, y(*db)), 2, GREEN.filled())))?;
+        if lines.iter().any(over) {
+            chart.draw_series(lines.iter().filter(|l| over(*l)).map(|(f, db)| PathElement::new(vec![(*f, floor), (*f, y(*db))], &RED)))?
+                .label("Over Limit")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+            chart.draw_series(lines.iter().filter(|l| over(*l)).map(|(f, db)| Circle::new((*f, y(*db)), 2, RED.filled())))?;
+        }
+
+        for (index, limit) in limits.iter().enumerate() {
+            let color = Palette99::pick(index + 1).to_rgba();
+            chart.draw_series(LineSeries::new(
+                    limit.outline().iter().map(|(f, db)| (*f, y(*db))),
+                    color.stroke_width(2)
+                ))?
+                .label(limit.name.as_str())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], color.stroke_width(2)));
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(limits.iter().map(|limit| limit.worst_margin(&noise)).collect())
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt;
+
+use sdomain_test::sdomain::{self, Fs};
+
+use crate::sweep::Sweep;
+use crate::units::{format_freq, format_ohms};
+
+/// Reason a SPICE subcircuit could not be read or converted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpiceError {
+    /// The text has no `.subckt` definition.
+    NoSubckt,
+    /// A line could not be parsed. Lines are numbered from 1.
+    Syntax {line: usize, text: String},
+    /// An element other than a resistor, inductor, or capacitor, or one
+    /// whose value is an expression.
+    Unsupported {line: usize, element: String},
+    /// The subcircuit has fewer or more than two pins.
+    PinCount(usize),
+    /// The network between the pins cannot be reduced to series and
+    /// parallel combinations, e.g. a bridge.
+    NotSeriesParallel,
+}
+
+impl fmt::Display for SpiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpiceError::NoSubckt => write!(f, "no .subckt definition found"),
+            SpiceError::Syntax {line, text} => write!(f, "cannot parse line {line}: {text}"),
+            SpiceError::Unsupported {line, element} => write!(f, "unsupported element {element} on line {line}"),
+            SpiceError::PinCount(count) => write!(f, "subcircuit has {count} pins, expected 2"),
+            SpiceError::NotSeriesParallel => write!(f, "network is not a series-parallel combination"),
+        }
+    }
+}
+
+impl std::error::Error for SpiceError {}
+
+/// Kind of a passive SPICE element.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElementKind {
+    Resistor,
+    Inductor,
+    Capacitor,
+}
+
+/// One R, L, or C line of a subcircuit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    /// Element name, e.g. `R1`.
+    pub name: String,
+    pub kind: ElementKind,
+    /// Nodes the element connects, lowercased.
+    pub nodes: (String, String),
+    /// Value in Ω, H, or F.
+    pub value: f64,
+}
+
+impl Element {
+    /// Impedance of the element in the s-domain.
+    pub fn model(&self) -> Fs {
+        match self.kind {
+            ElementKind::Resistor => sdomain::gen::resistor(self.value),
+            ElementKind::Inductor => sdomain::gen::inductor(self.value),
+            ElementKind::Capacitor => sdomain::gen::capacitor(self.value),
+        }
+    }
+}
+
+/// A two-pin subcircuit of resistors, inductors, and capacitors, such as a
+/// vendor's capacitor model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subckt {
+    pub name: String,
+    /// The two pins, lowercased.
+    pub pins: (String, String),
+    pub elements: Vec<Element>,
+}
+
+impl Subckt {
+    /// Impedance between the pins in the s-domain, found by repeatedly
+    /// combining parallel elements and series elements through inner nodes
+    /// with no other connections. Series RLC and ladder models, as published
+    /// by Murata, TDK, and KEMET, reduce this way.
+    /// Returns the impedance, or an error if the network does not reduce to
+    /// a single impedance between the pins.
+    pub fn model(&self) -> Result<Fs, SpiceError> {
+        let mut edges = self.elements.iter()
+            .filter(|e| e.nodes.0 != e.nodes.1)
+            .map(|e| (e.nodes.0.clone(), e.nodes.1.clone(), e.model()))
+            .collect::<Vec<(String, String, Fs)>>();
+        let is_pin = |node: &str| node == self.pins.0 || node == self.pins.1;
+        loop {
+            if edges.len() == 1 && is_pin(&edges[0].0) && is_pin(&edges[0].1) {
+                return Ok(edges.remove(0).2);
+            }
+            if let Some((i, j)) = parallel_pair(&edges) {
+                let (_, _, b) = edges.remove(j);
+                let (from, to, a) = edges.remove(i);
+                edges.push((from, to, sdomain::parallel(a, b)));
+                continue;
+            }
+            let inner = |node: &String| !is_pin(node);
+            let degree = |node: &String| edges.iter().filter(|e| e.0 == *node || e.1 == *node).count();
+            let nodes = edges.iter().flat_map(|e| [e.0.clone(), e.1.clone()]).collect::<Vec<String>>();
+            // An inner node reached by one element leaves it open
+            if let Some(node) = nodes.iter().find(|n| inner(n) && degree(n) == 1) {
+                edges.retain(|e| e.0 != *node && e.1 != *node);
+                continue;
+            }
+            if let Some(node) = nodes.iter().find(|n| inner(n) && degree(n) == 2).cloned() {
+                let mut pair = edges.iter().enumerate()
+                    .filter(|(_, e)| e.0 == node || e.1 == node)
+                    .map(|(i, _)| i)
+                    .collect::<Vec<usize>>();
+                let second = edges.remove(pair.pop().unwrap());
+                let first = edges.remove(pair.pop().unwrap());
+                let far = |e: &(String, String, Fs)| if e.0 == node {e.1.clone()} else {e.0.clone()};
+                let (a, b) = (far(&first), far(&second));
+                edges.push((a, b, first.2 + &second.2));
+                edges.retain(|e| e.0 != e.1);
+                continue;
+            }
+            return Err(SpiceError::NotSeriesParallel);
+        }
+    }
+}
+
+/// First two edges connecting the same pair of nodes, in either direction.
+fn parallel_pair(edges: &[(String, String, Fs)]) -> Option<(usize, usize)> {
+    (0..edges.len())
+        .flat_map(|i| (i + 1..edges.len()).map(move |j| (i, j)))
+        .find(|(i, j)| {
+            let (a, b) = (&edges[*i], &edges[*j]);
+            (a.0 == b.0 && a.1 == b.1) || (a.0 == b.1 && a.1 == b.0)
+        })
+}
+
+/// Parse a SPICE number with an optional scale suffix, e.g. `4.7u`,
+/// `0.35nH`, `1MEG`, or `1e-6`. Letters after the suffix are ignored, as
+/// SPICE does.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::spice::parse_value;
+///
+/// assert_eq!(parse_value("4.5m"), Some(4.5e-3));
+/// assert_eq!(parse_value("0.35nH"), Some(0.35e-9));
+/// assert_eq!(parse_value("2MEG"), Some(2e6));
+/// assert_eq!(parse_value("1.0E-06"), Some(1e-6));
+/// assert_eq!(parse_value("{C}"), None);
+/// ```
+pub fn parse_value(text: &str) -> Option<f64> {
+    let text = text.to_ascii_lowercase();
+    let end = text.char_indices()
+        .find(|(i, c)| {
+            !(c.is_ascii_digit() || *c == '.' || *c == '-' || *c == '+'
+                || (*c == 'e' && text[i + 1..].starts_with(|n: char| n.is_ascii_digit() || n == '-' || n == '+')))
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let number = &text[..end];
+    let suffix = &text[end..];
+    if suffix.starts_with("mil") {
+        return number.parse::<f64>().ok().map(|n| n*25.4e-6);
+    }
+    let exponent = if suffix.starts_with("meg") {6}
+        else {
+            match suffix.chars().next() {
+                Some('t') => 12,
+                Some('g') => 9,
+                Some('k') => 3,
+                Some('m') => -3,
+                Some('u') | Some('µ') => -6,
+                Some('n') => -9,
+                Some('p') => -12,
+                Some('f') => -15,
+                _ => 0,
+            }
+        };
+    // Scaling through the exponent keeps e.g. 4.5m exactly equal to 4.5e-3
+    if number.contains('e') {
+        number.parse::<f64>().ok().map(|n| n*10f64.powi(exponent))
+    } else {
+        format!("{number}e{exponent}").parse::<f64>().ok()
+    }
+}
+
+/// Read the first two-pin subcircuit of R, L, and C elements from a vendor
+/// SPICE model file (.mod, .cir, .lib). Comments, `;` end-of-line comments,
+/// `+` continuation lines, and lines outside the subcircuit are skipped.
+/// Returns the subcircuit, or an error naming the first line that could not
+/// be used.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::spice::{self, ElementKind};
+///
+/// let text = "\
+/// * 0402 1uF, simplified
+/// .SUBCKT C1U 1 2
+/// R1 1 3 4.5m
+/// L1 3 4 0.35n ; mounting not included
+/// C1 4 2 1u
+/// .ENDS C1U
+/// ";
+/// let subckt = spice::parse_subckt(text).unwrap();
+/// assert_eq!(subckt.name, "C1U");
+/// assert_eq!(subckt.elements.len(), 3);
+/// assert_eq!(subckt.elements[1].kind, ElementKind::Inductor);
+///
+/// // At the series resonance only the ESR is left
+/// let model = subckt.model().unwrap();
+/// let srf = 1.0/(2.0*std::f64::consts::PI*(0.35e-9*1e-6f64).sqrt());
+/// assert!((model.calculate_freq(srf).mag() - 4.5e-3).abs() < 1e-6);
+/// ```
+pub fn parse_subckt(text: &str) -> Result<Subckt, SpiceError> {
+    // Join continuation lines to the line they continue, keeping the first
+    // line number for errors
+    let mut lines: Vec<(usize, String)> = vec![];
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.split(';').next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with('*') {continue;}
+        match (line.strip_prefix('+'), lines.last_mut()) {
+            (Some(rest), Some(last)) => {
+                last.1.push(' ');
+                last.1.push_str(rest.trim());
+            },
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+
+    let mut subckt: Option<Subckt> = None;
+    for (number, line) in lines {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        let keyword = fields[0].to_ascii_lowercase();
+        match (&mut subckt, keyword.as_str()) {
+            (None, ".subckt") => {
+                let pins = fields.iter().skip(2)
+                    .take_while(|f| !f.contains('=') && !f.eq_ignore_ascii_case("params:"))
+                    .map(|f| f.to_ascii_lowercase())
+                    .collect::<Vec<String>>();
+                if fields.len() < 2 {return Err(SpiceError::Syntax {line: number, text: line});}
+                if pins.len() != 2 {return Err(SpiceError::PinCount(pins.len()));}
+                subckt = Some(Subckt {
+                    name: fields[1].to_string(),
+                    pins: (pins[0].clone(), pins[1].clone()),
+                    elements: vec![],
+                });
+            },
+            (None, _) => {},
+            (Some(current), ".ends") => return Ok(current.clone()),
+            (Some(current), _) => {
+                let kind = match keyword.chars().next() {
+                    Some('r') => ElementKind::Resistor,
+                    Some('l') => ElementKind::Inductor,
+                    Some('c') => ElementKind::Capacitor,
+                    _ => return Err(SpiceError::Unsupported {line: number, element: fields[0].to_string()}),
+                };
+                if fields.len() < 4 {return Err(SpiceError::Syntax {line: number, text: line});}
+                let value = parse_value(fields[3])
+                    .ok_or_else(|| SpiceError::Unsupported {line: number, element: fields[0].to_string()})?;
+                current.elements.push(Element {
+                    name: fields[0].to_string(),
+                    kind,
+                    nodes: (fields[1].to_ascii_lowercase(), fields[2].to_ascii_lowercase()),
+                    value,
+                });
+            },
+        }
+    }
+    // Some files end without .ends
+    subckt.ok_or(SpiceError::NoSubckt)
+}
+
+/// How a vendor model and a simple model of the same part compare over a
+/// sweep. Positive ratios mean the simple model is optimistic, predicting
+/// less impedance than the vendor model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModelComparison {
+    /// Largest ratio of the vendor impedance to the simple one in dB, and
+    /// the frequency in Hz where it occurs.
+    pub max_ratio: (f64, f64),
+    /// (frequency, magnitude) of the vendor model's impedance minimum.
+    pub vendor_min: (f64, f64),
+    /// (frequency, magnitude) of the simple model's impedance minimum.
+    pub simple_min: (f64, f64),
+}
+
+impl fmt::Display for ModelComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "vendor model up to {:.1} dB above the simple model at {}; minimum {} at {} vs {} at {}",
+            self.max_ratio.0, format_freq(self.max_ratio.1),
+            format_ohms(self.vendor_min.1), format_freq(self.vendor_min.0),
+            format_ohms(self.simple_min.1), format_freq(self.simple_min.0)
+        )
+    }
+}
+
+/// Compare a vendor model's impedance sweep with a simple model's sweep at
+/// the same frequencies.
+pub fn compare(vendor: &Sweep, simple: &Sweep) -> ModelComparison {
+    let minimum = |sweep: &Sweep| sweep.mag_points().fold((f64::NAN, f64::INFINITY), |best, p| if p.1 < best.1 {p} else {best});
+    let max_ratio = vendor.mags.iter().zip(simple.mags.iter()).zip(vendor.freqs.iter())
+        .map(|((v, s), freq)| (20.0*(v/s).log10(), *freq))
+        .fold((f64::NEG_INFINITY, f64::NAN), |best, p| if p.0 > best.0 {p} else {best});
+    ModelComparison {max_ratio, vendor_min: minimum(vendor), simple_min: minimum(simple)}
+}
@@ -0,0 +1,598 @@
+pub mod two_port_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{PURPLE, GREY};
+
+    use crate::bands;
+    use crate::config::PlotConfig;
+    use crate::error;
+    use crate::layout::SharedX;
+    use crate::two_port::TwoPort;
+    use crate::units::{format_ohms, format_plain};
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Plot the Rollett K factor and the Edwards-Sinsky µ factor of a two-port
+    /// over frequency. A horizontal line at 1 marks the unconditional
+    /// stability limit; the network is unconditionally stable wherever µ is
+    /// above the line. The factors are drawn between -10 and 10; samples
+    /// where they are not finite are left out.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A two-port network described by its Z parameters.
+    /// * `z0` - Reference impedance used to compute the S parameters for µ.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the stability plot.
+    /// * `config` - Sweep range, frequency axis, bands, caption, and axis
+    ///              labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::stability_plotter::two_port_plotter;
+    /// use sdomain_test_plotters::two_port::TwoPort;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/two_port_stability.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // A resistive T network
+    /// let model = TwoPort {
+    ///     z11: sdomain::gen::resistor(60.0),
+    ///     z12: sdomain::gen::resistor(10.0),
+    ///     z21: sdomain::gen::rl(10.0, 1e-6),
+    ///     z22: sdomain::gen::resistor(60.0),
+    /// };
+    /// let config = PlotConfig {fmax: 100e6, ..PlotConfig::default()};
+    /// two_port_plotter::plot_stability(&model, 50.0, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot_stability<DB: DrawingBackend>(model: &TwoPort, z0: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let freq_data = config.sample_freqs();
+        let params = model.z_params(&freq_data);
+        let k_data = params.iter().map(|z| z.k_factor()).collect::<Vec<f64>>();
+        let mu_data = params.iter().map(|z| z.to_s(z0).mu()).collect::<Vec<f64>>();
+
+        let finite = k_data.iter().chain(mu_data.iter()).cloned().filter(|v| v.is_finite());
+        let min_val = finite.clone().fold(0.0, f64::min).max(-10.0);
+        let max_val = finite.fold(2.0, f64::max).min(10.0);
+        error::check_limits(min_val, max_val)?;
+
+        let shared = SharedX::new(config, false);
+        let mut builder = shared.builder(canvas, true);
+        if let Some(caption) = config.caption_or("Stability Factors") {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), min_val..max_val*1.1)?;
+
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&|x| config.freq_tick(*x))
+            .x_desc(config.x_label())
+            .y_desc(config.y_label_or("Stability Factor"))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+        chart.draw_series(LineSeries::new(
+                vec![(shared.range.start, 1.0), (shared.range.end, 1.0)],
+                &PURPLE
+            ))?
+            .label("Stability Limit")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+        let points = |data: Vec<f64>| {
+            freq_data.iter().map(|f| config.freq_to_axis(*f)).zip(data).filter(|(_, v)| v.is_finite()).collect::<Vec<(f64, f64)>>()
+        };
+        chart.draw_series(LineSeries::new(
+                points(k_data),
+                &GREEN
+            ))?
+            .label("K")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        chart.draw_series(LineSeries::new(
+                points(mu_data),
+                &BLUE
+            ))?
+            .label("µ")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(())
+    }
+
+    /// Plot the insertion loss of a filter network between a source and a
+    /// load impedance, the quantity filter datasheets give, in positive dB:
+    /// how far inserting the filter drops the load voltage. With equal
+    /// terminations this is -20·log10(|S21|) referenced to that impedance;
+    /// with unequal ones S21 is higher by 20·log10(2·√(Rs·RL)/(Rs + RL)), the
+    /// mismatch between the terminations, which is not part of the loss.
+    /// Returns the insertion loss in dB at each frequency of the sweep, or an
+    /// error if it could not be plotted.
+    ///
+    /// # Arguments
+    /// * `model` - The filter network described by its Z parameters, port 1
+    ///             facing the source.
+    /// * `source` - Source impedance in Ω.
+    /// * `load` - Load impedance in Ω.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the insertion loss plot.
+    /// * `config` - Sweep range, frequency axis, bands, caption, and axis
+    ///              labels of the plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::stability_plotter::two_port_plotter;
+    /// use sdomain_test_plotters::two_port::TwoPort;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/two_port_insertion_loss.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // CLC pi filter: 1uF, 10uH, 1uF
+    /// let filter = TwoPort::pi(
+    ///     sdomain::gen::rcl(10e-3, 1e-6, 1e-9),
+    ///     sdomain::gen::rl(50e-3, 10e-6),
+    ///     sdomain::gen::rcl(10e-3, 1e-6, 1e-9),
+    /// );
+    /// let config = PlotConfig {fmax: 100e6, ..PlotConfig::default()};
+    /// let loss = two_port_plotter::plot_insertion_loss(&filter, 50.0, 50.0, &drawing_area, &config).unwrap();
+    /// // Passes DC, blocks the megahertz range
+    /// assert!(loss[0].abs() < 0.1);
+    /// assert!(loss.iter().cloned().fold(0.0, f64::max) > 60.0);
+    /// ```
+    pub fn plot_insertion_loss<DB: DrawingBackend>(model: &TwoPort, source: f64, load: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<Vec<f64>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let freq_data = config.sample_freqs();
+        let loss = model.insertion_loss(&freq_data, source, load);
+
+        let finite = loss.iter().cloned().filter(|v| v.is_finite());
+        let min_db = finite.clone().fold(f64::INFINITY, f64::min);
+        let max_db = finite.fold(f64::NEG_INFINITY, f64::max);
+        error::check_limits(min_db, max_db)?;
+
+        let shared = SharedX::new(config, false);
+        let mut builder = shared.builder(canvas, true);
+        if let Some(caption) = config.caption_or(&format!("Insertion Loss, {} Source, {} Load", format_ohms(source), format_ohms(load))) {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), min_db.min(0.0) - 5.0..(max_db + 10.0).max(10.0))?;
+
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&|x| config.freq_tick(*x))
+            .y_label_formatter(&|y| format_plain(*y))
+            .x_desc(config.x_label())
+            .y_desc(config.y_label_or("Insertion Loss [dB]"))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+        chart.draw_series(LineSeries::new(
+                freq_data.iter().map(|f| config.freq_to_axis(*f)).zip(loss.iter().cloned()).filter(|(_, db)| db.is_finite()),
+                &GREEN
+            ))?
+            .label("Insertion Loss")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(loss)
+    }
+}
+
+pub mod middlebrook_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{ORANGE, GREY};
+
+    use sdomain_test::sdomain::Fs;
+
+    use crate::bands;
+    use crate::config::{Caption, PlotConfig};
+    use crate::error;
+    use crate::layout::SharedX;
+    use crate::sweep::Sweep;
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Plot the Middlebrook stability check for a source feeding a load, such
+    /// as two cascaded converters. The upper panel overlays the source output
+    /// impedance and the load input impedance, shading the frequencies where
+    /// |Z_out| comes within `margin_db` of |Z_in|. The lower panel shows the
+    /// minor-loop gain Z_out/Z_in and its phase; the cascade is stable when
+    /// the minor-loop gain stays well below 0 dB.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `source` - Output impedance of the source in the s-domain.
+    /// * `load` - Input impedance of the load in the s-domain.
+    /// * `margin_db` - Required separation between |Z_in| and |Z_out| in dB.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the plots.
+    /// * `config` - Sweep range, axes, bands, and labels shared by both
+    ///              panels, and the caption of the upper panel. Hiding the
+    ///              caption hides the lower panel's too.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::config::PlotConfig;
+    /// use sdomain_test_plotters::stability_plotter::middlebrook_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/middlebrook.png", (960, 960))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let source = sdomain::parallel(sdomain::gen::rl(10e-3, 4.7e-6), sdomain::gen::rcl(5e-3, 22e-6, 1e-9));
+    /// let load = sdomain::gen::resistor(2.0);
+    /// let config = PlotConfig {fmax: 100e6, ..PlotConfig::default()};
+    /// middlebrook_plotter::plot(source, load, 6.0, &drawing_area, &config).unwrap();
+    /// ```
+    pub fn plot<DB: DrawingBackend>(source: Fs, load: Fs, margin_db: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        error::check_range(config.fmin, config.fmax)?;
+        let (out_sweep, in_sweep) = config.check_finite_pair(
+            Sweep::at(&source, config.sample_freqs()), "Z_out",
+            Sweep::at(&load, config.sample_freqs()), "Z_in"
+        )?;
+        let minor_loop = config.check_finite(Sweep::at(&(source / &load), config.sample_freqs()), "Z_out/Z_in")?;
+
+        let (upper, lower) = canvas.split_vertically((50).percent_height());
+        // The right axis of the lower panel is reserved in both so the panels line up
+        let shared = SharedX::new(config, true);
+        let x_label = config.x_label();
+        let x_formatter = |x: &f64| config.freq_tick(*x);
+
+        let min_mag = out_sweep.min_mag().min(in_sweep.min_mag());
+        let max_mag = out_sweep.max_mag().max(in_sweep.max_mag());
+        error::check_limits(min_mag, max_mag)?;
+        let mag_scale = config.mag_scale;
+        let y_range = config.y_range(min_mag, max_mag);
+        let mut builder = shared.builder(&upper, true);
+        if let Some(caption) = config.caption_or("Middlebrook Impedance Ratio") {
+            builder.caption(caption, ("Arial", 30));
+        }
+        let mut chart = builder.build_cartesian_2d(shared.range.clone(), y_range.clone())?;
+
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .y_labels(mag_scale.label_count(&y_range))
+            .y_label_formatter(&|y| config.mag_tick(*y, "Ω"))
+            .x_desc(x_label.as_str())
+            .y_desc(config.y_label_or(&mag_scale.label("Impedance", "Ω")))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+        // Shade each frequency interval where the impedances are too close
+        let margin = 10f64.powf(margin_db/20.0);
+        let too_close = out_sweep.mags.iter().zip(in_sweep.mags.iter())
+            .map(|(z_out, z_in)| z_out*margin > *z_in)
+            .collect::<Vec<bool>>();
+        chart.draw_series(
+                out_sweep.freqs.windows(2).zip(too_close.windows(2))
+                    .filter(|(_, close)| close[0] || close[1])
+                    .map(|(f, _)| Rectangle::new(
+                        [(config.freq_to_axis(f[0]), y_range.start), (config.freq_to_axis(f[1]), y_range.end)],
+                        ORANGE.mix(0.3).filled()
+                    ))
+            )?
+            .label(format!("Margin < {margin_db:.0} dB"))
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], ORANGE.mix(0.3).filled()));
+
+        chart.draw_series(LineSeries::new(
+                out_sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                &GREEN
+            ))?
+            .label("|Z_out|")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        chart.draw_series(LineSeries::new(
+                in_sweep.mag_points().map(|(f, m)| (config.freq_to_axis(f), mag_scale.to_axis(m))),
+                &BLUE
+            ))?
+            .label("|Z_in|")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        let gain_data = minor_loop.mags.iter().map(|m| 20.0*m.log10()).collect::<Vec<f64>>();
+        let min_gain = gain_data.iter().cloned().fold(0.0, f64::min) - 10.0;
+        let max_gain = gain_data.iter().cloned().fold(0.0, f64::max) + 10.0;
+        error::check_limits(min_gain, max_gain)?;
+        let mut builder = shared.builder(&lower, true);
+        if config.caption != Caption::Hidden {
+            builder.caption("Minor-Loop Gain Z_out/Z_in", ("Arial", 30));
+        }
+        let mut chart = builder
+            .build_cartesian_2d(shared.range.clone(), min_gain..max_gain)?
+            .set_secondary_coord(shared.range.clone(), config.phase_range.0..config.phase_range.1);
+
+        chart.configure_mesh()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .x_desc(x_label.as_str())
+            .y_desc("Magnitude [dB]")
+            .draw()?;
+        chart.configure_secondary_axes()
+            .x_labels(shared.labels)
+            .x_label_formatter(&x_formatter)
+            .x_desc(x_label.as_str())
+            .y_desc(config.phase_label_or("Phase [°]"))
+            .draw()?;
+
+        bands::draw(&mut chart, &config.bands, |f| config.freq_to_axis(f))?;
+        chart.draw_series(LineSeries::new(
+                vec![(shared.range.start, -margin_db), (shared.range.end, -margin_db)],
+                &ORANGE
+            ))?
+            .label("Gain Margin Limit")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &ORANGE));
+        chart.draw_series(LineSeries::new(
+                minor_loop.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(gain_data),
+                &GREEN
+            ))?
+            .label("Magnitude")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        chart.draw_secondary_series(LineSeries::new(
+                minor_loop.freqs.iter().map(|f| config.freq_to_axis(*f)).zip(config.phases(&minor_loop)),
+                &RED.mix(0.4)
+            ))?
+            .label("Phase")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(())
+    }
+}
+
+pub mod nyquist_plotter {
+    use std::f64::consts::PI;
+
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{GREY, PINK};
+
+    use crate::plottable::Plottable;
+    use crate::sweep::Sweep;
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    const MAX_FREQ: f64 = 100e6;
+    /// Largest distance from the origin drawn, so that the curve near -1
+    /// stays readable when the loop gain is large at low frequencies.
+    const MAX_EXTENT: f64 = 10.0;
+
+    /// Plot the Nyquist diagram of a loop gain L(jω) for positive and mirrored
+    /// negative frequencies. The disk |1 + L| < 1 around the critical point -1
+    /// is shaded, since the curve entering it means feedback amplifies
+    /// disturbances, and encircling -1 means the closed loop has
+    /// right-half-plane poles unless the loop gain has some of its own.
+    /// Returns the net number of clockwise encirclements of -1, or an error.
+    /// The count is only meaningful when the loop gain is small at both ends
+    /// of the sweep; an integrator's contour closes at infinity instead.
+    ///
+    /// # Arguments
+    /// * `model` - Loop gain in the s-domain.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the diagram.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::stability_plotter::nyquist_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/nyquist.png", (720, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // A first-order low pass loop gain of 2 never encircles -1
+    /// let loop_gain = sdomain::gen::resistor(2.0) / &(sdomain::gen::resistor(1.0) + &sdomain::gen::inductor(1e-3));
+    /// assert_eq!(nyquist_plotter::plot(&loop_gain, &drawing_area).unwrap(), 0);
+    /// ```
+    pub fn plot<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, canvas: &DrawAreaType<DB>) -> Result<i32, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let sweep = Sweep::checked(model, 1.0, MAX_FREQ)?;
+        let positive = sweep.real().into_iter().zip(sweep.imag()).collect::<Vec<(f64, f64)>>();
+        let negative = positive.iter().rev().map(|(re, im)| (*re, -im)).collect::<Vec<(f64, f64)>>();
+
+        let extent = positive.iter()
+            .map(|(re, im)| re.hypot(*im))
+            .filter(|r| r.is_finite())
+            .fold(2.0, f64::max)
+            .min(MAX_EXTENT)*1.1;
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("Nyquist Diagram of {}", model.display_name()), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 40)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(-extent..extent, -extent..extent)?;
+
+        chart.configure_mesh().x_desc("Re L(jω)").y_desc("Im L(jω)").draw()?;
+
+        chart.draw_series(std::iter::once(Polygon::new(
+                (0..=72).map(|step| {
+                    let angle = step as f64*2.0*PI/72.0;
+                    (-1.0 + angle.cos(), angle.sin())
+                }).collect::<Vec<(f64, f64)>>(),
+                PINK.mix(0.3).filled()
+            )))?
+            .label("|1 + L| < 1")
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], PINK.mix(0.3).filled()));
+
+        for run in runs_within(&negative, extent) {
+            chart.draw_series(LineSeries::new(run, BLUE.mix(0.4)))?;
+        }
+        for (index, run) in runs_within(&positive, extent).into_iter().enumerate() {
+            let series = chart.draw_series(LineSeries::new(run, &BLUE))?;
+            if index == 0 {
+                series.label("L(jω)")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+            }
+        }
+
+        chart.draw_series(std::iter::once(Cross::new((-1.0, 0.0), 6, RED.stroke_width(2))))?
+            .label("-1")
+            .legend(|(x, y)| Cross::new((x + 5, y), 4, RED.stroke_width(2)));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(encirclements(negative.iter().chain(positive.iter())))
+    }
+
+    /// Net clockwise turns of 1 + L around the origin along a closed contour.
+    fn encirclements<'a>(contour: impl Iterator<Item = &'a (f64, f64)>) -> i32 {
+        let mut angles = contour.map(|(re, im)| im.atan2(re + 1.0)).collect::<Vec<f64>>();
+        if let Some(first) = angles.first() {
+            angles.push(*first);
+        }
+        let turn = angles.windows(2)
+            .map(|a| {
+                let step = a[1] - a[0];
+                step - 2.0*PI*(step/(2.0*PI)).round()
+            })
+            .sum::<f64>();
+        -(turn/(2.0*PI)).round() as i32
+    }
+
+    /// Split points into runs that stay within `extent` of the origin on both
+    /// axes, so that the curve is not drawn outside the chart.
+    fn runs_within(points: &[(f64, f64)], extent: f64) -> Vec<Vec<(f64, f64)>> {
+        let mut runs = vec![];
+        let mut run = vec![];
+        for point in points {
+            if point.0.abs() <= extent && point.1.abs() <= extent {
+                run.push(*point);
+            } else if !run.is_empty() {
+                runs.push(std::mem::take(&mut run));
+            }
+        }
+        if !run.is_empty() {
+            runs.push(run);
+        }
+        runs
+    }
+}
+
+pub mod pole_zero_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::GREY;
+
+    use crate::plottable::Plottable;
+    use crate::pole_zero::{self, Root, RootKind};
+    use crate::sweep::Sweep;
+    use crate::units::format_eng;
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    const MAX_FREQ: f64 = 100e6;
+
+    /// Plot a pole-zero map of a model in the s-plane, with both axes in Hz
+    /// (s/2π). The right half-plane is shaded, and poles that fall in it are
+    /// drawn in red since they make the system unstable. Poles are marked ×
+    /// and zeros ○. The roots are found from the model's coefficients with
+    /// `pole_zero::find`; models that are not a ratio of polynomials fall
+    /// back to `pole_zero::estimate`, and the caption says so.
+    /// Returns the roots drawn, or an error.
+    ///
+    /// # Arguments
+    /// * `model` - A transfer function or impedance in the s-domain.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the map.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::stability_plotter::pole_zero_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pole_zero_map.png", (720, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // An underdamped LC low pass filter has a complex pole pair
+    /// let zc = sdomain::gen::capacitor(1e-6);
+    /// let lpf = zc.clone() / &(sdomain::gen::rl(5.0, 1e-3) + &zc);
+    /// let roots = pole_zero_plotter::plot(&lpf, &drawing_area).unwrap();
+    /// assert!(roots.iter().all(|root| !root.right_half_plane));
+    /// ```
+    pub fn plot<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, canvas: &DrawAreaType<DB>) -> Result<Vec<Root>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let (roots, estimated) = match pole_zero::find(model, 1.0, MAX_FREQ) {
+            Ok(roots) => (roots, false),
+            Err(_) => (pole_zero::estimate(&Sweep::checked(model, 1.0, MAX_FREQ)?), true),
+        };
+        let title = if estimated {"Estimated Pole-Zero Map"} else {"Pole-Zero Map"};
+
+        let extent = roots.iter()
+            .flat_map(|root| root.locations())
+            .map(|(sigma, omega)| sigma.abs().max(omega.abs()))
+            .fold(1.0, f64::max)*1.2;
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("{title} of {}", model.display_name()), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(-extent..extent, -extent..extent)?;
+
+        chart.configure_mesh()
+            .x_desc("σ/2π")
+            .y_desc("ω/2π")
+            .x_label_formatter(&|x| format_eng(*x, "Hz", 2))
+            .y_label_formatter(&|y| format_eng(*y, "Hz", 2))
+            .draw()?;
+
+        chart.draw_series(std::iter::once(Rectangle::new([(0.0, -extent), (extent, extent)], RED.mix(0.1).filled())))?
+            .label("Unstable Region")
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], RED.mix(0.1).filled()));
+        chart.draw_series(LineSeries::new(vec![(0.0, -extent), (0.0, extent)], &BLACK))?;
+
+        let pole_color = |root: &Root| if root.right_half_plane {RED} else {BLUE};
+        chart.draw_series(
+                roots.iter()
+                    .filter(|root| root.kind == RootKind::Pole)
+                    .flat_map(|root| root.locations().into_iter().map(move |at| Cross::new(at, 6, pole_color(root).stroke_width(2))))
+            )?
+            .label("Pole")
+            .legend(|(x, y)| Cross::new((x + 5, y), 4, BLUE.stroke_width(2)));
+        chart.draw_series(
+                roots.iter()
+                    .filter(|root| root.kind == RootKind::Zero)
+                    .flat_map(|root| root.locations().into_iter().map(|at| Circle::new(at, 6, GREEN.stroke_width(2))))
+            )?
+            .label("Zero")
+            .legend(|(x, y)| Circle::new((x + 5, y), 4, GREEN.stroke_width(2)));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(roots)
+    }
+}
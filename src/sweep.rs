@@ -0,0 +1,420 @@
+use sdomain_test::range_generators::gen_log_range;
+
+use crate::config::PlotConfig;
+use crate::error::PlotError;
+use crate::phasor::Phasor;
+use crate::plottable::Plottable;
+
+/// Magnitude and phase of a response sampled over a logarithmic frequency
+/// range. This is the data every plotter draws from.
+#[derive(Clone, Debug)]
+pub struct Sweep {
+    /// Sample frequencies in Hz.
+    pub freqs: Vec<f64>,
+    /// Linear magnitude at each frequency.
+    pub mags: Vec<f64>,
+    /// Phase in degrees at each frequency.
+    pub phases: Vec<f64>,
+}
+
+impl Sweep {
+    /// Sample a model from `fmin` to `fmax`.
+    ///
+    /// # Arguments
+    /// * `model` - A model to evaluate, such as an `Fs` or `PDNModel`.
+    /// * `fmin` - Lowest frequency of the sweep in Hz.
+    /// * `fmax` - Highest frequency of the sweep in Hz.
+    pub fn of<P: Plottable + ?Sized>(model: &P, fmin: f64, fmax: f64) -> Sweep {
+        Sweep::at(model, gen_log_range(fmin, fmax, 10.0, 100))
+    }
+
+    /// Sample a model from `fmin` to `fmax` as `of` does, under the default
+    /// `NonFinitePolicy::Error`. Plotters with a fixed frequency range
+    /// sweep through this so a bad model fails with an error naming it
+    /// rather than drawing NaN.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::error::PlotError;
+    /// use sdomain_test_plotters::plottable::Plottable;
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// assert!(Sweep::checked(&sdomain::gen::capacitor(1e-6), 1.0, 1e6).is_ok());
+    /// // Dividing by a zero-valued resistor gives 0/0 at every frequency
+    /// let broken = (sdomain::gen::resistor(0.0) / &sdomain::gen::resistor(0.0)).named("0/0");
+    /// assert_eq!(
+    ///     Sweep::checked(&broken, 1.0, 1e6).unwrap_err(),
+    ///     PlotError::NonFiniteSample {model: "0/0".to_string(), freq: 1.0}
+    /// );
+    /// ```
+    pub fn checked<P: Plottable + ?Sized>(model: &P, fmin: f64, fmax: f64) -> Result<Sweep, PlotError> {
+        PlotConfig::default().check_finite(Sweep::of(model, fmin, fmax), &model.display_name())
+    }
+
+    /// Sample a model at arbitrary frequencies.
+    ///
+    /// # Arguments
+    /// * `model` - A model to evaluate, such as an `Fs` or `PDNModel`.
+    /// * `freqs` - Frequencies in Hz at which to evaluate the model.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(model = %model.display_name(), points = freqs.len())))]
+    pub fn at<P: Plottable + ?Sized>(model: &P, freqs: Vec<f64>) -> Sweep {
+        let complex_data = model.responses(&freqs);
+        let mags = complex_data.iter().map(|c| c.mag()).collect::<Vec<f64>>();
+        let phases = complex_data.iter().map(|c| c.phase_deg()).collect::<Vec<f64>>();
+        let sweep = Sweep {freqs, mags, phases};
+        #[cfg(feature = "tracing")]
+        tracing::debug!(min = sweep.min_mag(), max = sweep.max_mag(), non_finite = ?sweep.first_non_finite(), "sweep evaluated");
+        sweep
+    }
+
+    /// Multiply two sweeps taken over the same frequencies point by point.
+    ///
+    /// # Panics
+    /// If the sweeps were not taken over the same frequencies.
+    pub fn product(&self, other: &Sweep) -> Sweep {
+        self.assert_aligned(other);
+        Sweep {
+            freqs: self.freqs.clone(),
+            mags: self.mags.iter().zip(other.mags.iter()).map(|(a, b)| a*b).collect(),
+            phases: self.phases.iter().zip(other.phases.iter()).map(|(a, b)| wrap_phase(a + b)).collect(),
+        }
+    }
+
+    /// Divide this sweep by another taken over the same frequencies point by
+    /// point, e.g. to compare two models of the same network.
+    ///
+    /// # Panics
+    /// If the sweeps were not taken over the same frequencies, e.g. when
+    /// non-finite samples were dropped from one but not the other; see
+    /// `without_non_finite_jointly`.
+    pub fn ratio(&self, other: &Sweep) -> Sweep {
+        self.assert_aligned(other);
+        Sweep {
+            freqs: self.freqs.clone(),
+            mags: self.mags.iter().zip(other.mags.iter()).map(|(a, b)| a/b).collect(),
+            phases: self.phases.iter().zip(other.phases.iter()).map(|(a, b)| wrap_phase(a - b)).collect(),
+        }
+    }
+
+    /// Frequency ranges in Hz where this sweep and another taken over the
+    /// same frequencies differ in magnitude by more than `threshold_db`.
+    /// Each range covers the samples that differ and extends halfway, on a
+    /// logarithmic axis, to the neighboring samples that match, so a single
+    /// differing sample still gives a range of some width. Ranges at the
+    /// ends of the sweep stop at the first or last frequency.
+    ///
+    /// # Panics
+    /// If the sweeps were not taken over the same frequencies.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// // A simple capacitor model stops matching one with ESL near resonance
+    /// let ideal = Sweep::of(&sdomain::gen::capacitor(1e-6), 1e3, 1e9);
+    /// let real = Sweep::of(&sdomain::gen::rcl(1e-3, 1e-6, 1e-9), 1e3, 1e9);
+    /// let regions = real.divergence(&ideal, 1.0);
+    /// assert!(regions[0].0 > 1e6 && regions[0].0 < 5e6);
+    /// assert_eq!(regions[regions.len() - 1].1, *ideal.freqs.last().unwrap());
+    ///
+    /// // One differing sample spans the half steps to either side
+    /// let flat = Sweep {freqs: vec![1.0, 10.0, 100.0], mags: vec![1.0; 3], phases: vec![0.0; 3]};
+    /// let spike = Sweep {mags: vec![1.0, 2.0, 1.0], ..flat.clone()};
+    /// assert_eq!(spike.divergence(&flat, 3.0), vec![(10f64.sqrt(), 1000f64.sqrt())]);
+    /// ```
+    pub fn divergence(&self, other: &Sweep, threshold_db: f64) -> Vec<(f64, f64)> {
+        self.assert_aligned(other);
+        let freqs = &self.freqs;
+        let differs = self.mags.iter().zip(other.mags.iter())
+            .map(|(a, b)| (20.0*(a/b).log10()).abs() > threshold_db)
+            .collect::<Vec<bool>>();
+        let mut regions = vec![];
+        let mut index = 0;
+        while index < differs.len() {
+            if !differs[index] {
+                index += 1;
+                continue;
+            }
+            let first = index;
+            while index + 1 < differs.len() && differs[index + 1] {index += 1;}
+            let start = if first > 0 {(freqs[first - 1]*freqs[first]).sqrt()} else {freqs[first]};
+            let end = if index + 1 < freqs.len() {(freqs[index]*freqs[index + 1]).sqrt()} else {freqs[index]};
+            regions.push((start, end));
+            index += 1;
+        }
+        regions
+    }
+
+    /// Reflection coefficient S11 of a swept impedance against a real
+    /// reference impedance `z0` in Ω, (Z - Z0)/(Z + Z0), as a sweep of its
+    /// linear magnitude and phase. A VNA or an EM solver reports the same
+    /// quantity for a one-port.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// // A matched load reflects nothing, a 1mΩ short reflects nearly all
+    /// let matched = Sweep::at(&sdomain::gen::resistor(50.0), vec![1e6]).to_s11(50.0);
+    /// assert!(matched.mags[0] < 1e-12);
+    /// let short = Sweep::at(&sdomain::gen::resistor(1e-3), vec![1e6]).to_s11(50.0);
+    /// assert!((short.mags[0] - 1.0).abs() < 1e-4);
+    /// assert!((short.phases[0].abs() - 180.0).abs() < 1e-6);
+    /// ```
+    pub fn to_s11(&self, z0: f64) -> Sweep {
+        let z0 = Phasor::from(z0);
+        let s11 = self.mags.iter().zip(self.phases.iter())
+            .map(|(m, p)| {
+                let z = Phasor::from_polar(*m, *p);
+                (z - z0)/(z + z0)
+            })
+            .collect::<Vec<Phasor>>();
+        Sweep {
+            freqs: self.freqs.clone(),
+            mags: s11.iter().map(Phasor::abs).collect(),
+            phases: s11.iter().map(Phasor::arg_deg).collect(),
+        }
+    }
+
+    /// Voltage standing wave ratio at each frequency of a reflection
+    /// coefficient sweep such as one from `to_s11`, (1 + |Γ|)/(1 - |Γ|).
+    /// Infinite where the reflection is total.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// // 100Ω against 50Ω reflects a third of the wave, a 2:1 VSWR
+    /// let vswr = Sweep::at(&sdomain::gen::resistor(100.0), vec![1e6]).to_s11(50.0).vswr();
+    /// assert!((vswr[0] - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn vswr(&self) -> Vec<f64> {
+        self.mags.iter()
+            .map(|gamma| if *gamma < 1.0 {(1.0 + gamma)/(1.0 - gamma)} else {f64::INFINITY})
+            .collect()
+    }
+
+    /// Real part of the response at each frequency.
+    pub fn real(&self) -> Vec<f64> {
+        self.mags.iter().zip(self.phases.iter()).map(|(m, p)| m*p.to_radians().cos()).collect()
+    }
+
+    /// Imaginary part of the response at each frequency.
+    pub fn imag(&self) -> Vec<f64> {
+        self.mags.iter().zip(self.phases.iter()).map(|(m, p)| m*p.to_radians().sin()).collect()
+    }
+
+    /// Smallest magnitude in the sweep.
+    pub fn min_mag(&self) -> f64 {
+        self.mags.iter().cloned().fold(f64::INFINITY, f64::min)
+    }
+
+    /// Largest magnitude in the sweep.
+    pub fn max_mag(&self) -> f64 {
+        self.mags.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Iterate over (frequency, magnitude) pairs.
+    pub fn mag_points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.freqs.iter().cloned().zip(self.mags.iter().cloned())
+    }
+
+    /// Iterate over (frequency, phase) pairs.
+    pub fn phase_points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.freqs.iter().cloned().zip(self.phases.iter().cloned())
+    }
+
+    /// Stable text table of the sweep for committing as a test snapshot.
+    /// Every value is rounded to `digits` significant figures and written in
+    /// scientific notation in a fixed-width column, so a numerical change
+    /// shows up as a readable line diff.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// let sweep = Sweep {
+    ///     freqs: vec![1e3, 1e6],
+    ///     mags: vec![0.052, 9.4247],
+    ///     phases: vec![0.0, -89.68],
+    /// };
+    /// let expected = concat!(
+    ///     "    freq [Hz]         |mag|     phase [°]\n",
+    ///     "      1.000e3      5.200e-2       0.000e0\n",
+    ///     "      1.000e6       9.425e0      -8.968e1\n",
+    /// );
+    /// assert_eq!(sweep.to_snapshot(4), expected);
+    /// ```
+    pub fn to_snapshot(&self, digits: usize) -> String {
+        let precision = digits.max(1) - 1;
+        // Negative zero would print as "-0.000e0" on some samples only
+        let cell = |value: f64| format!("{:>13.*e}", precision, if value == 0.0 {0.0} else {value});
+        let mut text = format!("{:>13} {:>13} {:>13}\n", "freq [Hz]", "|mag|", "phase [°]");
+        for ((freq, mag), phase) in self.freqs.iter().zip(self.mags.iter()).zip(self.phases.iter()) {
+            text.push_str(&format!("{} {} {}\n", cell(*freq), cell(*mag), cell(*phase)));
+        }
+        text
+    }
+
+    /// Frequency of the first sample whose magnitude or phase is NaN or
+    /// infinite, e.g. from a zero-valued component.
+    pub fn first_non_finite(&self) -> Option<f64> {
+        (0..self.freqs.len()).find(|i| !self.is_finite_at(*i)).map(|i| self.freqs[i])
+    }
+
+    /// The sweep with every sample whose magnitude or phase is NaN or
+    /// infinite left out.
+    pub fn without_non_finite(&self) -> Sweep {
+        let keep = (0..self.freqs.len()).filter(|i| self.is_finite_at(*i)).collect::<Vec<usize>>();
+        self.select(&keep)
+    }
+
+    /// This sweep and another taken over the same frequencies with every
+    /// sample that is NaN or infinite in either left out of both, so the
+    /// two stay aligned for `ratio`, `product`, or `divergence`.
+    ///
+    /// # Panics
+    /// If the sweeps were not taken over the same frequencies.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// let a = Sweep {freqs: vec![1.0, 2.0, 3.0], mags: vec![f64::NAN, 1.0, 1.0], phases: vec![0.0; 3]};
+    /// let b = Sweep {freqs: vec![1.0, 2.0, 3.0], mags: vec![1.0, 1.0, f64::INFINITY], phases: vec![0.0; 3]};
+    /// let (a, b) = a.without_non_finite_jointly(&b);
+    /// assert_eq!(a.freqs, vec![2.0]);
+    /// assert_eq!(b.freqs, vec![2.0]);
+    /// ```
+    pub fn without_non_finite_jointly(&self, other: &Sweep) -> (Sweep, Sweep) {
+        self.assert_aligned(other);
+        let keep = (0..self.freqs.len()).filter(|i| self.is_finite_at(*i) && other.is_finite_at(*i)).collect::<Vec<usize>>();
+        (self.select(&keep), other.select(&keep))
+    }
+
+    /// The sweep with every sample whose magnitude or phase is NaN or
+    /// infinite replaced by the nearest finite sample, keeping the
+    /// frequencies as they are.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// let sweep = Sweep {
+    ///     freqs: vec![1.0, 2.0, 3.0, 4.0],
+    ///     mags: vec![f64::INFINITY, 2.0, f64::NAN, 4.0],
+    ///     phases: vec![0.0, 10.0, 20.0, 30.0],
+    /// };
+    /// assert_eq!(sweep.first_non_finite(), Some(1.0));
+    /// assert_eq!(sweep.without_non_finite().freqs, vec![2.0, 4.0]);
+    /// let clamped = sweep.clamp_non_finite();
+    /// assert_eq!(clamped.mags, vec![2.0, 2.0, 2.0, 4.0]);
+    /// assert_eq!(clamped.phases, vec![10.0, 10.0, 10.0, 30.0]);
+    /// ```
+    pub fn clamp_non_finite(&self) -> Sweep {
+        let finite = (0..self.freqs.len()).filter(|i| self.is_finite_at(*i)).collect::<Vec<usize>>();
+        let mut clamped = self.clone();
+        for i in 0..self.freqs.len() {
+            if self.is_finite_at(i) {continue;}
+            if let Some(nearest) = finite.iter().min_by_key(|j| j.abs_diff(i)) {
+                clamped.mags[i] = self.mags[*nearest];
+                clamped.phases[i] = self.phases[*nearest];
+            }
+        }
+        clamped
+    }
+
+    /// Whether the sample at `index` has a finite magnitude and phase.
+    fn is_finite_at(&self, index: usize) -> bool {
+        self.mags[index].is_finite() && self.phases[index].is_finite()
+    }
+
+    /// The samples at `indices`, in order.
+    fn select(&self, indices: &[usize]) -> Sweep {
+        Sweep {
+            freqs: indices.iter().map(|i| self.freqs[*i]).collect(),
+            mags: indices.iter().map(|i| self.mags[*i]).collect(),
+            phases: indices.iter().map(|i| self.phases[*i]).collect(),
+        }
+    }
+
+    /// Panic unless `other` was taken over the same frequencies.
+    fn assert_aligned(&self, other: &Sweep) {
+        assert!(self.freqs == other.freqs, "sweeps taken over different frequencies ({} and {} samples)", self.freqs.len(), other.freqs.len());
+    }
+
+    /// Phase in degrees at each frequency with the ±180° wrapping removed,
+    /// so that a multi-pole response keeps falling past -180°. The first
+    /// point keeps its wrapped value.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// let sweep = Sweep {
+    ///     freqs: vec![1.0, 2.0, 3.0],
+    ///     mags: vec![1.0, 1.0, 1.0],
+    ///     phases: vec![-170.0, 170.0, 90.0],
+    /// };
+    /// assert_eq!(sweep.unwrapped_phases(), vec![-170.0, -190.0, -270.0]);
+    /// ```
+    pub fn unwrapped_phases(&self) -> Vec<f64> {
+        let mut offset = 0.0;
+        let mut previous: Option<f64> = None;
+        self.phases.iter().map(|phase| {
+                if let Some(last) = previous {
+                    let jump = phase - last;
+                    if jump > 180.0 {offset -= 360.0;}
+                    else if jump < -180.0 {offset += 360.0;}
+                }
+                previous = Some(*phase);
+                phase + offset
+            })
+            .collect()
+    }
+
+    /// Group delay -dφ/dω in seconds at each frequency, differentiating the
+    /// unwrapped phase. Interior points use the difference across their
+    /// neighbors and the end points the difference to their one neighbor.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::sweep::Sweep;
+    ///
+    /// // A pure 1µs delay has a phase of -360°·f·1µs
+    /// let freqs = vec![100e3, 200e3, 300e3];
+    /// let sweep = Sweep {
+    ///     phases: freqs.iter().map(|f| -360.0*f*1e-6).collect(),
+    ///     mags: vec![1.0; 3],
+    ///     freqs,
+    /// };
+    /// assert!(sweep.group_delays().iter().all(|tau| (tau/1e-6 - 1.0).abs() < 1e-9));
+    /// ```
+    pub fn group_delays(&self) -> Vec<f64> {
+        let phases = self.unwrapped_phases();
+        let last = self.freqs.len().saturating_sub(1);
+        (0..self.freqs.len())
+            .map(|index| {
+                let (before, after) = (index.saturating_sub(1), (index + 1).min(last));
+                if before == after {return 0.0;}
+                let dphase = (phases[after] - phases[before]).to_radians();
+                let domega = 2.0*std::f64::consts::PI*(self.freqs[after] - self.freqs[before]);
+                -dphase/domega
+            })
+            .collect()
+    }
+}
+
+/// Wrap a phase in degrees into the range (-180, 180].
+pub fn wrap_phase(deg: f64) -> f64 {
+    let wrapped = (deg + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped == -180.0 {180.0} else {wrapped}
+}
@@ -0,0 +1,114 @@
+use sdomain_test::pdn::PDNModel;
+
+use crate::bom::{self, Decap};
+use crate::vrm::BuckVrm;
+
+/// Capacitor values to draw decoupling from, E3 steps from 10nF to 47µF.
+const VALUES: [f64; 12] = [10e-9, 22e-9, 47e-9, 100e-9, 220e-9, 470e-9, 1e-6, 2.2e-6, 4.7e-6, 10e-6, 22e-6, 47e-6];
+
+/// A randomly generated PDN: a buck regulator and a decoupling bill of
+/// materials.
+#[derive(Clone, Debug)]
+pub struct SyntheticPdn {
+    pub vrm: BuckVrm,
+    pub decaps: Vec<Decap>,
+}
+
+impl SyntheticPdn {
+    /// The PDN with the regulator's closed-loop impedance as its source.
+    pub fn model(&self) -> PDNModel {
+        bom::build_pdn(&self.vrm.model(), &self.decaps)
+    }
+}
+
+/// Seeded generator of random but plausible PDN designs, for exercising the
+/// plotters, filling documentation galleries, and benchmarking. The same
+/// seed always gives the same designs on every platform.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::synthetic::Synthetic;
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use plotters::prelude::*;
+///
+/// let drawing_area = BitMapBackend::new("images/pdn_synthetic.png", (960, 720))
+///     .into_drawing_area();
+/// drawing_area.fill(&WHITE).unwrap();
+///
+/// let design = Synthetic::new(7).pdn();
+/// assert_eq!(design.decaps.len(), Synthetic::new(7).pdn().decaps.len());
+/// pdn_plotter::plot(&design.model(), &drawing_area, Some(0.05)).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Synthetic {
+    state: u64,
+}
+
+impl Synthetic {
+    /// Start a generator from `seed`.
+    pub fn new(seed: u64) -> Synthetic {
+        Synthetic {state: seed}
+    }
+
+    /// A buck regulator with a 20kHz to 200kHz loop, 220nH to 2.2µH
+    /// inductor, and 47µF to 470µF of bulk capacitance.
+    pub fn vrm(&mut self) -> BuckVrm {
+        BuckVrm {
+            bandwidth: self.log_uniform(20e3, 200e3),
+            inductance: self.log_uniform(220e-9, 2.2e-6),
+            inductor_resistance: self.log_uniform(1e-3, 10e-3),
+            capacitance: self.log_uniform(47e-6, 470e-6),
+            capacitor_esr: self.log_uniform(1e-3, 10e-3),
+        }
+    }
+
+    /// Two to five lines of decoupling capacitors with distinct values from
+    /// 10nF to 47µF, one to ten of each, in packages suited to their values.
+    pub fn decaps(&mut self) -> Vec<Decap> {
+        let lines = self.between(2, 5);
+        let mut values = VALUES.to_vec();
+        let mut decaps = vec![];
+        for index in 0..lines {
+            let value = values.remove(self.between(0, values.len() - 1));
+            let package = if value <= 100e-9 {"0201"} else if value <= 1e-6 {"0402"} else {"0603"};
+            decaps.push(Decap {
+                name: format!("C{}", index + 1),
+                value,
+                package: package.to_string(),
+                count: self.between(1, 10),
+            });
+        }
+        decaps.sort_by(|a, b| b.value.total_cmp(&a.value));
+        decaps
+    }
+
+    /// A regulator and decoupling together.
+    pub fn pdn(&mut self) -> SyntheticPdn {
+        SyntheticPdn {vrm: self.vrm(), decaps: self.decaps()}
+    }
+
+    /// Next raw value of the SplitMix64 sequence.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in [0, 1).
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64/(1u64 << 53) as f64
+    }
+
+    /// Value from `low` to `high`, uniform on a logarithmic scale.
+    fn log_uniform(&mut self, low: f64, high: f64) -> f64 {
+        low*(high/low).powf(self.unit())
+    }
+
+    /// Integer from `low` to `high` inclusive.
+    fn between(&mut self, low: usize, high: usize) -> usize {
+        low + (self.unit()*(high - low + 1) as f64) as usize
+    }
+}
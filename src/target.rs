@@ -0,0 +1,55 @@
+/// A frequency-dependent impedance target. The limit is interpolated
+/// linearly on log-log axes between corners, and held flat before the first
+/// corner and after the last.
+#[derive(Clone, Debug)]
+pub struct TargetMask {
+    points: Vec<(f64, f64)>,
+}
+
+impl From<Vec<(f64, f64)>> for TargetMask {
+    /// Create a mask from (frequency, impedance) corners. Corners are sorted by frequency.
+    fn from(mut points: Vec<(f64, f64)>) -> TargetMask {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        TargetMask {points}
+    }
+}
+
+impl TargetMask {
+    /// A target that is the same impedance at every frequency.
+    pub fn flat(impedance: f64) -> TargetMask {
+        TargetMask {points: vec![(1.0, impedance)]}
+    }
+
+    /// Impedance limit at `freq`.
+    pub fn limit_at(&self, freq: f64) -> f64 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return f64::INFINITY,
+        };
+        if freq <= first.0 {return first.1;}
+        if freq >= last.0 {return last.1;}
+        for pair in self.points.windows(2) {
+            let ((f0, z0), (f1, z1)) = (pair[0], pair[1]);
+            if freq <= f1 {
+                let frac = (freq/f0).ln()/(f1/f0).ln();
+                return z0*(z1/z0).powf(frac);
+            }
+        }
+        last.1
+    }
+
+    /// Corners of the mask clipped to `fmin..fmax`, suitable for drawing as a
+    /// line series.
+    pub fn outline(&self, fmin: f64, fmax: f64) -> Vec<(f64, f64)> {
+        let mut outline = vec![(fmin, self.limit_at(fmin))];
+        outline.extend(self.points.iter().cloned().filter(|(f, _)| *f > fmin && *f < fmax));
+        outline.push((fmax, self.limit_at(fmax)));
+        outline
+    }
+
+    /// Smallest and largest limit within `fmin..fmax`.
+    pub fn span(&self, fmin: f64, fmax: f64) -> (f64, f64) {
+        self.outline(fmin, fmax).iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, z)| (lo.min(*z), hi.max(*z)))
+    }
+}
@@ -0,0 +1,365 @@
+use std::convert::Infallible;
+use std::fmt;
+#[cfg(feature = "bitmap")]
+use std::fs;
+#[cfg(feature = "bitmap")]
+use std::path::Path;
+use std::path::PathBuf;
+
+use plotters::backend::{BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind};
+#[cfg(feature = "bitmap")]
+use plotters::prelude::*;
+
+#[cfg(feature = "bitmap")]
+use crate::render::{self, DrawAreaType};
+
+/// How far a rendered image may stray from its reference before a golden
+/// image check fails. Antialiased edges and text shift slightly between
+/// font rasterizer versions, so a small tolerance avoids false alarms.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    /// Largest difference in any color channel for a pixel to still count
+    /// as matching.
+    pub channel: u8,
+    /// Fraction of pixels, from 0 to 1, allowed to differ by more than
+    /// `channel`.
+    pub pixels: f64,
+}
+
+impl Tolerance {
+    /// Every pixel must match exactly.
+    pub fn exact() -> Tolerance {
+        Tolerance {channel: 0, pixels: 0.0}
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance {channel: 8, pixels: 0.001}
+    }
+}
+
+/// Result of comparing two RGB buffers pixel by pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// Number of pixels differing by more than the channel tolerance.
+    pub differing: usize,
+    /// Number of pixels compared.
+    pub total: usize,
+    /// Largest difference found in any color channel.
+    pub max_delta: u8,
+}
+
+impl Comparison {
+    /// Fraction of pixels that differ, from 0 to 1.
+    pub fn differing_fraction(&self) -> f64 {
+        if self.total == 0 {0.0} else {self.differing as f64/self.total as f64}
+    }
+}
+
+/// A golden image check that failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GoldenError {
+    /// There is no reference image at the path, and recording one was not
+    /// requested.
+    MissingReference(PathBuf),
+    /// The reference holds a different number of bytes than the rendered
+    /// image, e.g. because the image size changed.
+    SizeMismatch {expected: usize, actual: usize},
+    /// Too many pixels differ from the reference.
+    Mismatch(Comparison),
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GoldenError::MissingReference(path) => write!(f, "no reference at {}; run with UPDATE_GOLDEN=1 to record it", path.display()),
+            GoldenError::SizeMismatch {expected, actual} => write!(f, "reference has {expected} bytes but the image has {actual}"),
+            GoldenError::Mismatch(comparison) => write!(
+                f,
+                "{} of {} pixels differ from the reference (largest channel difference {})",
+                comparison.differing, comparison.total, comparison.max_delta
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+/// Compare two RGB buffers of the same size, three bytes per pixel.
+/// Returns how many pixels differ by more than `channel_tolerance` in any
+/// color channel, or an error if the buffers differ in size.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::testing::{self, GoldenError};
+///
+/// let reference = vec![255u8; 4*3];
+/// let mut actual = reference.clone();
+/// actual[0] = 250;
+/// actual[3] = 200;
+///
+/// let comparison = testing::compare(&actual, &reference, 8).unwrap();
+/// assert_eq!((comparison.differing, comparison.total, comparison.max_delta), (1, 4, 55));
+/// assert_eq!(testing::compare(&actual[..6], &reference, 8), Err(GoldenError::SizeMismatch {expected: 12, actual: 6}));
+/// ```
+pub fn compare(actual: &[u8], reference: &[u8], channel_tolerance: u8) -> Result<Comparison, GoldenError> {
+    if actual.len() != reference.len() {
+        return Err(GoldenError::SizeMismatch {expected: reference.len(), actual: actual.len()});
+    }
+    let mut comparison = Comparison {differing: 0, total: actual.len()/3, max_delta: 0};
+    for (a, r) in actual.chunks(3).zip(reference.chunks(3)) {
+        let delta = a.iter().zip(r.iter()).map(|(a, r)| a.abs_diff(*r)).max().unwrap_or(0);
+        comparison.max_delta = comparison.max_delta.max(delta);
+        if delta > channel_tolerance {
+            comparison.differing += 1;
+        }
+    }
+    Ok(comparison)
+}
+
+/// Whether golden references are to be recorded instead of checked, as
+/// requested by setting the environment variable `UPDATE_GOLDEN=1`.
+pub fn update_requested() -> bool {
+    std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1")
+}
+
+/// Render a plot into memory and check it against the reference image at
+/// `path`, stored as raw RGB bytes. A missing reference is an error, so a
+/// check never passes by accepting whatever it drew; run the tests with
+/// `UPDATE_GOLDEN=1` to record new references, or to re-record them after
+/// an intended change to a chart. With the `deterministic` feature and
+/// fonts pinned by `render::pin_font`, references can be checked with
+/// `Tolerance::exact` on any machine.
+/// Returns a result to indicate if the image matched, or why it did not.
+///
+/// # Arguments
+/// * `path` - Reference image file.
+/// * `size` - Width and height of the image in pixels.
+/// * `tolerance` - How far the image may stray from the reference.
+/// * `draw` - Plotting function to run on the drawing area.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::testing::{self, Tolerance};
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// let model = sdomain::gen::rl(52e-3, 1.5e-6);
+/// let path = std::env::temp_dir().join("sdomain_test_plotters_golden_check.rgb");
+/// testing::record_or_check_golden(&path, (320, 240), Tolerance::exact(), true, |area| {
+///     pdn_plotter::plot(&model, area, Some(0.1))
+/// }).unwrap();
+///
+/// testing::check_golden(&path, (320, 240), Tolerance::exact(), |area| {
+///     pdn_plotter::plot(&model, area, Some(0.1))
+/// }).unwrap();
+/// ```
+#[cfg(feature = "bitmap")]
+pub fn check_golden<F>(path: impl AsRef<Path>, size: (u32, u32), tolerance: Tolerance, draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<BitMapBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    record_or_check_golden(path, size, tolerance, update_requested(), draw)
+}
+
+/// Like `check_golden`, but whether to record the reference is given by
+/// `record` instead of the environment. Recording writes the rendered image
+/// to `path`, replacing any reference there, and always succeeds.
+///
+/// # Arguments
+/// * `path` - Reference image file.
+/// * `size` - Width and height of the image in pixels.
+/// * `tolerance` - How far the image may stray from the reference.
+/// * `record` - Whether to record the reference instead of checking it.
+/// * `draw` - Plotting function to run on the drawing area.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::testing::{self, Tolerance};
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use sdomain_test::sdomain;
+///
+/// let model = sdomain::gen::rl(52e-3, 1.5e-6);
+/// let path = std::env::temp_dir().join("sdomain_test_plotters_golden_rl.rgb");
+/// let _ = std::fs::remove_file(&path);
+///
+/// // Without a reference the check fails until one is recorded
+/// for record in [false, true, false] {
+///     let result = testing::record_or_check_golden(&path, (320, 240), Tolerance::exact(), record, |area| {
+///         pdn_plotter::plot(&model, area, Some(0.1))
+///     });
+///     assert_eq!(result.is_ok(), record || path.exists());
+/// }
+///
+/// // A different plot no longer matches
+/// let result = testing::record_or_check_golden(&path, (320, 240), Tolerance::default(), false, |area| {
+///     pdn_plotter::plot(&model, area, None)
+/// });
+/// assert!(result.is_err());
+/// ```
+#[cfg(feature = "bitmap")]
+pub fn record_or_check_golden<F>(path: impl AsRef<Path>, size: (u32, u32), tolerance: Tolerance, record: bool, draw: F) -> Result<(), Box <dyn std::error::Error>>
+where F: FnOnce(&DrawAreaType<BitMapBackend>) -> Result<(), Box <dyn std::error::Error>> {
+    let actual = render::render_to_buffer(size, draw)?;
+    let path = path.as_ref();
+    if record {
+        fs::write(path, &actual)?;
+        return Ok(());
+    }
+    if !path.exists() {
+        return Err(GoldenError::MissingReference(path.to_path_buf()).into());
+    }
+
+    let reference = fs::read(path)?;
+    let comparison = compare(&actual, &reference, tolerance.channel)?;
+    if comparison.differing_fraction() > tolerance.pixels {
+        return Err(GoldenError::Mismatch(comparison).into());
+    }
+    Ok(())
+}
+
+/// A drawing primitive captured by `RecordingBackend`. Coordinates are in
+/// pixels and colors are (red, green, blue).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Primitive {
+    /// A line or polyline, such as a series, gridline, or legend sample.
+    Path {points: Vec<(i32, i32)>, color: (u8, u8, u8)},
+    /// A rectangle, e.g. a background fill or legend box.
+    Rect {upper_left: (i32, i32), bottom_right: (i32, i32), color: (u8, u8, u8), filled: bool},
+    /// A filled polygon, such as the area under an area series.
+    Polygon {points: Vec<(i32, i32)>, color: (u8, u8, u8)},
+    /// A circle, such as a marker.
+    Circle {center: (i32, i32), radius: u32, color: (u8, u8, u8), filled: bool},
+    /// Text, such as a caption, axis label, tick label, or legend entry.
+    Text {text: String, pos: (i32, i32), size: f64},
+}
+
+/// Everything drawn on a `RecordingBackend`, in drawing order.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    pub primitives: Vec<Primitive>,
+    /// Number of single pixels drawn, e.g. by a bitmap blit.
+    pub pixels: usize,
+}
+
+impl Recording {
+    /// Every piece of text drawn, in drawing order.
+    pub fn texts(&self) -> Vec<&str> {
+        self.primitives.iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Text {text, ..} => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether `text` was drawn exactly, e.g. a caption or tick label.
+    pub fn has_text(&self, text: &str) -> bool {
+        self.texts().contains(&text)
+    }
+
+    /// Every path drawn in `color`, e.g. the series drawn in green.
+    pub fn paths_with_color(&self, color: (u8, u8, u8)) -> Vec<&[(i32, i32)]> {
+        self.primitives.iter()
+            .filter_map(|primitive| match primitive {
+                Primitive::Path {points, color: c} if *c == color => Some(points.as_slice()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// A drawing backend that records primitives instead of rasterizing them, so
+/// unit tests can check what a plotter drew, such as captions, tick labels,
+/// and series point counts, without fonts or pixel decoding. Text sizes are
+/// estimated from the character count.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::testing::{Recording, RecordingBackend};
+/// use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+///
+/// use plotters::prelude::*;
+/// use plotters::style::full_palette::PURPLE;
+/// use sdomain_test::sdomain;
+///
+/// let mut recording = Recording::default();
+/// {
+///     let area = RecordingBackend::new(&mut recording, (960, 720)).into_drawing_area();
+///     pdn_plotter::plot(&sdomain::gen::rl(52e-3, 1.5e-6), &area, Some(0.1)).unwrap();
+///     area.present().unwrap();
+/// }
+/// assert!(recording.has_text("Impedance of Model"));
+/// assert!(recording.has_text("100 mΩ"));
+/// // The impedance outline is drawn in purple when there is a target
+/// let outline = recording.paths_with_color((PURPLE.0, PURPLE.1, PURPLE.2));
+/// assert!(outline.iter().any(|points| points.len() > 100));
+/// ```
+pub struct RecordingBackend<'a> {
+    recording: &'a mut Recording,
+    size: (u32, u32),
+}
+
+impl<'a> RecordingBackend<'a> {
+    /// Record into `recording` as if drawing on an image of `size` pixels.
+    pub fn new(recording: &'a mut Recording, size: (u32, u32)) -> RecordingBackend<'a> {
+        RecordingBackend {recording, size}
+    }
+}
+
+impl<'a> DrawingBackend for RecordingBackend<'a> {
+    type ErrorType = Infallible;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Infallible>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Infallible>> {
+        Ok(())
+    }
+
+    fn draw_pixel(&mut self, _point: BackendCoord, _color: BackendColor) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.pixels += 1;
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(&mut self, from: BackendCoord, to: BackendCoord, style: &S) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.primitives.push(Primitive::Path {points: vec![from, to], color: style.color().rgb});
+        Ok(())
+    }
+
+    fn draw_rect<S: BackendStyle>(&mut self, upper_left: BackendCoord, bottom_right: BackendCoord, style: &S, fill: bool) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.primitives.push(Primitive::Rect {upper_left, bottom_right, color: style.color().rgb, filled: fill});
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(&mut self, path: I, style: &S) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.primitives.push(Primitive::Path {points: path.into_iter().collect(), color: style.color().rgb});
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(&mut self, center: BackendCoord, radius: u32, style: &S, fill: bool) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.primitives.push(Primitive::Circle {center, radius, color: style.color().rgb, filled: fill});
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(&mut self, vert: I, style: &S) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.primitives.push(Primitive::Polygon {points: vert.into_iter().collect(), color: style.color().rgb});
+        Ok(())
+    }
+
+    fn draw_text<S: BackendTextStyle>(&mut self, text: &str, style: &S, pos: BackendCoord) -> Result<(), DrawingErrorKind<Infallible>> {
+        self.recording.primitives.push(Primitive::Text {text: text.to_string(), pos, size: style.size()});
+        Ok(())
+    }
+
+    fn estimate_text_size<S: BackendTextStyle>(&self, text: &str, style: &S) -> Result<(u32, u32), DrawingErrorKind<Infallible>> {
+        let size = style.size();
+        Ok(((0.6*size*text.chars().count() as f64) as u32, size as u32))
+    }
+}
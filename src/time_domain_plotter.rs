@@ -0,0 +1,865 @@
+pub mod pdn_transient_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{PURPLE, GREY};
+
+    use sdomain_test::sdomain::Fs;
+
+    use crate::config::PlotConfig;
+    use crate::error::PlotError;
+    use crate::gradient::Gradient;
+    use crate::plottable::Plottable;
+    use crate::time_domain::{self, ImpulseResponse, LoadShape, PeriodicLoad, Pwl, VoltageLimits};
+    use crate::units::{format_freq, format_ohms, format_si};
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Plot the supply voltage deviation of a PDN driven by a piecewise-linear
+    /// load current waveform. The response is found by convolving the load
+    /// current with the impulse response of the PDN impedance. The worst droop
+    /// and overshoot are marked on the plot.
+    /// Returns a result to indicate if the function executed without error.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `load` - Load current waveform in amps.
+    /// * `duration` - Length of time to plot in seconds.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_ripple.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 1A load pulse with 100ns edges
+    /// let load = Pwl::from(vec![(0.0, 0.0), (2e-6, 0.0), (2.1e-6, 1.0), (12e-6, 1.0), (12.1e-6, 0.0)]);
+    /// pdn_transient_plotter::plot_ripple(&pdn, &load, 40e-6, &drawing_area).unwrap();
+    /// ```
+    pub fn plot_ripple<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, load: &Pwl, duration: f64, canvas: &DrawAreaType<DB>) -> Result<(), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw_deviation(model, load, duration, Some("Supply Voltage Deviation of PDN"), None, canvas).map(|_| ())
+    }
+
+    /// Plot the supply voltage deviation of a PDN driven by a load current
+    /// waveform against droop and overshoot limits, like an impedance plot
+    /// against its target. The limits are drawn as horizontal lines and the
+    /// times the voltage is outside them are shaded.
+    /// Returns the time ranges in seconds, (start, end), over which the
+    /// voltage is outside the limits, empty if it never is.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `load` - Load current waveform in amps.
+    /// * `duration` - Length of time to plot in seconds.
+    /// * `limits` - Allowed droop and overshoot of the rail.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::{Pwl, VoltageLimits};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_ripple_limits.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 5A load pulse on a 0.9V rail allowed to move ±3%
+    /// let load = Pwl::from(vec![(0.0, 0.0), (2e-6, 0.0), (2.1e-6, 5.0), (12e-6, 5.0), (12.1e-6, 0.0)]);
+    /// let limits = VoltageLimits::percent(0.9, 3.0);
+    /// let violations = pdn_transient_plotter::plot_ripple_with_limits(&pdn, &load, 40e-6, &limits, &drawing_area).unwrap();
+    /// for (start, end) in violations {
+    ///     assert!(start >= 2e-6 && end > start);
+    /// }
+    /// ```
+    pub fn plot_ripple_with_limits<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, load: &Pwl, duration: f64, limits: &VoltageLimits, canvas: &DrawAreaType<DB>) -> Result<Vec<(f64, f64)>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw_deviation(model, load, duration, Some("Supply Voltage Deviation of PDN"), Some(limits), canvas).map(|(_, _, violations)| violations)
+    }
+
+    /// Plot the supply voltage deviation of a PDN driven by an arbitrary
+    /// piecewise-linear load current, such as a bursty workload trace read
+    /// with `Pwl::parse`. The plot runs half as long again as the waveform,
+    /// so the response to its last corner is visible.
+    /// Returns the worst droop and overshoot in volts, each zero if the
+    /// voltage never moves in that direction.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `load` - Load current waveform in amps.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_pwl_load.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // Two bursts of activity on top of a 0.2A idle current
+    /// let load = Pwl::parse("PWL(0 0.2 1u 0.2 1.05u 1.5 4u 1.5 4.05u 0.2 8u 0.2 8.05u 2 9u 2 9.05u 0.2)").unwrap();
+    /// let (droop, _) = pdn_transient_plotter::plot_load(&pdn, &load, &drawing_area).unwrap();
+    /// assert!(droop < 0.0);
+    /// ```
+    pub fn plot_load<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, load: &Pwl, canvas: &DrawAreaType<DB>) -> Result<(f64, f64), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        draw_deviation(model, load, 1.5*load.end_time(), Some("Supply Voltage Deviation of PDN"), None, canvas)
+            .map(|(droop, overshoot, _)| (droop, overshoot))
+    }
+
+    /// Plot the steady-state supply voltage ripple of a PDN driven by a
+    /// periodic square or triangle load current over a few cycles, with the
+    /// load current on the secondary axis and the peak-to-peak ripple marked.
+    /// Returns the peak-to-peak ripple in volts.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `load` - The periodic load current.
+    /// * `cycles` - Number of periods to plot.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::{LoadShape, PeriodicLoad};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_periodic.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 0.5A to 2A at 100kHz, high 30% of the time
+    /// let load = PeriodicLoad {shape: LoadShape::Square, freq: 100e3, duty: 0.3, low: 0.5, high: 2.0};
+    /// let ripple = pdn_transient_plotter::plot_periodic(&pdn, &load, 3.0, &drawing_area).unwrap();
+    /// assert!(ripple > 0.0);
+    /// ```
+    pub fn plot_periodic<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, load: &PeriodicLoad, cycles: f64, canvas: &DrawAreaType<DB>) -> Result<f64, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        let duration = cycles/load.freq;
+        if !(duration.is_finite() && duration > 0.0) {return Err(PlotError::InvalidDuration(duration).into());}
+        let (times, voltage) = time_domain::periodic_ripple(model, load, cycles);
+        let (scale, unit) = time_unit(duration);
+        let times = times.into_iter().map(|t| t*scale).collect::<Vec<f64>>();
+
+        let v_max = voltage.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let v_min = voltage.iter().cloned().fold(f64::INFINITY, f64::min);
+        let ripple = v_max - v_min;
+        let span = ripple.max(1e-6);
+        let v_range = (v_min - 0.15*span)..(v_max + 0.15*span);
+
+        let currents = times.iter().map(|t| load.value_at(t/scale)).collect::<Vec<f64>>();
+        let (i_min, i_max) = (load.low.min(load.high), load.low.max(load.high));
+        let i_span = (i_max - i_min).max(1e-3);
+
+        let shape = match load.shape {
+            LoadShape::Square => "Square",
+            LoadShape::Triangle => "Triangle",
+        };
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("Steady-State Ripple, {shape} Load at {}", format_freq(load.freq)), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Right, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(0.0..duration*scale, v_range)?
+            .set_secondary_coord(0.0..duration*scale, (i_min - 0.1*i_span)..(i_max + 0.1*i_span));
+
+        chart.configure_mesh()
+            .x_desc(format!("Time [{unit}]"))
+            .y_desc("Voltage Ripple [mV]")
+            .y_label_formatter(&|v| format!("{:.1}", v*1e3))
+            .draw()?;
+        chart.configure_secondary_axes().y_desc("Load Current [A]").draw()?;
+
+        chart.draw_series(LineSeries::new(
+                times.iter().cloned().zip(voltage.iter().cloned()),
+                &GREEN
+            ))?
+            .label("Voltage")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+        for level in [v_max, v_min] {
+            chart.draw_series(LineSeries::new(
+                    vec![(0.0, level), (duration*scale, level)],
+                    BLUE.mix(0.5)
+                ))?;
+        }
+        chart.draw_series(std::iter::once(Text::new(
+                format!("{} peak-to-peak", format_si(ripple, "V")),
+                (0.0, v_max + 0.1*span),
+                ("Arial", 15)
+            )))?;
+
+        chart.draw_secondary_series(LineSeries::new(
+                times.iter().cloned().zip(currents),
+                &RED.mix(0.4)
+            ))?
+            .label("Load Current")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(ripple)
+    }
+
+    /// Plot the supply voltage deviation of a PDN as a periodic load switches
+    /// on, with every cycle folded onto a single period and overlaid, like an
+    /// eye diagram. Cycles are drawn with transparency and colored from the
+    /// first to the last along the viridis gradient, so the settling of the
+    /// network shows as the spread between them and the steady state as the
+    /// dense band they converge to.
+    /// Returns the peak-to-peak ripple of each cycle in volts, first to last.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `load` - The periodic load current.
+    /// * `cycles` - Number of periods to simulate and overlay.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::{LoadShape, PeriodicLoad};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_folded.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// let load = PeriodicLoad {shape: LoadShape::Square, freq: 500e3, duty: 0.3, low: 0.5, high: 2.0};
+    /// let ripples = pdn_transient_plotter::plot_folded(&pdn, &load, 12, &drawing_area).unwrap();
+    /// assert_eq!(ripples.len(), 12);
+    /// ```
+    pub fn plot_folded<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, load: &PeriodicLoad, cycles: usize, canvas: &DrawAreaType<DB>) -> Result<Vec<f64>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if cycles == 0 {return Err(PlotError::Empty.into());}
+        let period = 1.0/load.freq;
+        if !(period.is_finite() && period > 0.0) {return Err(PlotError::InvalidDuration(period).into());}
+        let (times, voltage) = time_domain::periodic_transient(model, load, cycles);
+        let (scale, unit) = time_unit(period);
+        let per_cycle = times.len()/cycles;
+
+        // Time within the period of every sample, one trace per cycle
+        let traces = voltage.chunks(per_cycle)
+            .map(|cycle| times.iter().take(per_cycle).map(|t| t*scale).zip(cycle.iter().cloned()).collect::<Vec<(f64, f64)>>())
+            .collect::<Vec<Vec<(f64, f64)>>>();
+        let ripples = traces.iter()
+            .map(|trace| {
+                let max = trace.iter().map(|(_, v)| *v).fold(f64::NEG_INFINITY, f64::max);
+                let min = trace.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+                max - min
+            })
+            .collect::<Vec<f64>>();
+
+        let v_max = voltage.iter().cloned().fold(0.0, f64::max);
+        let v_min = voltage.iter().cloned().fold(0.0, f64::min);
+        let span = (v_max - v_min).max(1e-6);
+        let v_range = (v_min - 0.1*span)..(v_max + 0.15*span);
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("Folded Ripple, {cycles} Cycles at {}", format_freq(load.freq)), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(0.0..period*scale, v_range)?;
+
+        chart.configure_mesh()
+            .x_desc(format!("Time in Period [{unit}]"))
+            .y_desc("Voltage Deviation [mV]")
+            .y_label_formatter(&|v| format!("{:.1}", v*1e3))
+            .draw()?;
+
+        let colors = Gradient::Viridis.spread(cycles);
+        for (index, (trace, color)) in traces.into_iter().zip(colors.iter()).enumerate() {
+            let color = color.mix(0.5);
+            let series = chart.draw_series(LineSeries::new(trace, color))?;
+            // Only the ends of the gradient need naming
+            if index == 0 || index == cycles - 1 {
+                series
+                    .label(format!("Cycle {}", index + 1))
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], color));
+            }
+        }
+
+        let first = ripples[0];
+        let last = ripples[cycles - 1];
+        chart.draw_series(std::iter::once(Text::new(
+                format!("First cycle {} pk-pk, last cycle {} pk-pk", format_si(first, "V"), format_si(last, "V")),
+                (0.0, v_max + 0.1*span),
+                ("Arial", 15)
+            )))?;
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(ripples)
+    }
+
+    /// Plot the supply voltage deviation of a PDN for several load current
+    /// waveforms at once, from a tabulated impulse response. Each waveform
+    /// costs only a convolution with the table, so what-if comparisons of
+    /// workloads can be replotted quickly from a response computed once with
+    /// `ImpulseResponse::from_model` or `from_transform`, or measured.
+    /// Returns the worst droop and overshoot in volts for each load, in the
+    /// order given, each zero if the voltage never moves in that direction.
+    ///
+    /// # Arguments
+    /// * `impulse` - Impulse response of the PDN impedance.
+    /// * `loads` - (name, waveform) of each load current in amps.
+    /// * `duration` - Length of time to plot in seconds.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::{ImpulseResponse, Pwl};
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_what_if.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    /// let impulse = ImpulseResponse::from_model(&pdn, 40e-9, 1000);
+    ///
+    /// let fast = Pwl::from(vec![(0.0, 0.0), (2e-6, 0.0), (2.05e-6, 1.0)]);
+    /// let slow = Pwl::from(vec![(0.0, 0.0), (2e-6, 0.0), (4e-6, 1.0)]);
+    /// let extremes = pdn_transient_plotter::plot_what_if(&impulse, &[("50ns edge", &fast), ("2us edge", &slow)], 40e-6, &drawing_area).unwrap();
+    /// assert_eq!(extremes.len(), 2);
+    /// ```
+    pub fn plot_what_if<DB: DrawingBackend>(impulse: &ImpulseResponse, loads: &[(&str, &Pwl)], duration: f64, canvas: &DrawAreaType<DB>) -> Result<Vec<(f64, f64)>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if loads.is_empty() {return Err(PlotError::Empty.into());}
+        if !(duration.is_finite() && duration > 0.0) {return Err(PlotError::InvalidDuration(duration).into());}
+        let (scale, unit) = time_unit(duration);
+        let responses = loads.iter()
+            .map(|(_, load)| {
+                let (times, voltage) = impulse.voltage_deviation(load, duration);
+                times.into_iter().map(|t| t*scale).zip(voltage).collect::<Vec<(f64, f64)>>()
+            })
+            .collect::<Vec<Vec<(f64, f64)>>>();
+        if responses[0].is_empty() {return Err(PlotError::Empty.into());}
+        let extremes = responses.iter()
+            .map(|response| (
+                response.iter().map(|(_, v)| *v).fold(0.0, f64::min),
+                response.iter().map(|(_, v)| *v).fold(0.0, f64::max),
+            ))
+            .collect::<Vec<(f64, f64)>>();
+
+        let v_min = extremes.iter().map(|(droop, _)| *droop).fold(0.0, f64::min);
+        let v_max = extremes.iter().map(|(_, overshoot)| *overshoot).fold(0.0, f64::max);
+        let span = (v_max - v_min).max(1e-6);
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption("Supply Voltage Deviation by Load", ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(0.0..duration*scale, (v_min - 0.1*span)..(v_max + 0.1*span))?;
+
+        chart.configure_mesh()
+            .x_desc(format!("Time [{unit}]"))
+            .y_desc("Voltage Deviation [mV]")
+            .y_label_formatter(&|v| format!("{:.1}", v*1e3))
+            .draw()?;
+
+        for (index, ((name, _), response)) in loads.iter().zip(responses.into_iter()).enumerate() {
+            let color = Palette99::pick(index).to_rgba();
+            let (droop, _) = extremes[index];
+            chart.draw_series(LineSeries::new(response, &color))?
+                .label(format!("{name} (droop {:.2} mV)", droop*1e3))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(extremes)
+    }
+
+    /// Plot which capacitor banks of a PDN supply a load current step and
+    /// when: the current from each bank over time, and below it the charge
+    /// each has delivered so far. Whatever the banks do not supply comes
+    /// from the source, shown as the remainder. Fast, small capacitors carry
+    /// the first edge and hand over to the bulk capacitors and then the
+    /// source as time goes on.
+    /// Returns the charge in coulombs delivered by each bank by the end of
+    /// the plot, in the order given.
+    ///
+    /// # Arguments
+    /// * `model` - Impedance of the whole PDN, e.g. from `PDNModel::model`.
+    /// * `banks` - The name, single-part impedance model, and count of each bank.
+    /// * `load` - Load current waveform in amps.
+    /// * `duration` - Length of time to plot in seconds.
+    /// * `canvas` - A Plotter's DrawingArea to divide between the current and
+    ///              charge plots.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::time_domain::Pwl;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_bank_currents.png", (960, 960))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let banks = [
+    ///     ("0201 100nF", Capacitor::from(100e-9, "0201").model(), 8),
+    ///     ("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4),
+    /// ];
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(5e-3, 1.5e-6), None);
+    /// for (name, model, count) in banks.iter() {
+    ///     pdn.add_capacitor(name, model.clone(), *count);
+    /// }
+    ///
+    /// // 2A step with a 20ns edge
+    /// let load = Pwl::from(vec![(0.0, 0.0), (0.5e-6, 0.0), (0.52e-6, 2.0)]);
+    /// let charges = pdn_transient_plotter::plot_bank_currents(&pdn.model(), &banks, &load, 20e-6, &drawing_area).unwrap();
+    /// assert_eq!(charges.len(), 2);
+    /// ```
+    pub fn plot_bank_currents<DB: DrawingBackend>(model: &Fs, banks: &[(&str, Fs, usize)], load: &Pwl, duration: f64, canvas: &DrawAreaType<DB>) -> Result<Vec<f64>, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if banks.is_empty() {return Err(PlotError::Empty.into());}
+        if !(duration.is_finite() && duration > 0.0) {return Err(PlotError::InvalidDuration(duration).into());}
+        let (times, mut currents) = time_domain::bank_currents(model, banks, load, duration);
+        let dt = duration/times.len().max(1) as f64;
+        let (scale, unit) = time_unit(duration);
+
+        let initial = load.value_at(0.0);
+        let load_current = times.iter().map(|t| load.value_at(*t) - initial).collect::<Vec<f64>>();
+        let source = load_current.iter().enumerate()
+            .map(|(k, i)| i - currents.iter().map(|bank| bank[k]).sum::<f64>())
+            .collect::<Vec<f64>>();
+        let mut names = banks.iter().map(|(name, _, count)| format!("{count} × {name}")).collect::<Vec<String>>();
+        names.push("Source".to_string());
+        currents.push(source);
+
+        // Running sum of current over time
+        let charges = currents.iter()
+            .map(|current| current.iter().scan(0.0, |charge, i| {*charge += i*dt; Some(*charge)}).collect::<Vec<f64>>())
+            .collect::<Vec<Vec<f64>>>();
+
+        let (upper, lower) = canvas.split_vertically((50).percent_height());
+        let times = times.into_iter().map(|t| t*scale).collect::<Vec<f64>>();
+        let panels = [
+            (&upper, "Current Supplied by Each Bank", "Current", &currents, "A", Some(&load_current)),
+            (&lower, "Charge Delivered by Each Bank", "Charge", &charges, "C", None),
+        ];
+        for (area, title, y_desc, series, si_unit, reference) in panels {
+            let values = || series.iter().flatten().chain(reference.into_iter().flatten()).cloned();
+            let min = values().fold(0.0, f64::min);
+            let max = values().fold(0.0, f64::max);
+            let span = (max - min).max(1e-12);
+
+            let mut chart = ChartBuilder::on(area)
+            .caption(title, ("Arial", 30))
+                .set_label_area_size(LabelAreaPosition::Left, 60)
+                .set_label_area_size(LabelAreaPosition::Bottom, 40)
+                .margin(10)
+                .build_cartesian_2d(0.0..duration*scale, (min - 0.1*span)..(max + 0.1*span))?;
+
+            chart.configure_mesh()
+                .x_desc(format!("Time [{unit}]"))
+                .y_desc(y_desc)
+                .y_label_formatter(&|v| format_si(*v, si_unit))
+                .draw()?;
+
+            if let Some(reference) = reference {
+                chart.draw_series(LineSeries::new(
+                        times.iter().cloned().zip(reference.iter().cloned()),
+                        &RED.mix(0.4)
+                    ))?
+                    .label("Load")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+            }
+            for (index, (name, values)) in names.iter().zip(series.iter()).enumerate() {
+                let color = if index == banks.len() {BLACK.to_rgba()} else {Palette99::pick(index).to_rgba()};
+                chart.draw_series(LineSeries::new(
+                        times.iter().cloned().zip(values.iter().cloned()),
+                        &color
+                    ))?
+                    .label(name.as_str())
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &color));
+            }
+
+            chart.configure_series_labels()
+                .position(SeriesLabelPosition::LowerRight)
+                .border_style(&BLACK)
+                .background_style(&GREY.mix(0.3))
+                .draw()?;
+        }
+
+        Ok(charges.iter().take(banks.len()).map(|charge| charge.last().cloned().unwrap_or(0.0)).collect())
+    }
+
+    /// Plot the supply voltage excursion of a PDN after a load current step,
+    /// the time-domain counterpart of an impedance target. The step starts
+    /// after a short flat lead-in and the plot spans two periods of the
+    /// PDN's lowest anti-resonance, or 200 rise times if that is longer, so
+    /// the slowest droop is in view (see `time_domain::load_step_window`).
+    /// The peak droop is marked together with the impedance it corresponds
+    /// to, ΔV/ΔI, for comparison with the target, in a line under the chart.
+    /// Returns the peak droop in volts as a positive number.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `i_step` - Size of the load current step in amps.
+    /// * `rise_time` - Duration of the current edge in seconds.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    /// * `config` - Options for the plot; only the caption applies.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::pdn_transient_plotter;
+    /// use sdomain_test_plotters::config::PlotConfig;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::passives::capacitor::Capacitor;
+    /// use sdomain_test::pdn::PDNModel;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/pdn_load_step.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    /// pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 4);
+    ///
+    /// // 2A step with a 100ns edge
+    /// let droop = pdn_transient_plotter::plot_load_step(&pdn, 2.0, 100e-9, &drawing_area, &PlotConfig::default()).unwrap();
+    /// assert!(droop > 0.0);
+    /// ```
+    pub fn plot_load_step<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, i_step: f64, rise_time: f64, canvas: &DrawAreaType<DB>, config: &PlotConfig) -> Result<f64, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if !(rise_time.is_finite() && rise_time > 0.0) {return Err(PlotError::InvalidDuration(rise_time).into());}
+        let duration = time_domain::load_step_window(model, rise_time);
+        let start = 0.05*duration;
+        let load = Pwl::from(vec![(0.0, 0.0), (start, 0.0), (start + rise_time, i_step)]);
+        let title = config.caption_or(&format!("Load Step Response of PDN ({i_step} A, {} edge)", format_si(rise_time, "s")));
+
+        // The readout gets its own strip so it covers neither the caption nor the trace
+        let (_, height) = canvas.dim_in_pixel();
+        let (chart_area, readout_area) = canvas.split_vertically(height.saturating_sub(30));
+        let (droop, _, _) = draw_deviation(model, &load, duration, title.as_deref(), None, &chart_area)?;
+        let peak = droop.abs();
+
+        if i_step != 0.0 {
+            let line = format!("Peak droop {} = {} × {}", format_si(peak, "V"), format_ohms(peak/i_step.abs()), format_si(i_step.abs(), "A"));
+            readout_area.draw(&Text::new(line, (10, 5), ("Arial", 18)))?;
+        }
+
+        Ok(peak)
+    }
+
+    /// Plot the supply voltage deviation of a PDN driven by a load current
+    /// waveform, with the load current on the secondary axis and the worst
+    /// droop and overshoot marked.
+    /// Returns the worst droop and overshoot in volts, each zero if the
+    /// voltage never moves in that direction, and the time ranges in seconds
+    /// over which the voltage is outside the limits, if any are given.
+    ///
+    /// # Arguments
+    /// * `model` - A power distribution network model, or any impedance model.
+    /// * `load` - Load current waveform in amps.
+    /// * `duration` - Length of time to plot in seconds.
+    /// * `title` - (Optional) Text to print as the plot title.
+    /// * `limits` - (Optional) If specified, the limits are drawn as lines and
+    ///              the times outside them are shaded.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the voltage plot.
+    fn draw_deviation<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, load: &Pwl, duration: f64, title: Option<&str>, limits: Option<&VoltageLimits>, canvas: &DrawAreaType<DB>) -> Result<(f64, f64, Vec<(f64, f64)>), Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if !(duration.is_finite() && duration > 0.0) {return Err(PlotError::InvalidDuration(duration).into());}
+        let (times, voltage) = time_domain::voltage_deviation(model, load, duration);
+        let violations = limits.map(|limits| limits.violations(&times, &voltage)).unwrap_or_default();
+        let (scale, unit) = time_unit(duration);
+        let times = times.into_iter().map(|t| t*scale).collect::<Vec<f64>>();
+
+        let (mut droop, mut overshoot) = ((0.0, 0.0), (0.0, 0.0));
+        for (t, v) in times.iter().zip(voltage.iter()) {
+            if *v < droop.1 {droop = (*t, *v);}
+            if *v > overshoot.1 {overshoot = (*t, *v);}
+        }
+        let (v_low, v_high) = match limits {
+            Some(limits) => (droop.1.min(-limits.droop), overshoot.1.max(limits.overshoot)),
+            None => (droop.1, overshoot.1),
+        };
+        let span = (v_high - v_low).max(1e-6);
+        let (bottom, top) = (v_low - 0.1*span, v_high + 0.1*span);
+
+        let currents = times.iter().map(|t| load.value_at(t/scale)).collect::<Vec<f64>>();
+        let i_max = currents.iter().cloned().fold(0.0, f64::max);
+        let i_min = currents.iter().cloned().fold(0.0, f64::min);
+        let i_span = (i_max - i_min).max(1e-3);
+
+        let mut builder = ChartBuilder::on(canvas);
+        if let Some(title) = title {
+            builder.caption(title, ("Arial", 30));
+        }
+        let mut chart = builder
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Right, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(0.0..duration*scale, bottom..top)?
+            .set_secondary_coord(0.0..duration*scale, (i_min - 0.1*i_span)..(i_max + 0.1*i_span));
+
+        chart.configure_mesh()
+            .x_desc(format!("Time [{unit}]"))
+            .y_desc("Voltage Deviation [mV]")
+            .y_label_formatter(&|v| format!("{:.1}", v*1e3))
+            .draw()?;
+        chart.configure_secondary_axes().y_desc("Load Current [A]").draw()?;
+
+        if let Some(limits) = limits {
+            if !violations.is_empty() {
+                chart.draw_series(violations.iter().map(|(start, end)| {
+                        Rectangle::new([(start*scale, bottom), (end*scale, top)], RED.mix(0.2).filled())
+                    }))?
+                    .label("Out of Spec")
+                    .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], RED.mix(0.2).filled()));
+            }
+            chart.draw_series(LineSeries::new(
+                    vec![(0.0, -limits.droop), (duration*scale, -limits.droop)],
+                    &PURPLE
+                ))?
+                .label("Spec")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &PURPLE));
+            chart.draw_series(LineSeries::new(
+                    vec![(0.0, limits.overshoot), (duration*scale, limits.overshoot)],
+                    &PURPLE
+                ))?;
+        }
+
+        chart.draw_series(LineSeries::new(
+                times.iter().cloned().zip(voltage.iter().cloned()),
+                &GREEN
+            ))?
+            .label("Voltage")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+        chart.draw_secondary_series(LineSeries::new(
+                times.iter().cloned().zip(currents),
+                &RED.mix(0.4)
+            ))?
+            .label("Load Current")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+
+        let markers = [("Droop", droop, &BLUE), ("Overshoot", overshoot, &MAGENTA)];
+        for (name, (t, v), color) in markers {
+            if v == 0.0 {continue;}
+            chart.draw_series(std::iter::once(
+                    EmptyElement::at((t, v))
+                    + Circle::new((0, 0), 4, color.filled())
+                    + Text::new(format!("{name}: {:.2} mV @ {t:.2} {unit}", v*1e3), (8, -8), ("Arial", 15))
+                ))?;
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok((droop.1, overshoot.1, violations))
+    }
+
+    /// Pick a scale factor and unit name so that `duration` reads as a
+    /// reasonable number on the time axis.
+    pub(super) fn time_unit(duration: f64) -> (f64, &'static str) {
+        if duration >= 1.0 {(1.0, "s")}
+        else if duration >= 1e-3 {(1e3, "ms")}
+        else if duration >= 1e-6 {(1e6, "µs")}
+        else {(1e9, "ns")}
+    }
+}
+
+pub mod step_plotter {
+    use plotters::prelude::*;
+    use plotters::style::full_palette::{ORANGE, GREY};
+
+    use crate::error::PlotError;
+    use crate::plottable::Plottable;
+    use crate::time_domain::{self, StepMetrics, TIME_POINTS};
+    type DrawAreaType<DB> = DrawingArea <DB, plotters::coord::Shift>;
+
+    /// Plot the unit step response of a model, such as a closed-loop
+    /// transfer function, with the 10–90% rise time and the 2% and 5%
+    /// settling times marked by guide lines. The peak overshoot and any
+    /// undershoot are marked with their absolute and percentage size.
+    /// Returns the rise and settling figures that were drawn.
+    ///
+    /// # Arguments
+    /// * `model` - A transfer function or any other model.
+    /// * `duration` - Length of time to plot in seconds.
+    /// * `canvas` - A Plotter's DrawingArea on which to draw the step response.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::time_domain_plotter::step_plotter;
+    ///
+    /// use plotters::prelude::*;
+    /// use sdomain_test::sdomain;
+    ///
+    /// let drawing_area = BitMapBackend::new("images/step_response.png", (960, 720))
+    ///     .into_drawing_area();
+    /// drawing_area.fill(&WHITE).unwrap();
+    ///
+    /// // RC low pass filter with τ = 100µs
+    /// let zr = sdomain::gen::resistor(100.0);
+    /// let zc = sdomain::gen::capacitor(1e-6);
+    /// let lpf = zc.clone() / &(zr + &zc);
+    /// let metrics = step_plotter::plot_step(&lpf, 1e-3, &drawing_area).unwrap();
+    /// assert!((metrics.final_value - 1.0).abs() < 0.05);
+    /// assert!(metrics.rise_time().unwrap() > 100e-6);
+    /// ```
+    pub fn plot_step<DB: DrawingBackend, P: Plottable + ?Sized>(model: &P, duration: f64, canvas: &DrawAreaType<DB>) -> Result<StepMetrics, Box <dyn std::error::Error>>
+    where DB::ErrorType: 'static {
+        if !(duration.is_finite() && duration > 0.0) {return Err(PlotError::InvalidDuration(duration).into());}
+        let dt = duration/TIME_POINTS as f64;
+        let values = time_domain::step_response(model, dt, TIME_POINTS);
+        let times = (0..TIME_POINTS).map(|k| k as f64*dt).collect::<Vec<f64>>();
+        let metrics = time_domain::step_metrics(&times, &values);
+
+        let (scale, unit) = super::pdn_transient_plotter::time_unit(duration);
+        let min_val = values.iter().cloned().fold(0.0, f64::min);
+        let max_val = values.iter().cloned().fold(0.0, f64::max);
+        let span = (max_val - min_val).max(1e-9);
+        let v_range = (min_val - 0.1*span)..(max_val + 0.1*span);
+
+        let mut chart = ChartBuilder::on(&canvas)
+        .caption(format!("Step Response of {}", model.display_name()), ("Arial", 30))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(10)
+            .build_cartesian_2d(0.0..duration*scale, v_range.clone())?;
+
+        chart.configure_mesh()
+            .x_desc(format!("Time [{unit}]"))
+            .y_desc("Response")
+            .draw()?;
+
+        // Settling bands around the final value, a fraction of the step size
+        // as in `step_metrics`
+        let final_value = metrics.final_value;
+        let change = (final_value - metrics.initial_value).abs();
+        for (band, color) in [(0.02, &BLUE), (0.05, &ORANGE)] {
+            for side in [-1.0, 1.0] {
+                let level = final_value + side*band*change;
+                chart.draw_series(LineSeries::new(
+                        vec![(0.0, level), (duration*scale, level)],
+                        color.mix(0.3)
+                    ))?;
+            }
+        }
+
+        chart.draw_series(LineSeries::new(
+                times.iter().map(|t| t*scale).zip(values.iter().cloned()),
+                &GREEN
+            ))?
+            .label("Step Response")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &GREEN));
+
+        let mut guides = vec![];
+        if let Some((start, end)) = metrics.rise {
+            guides.push((start, format!("10% @ {:.3} {unit}", start*scale), &MAGENTA));
+            guides.push((end, format!("90% @ {:.3} {unit} (tr = {:.3} {unit})", end*scale, (end - start)*scale), &MAGENTA));
+        }
+        guides.push((metrics.settling_5pct, format!("5% settled @ {:.3} {unit}", metrics.settling_5pct*scale), &ORANGE));
+        guides.push((metrics.settling_2pct, format!("2% settled @ {:.3} {unit}", metrics.settling_2pct*scale), &BLUE));
+        for (index, (t, label, color)) in guides.into_iter().enumerate() {
+            chart.draw_series(LineSeries::new(
+                    vec![(t*scale, v_range.start), (t*scale, v_range.end)],
+                    color.mix(0.5)
+                ))?;
+            chart.draw_series(std::iter::once(
+                    EmptyElement::at((t*scale, v_range.start))
+                    + Text::new(label, (4, -20 - 18*index as i32), ("Arial", 15))
+                ))?;
+        }
+
+        let initial = metrics.initial_value;
+        let direction = if final_value < initial {-1.0} else {1.0};
+        let peaks = [
+            ("Overshoot", metrics.overshoot.map(|(t, amount)| (t, final_value + direction*amount, amount))),
+            ("Undershoot", metrics.undershoot.map(|(t, amount)| (t, initial - direction*amount, amount))),
+        ];
+        for (name, peak) in peaks {
+            if let Some((t, v, amount)) = peak {
+                let pct = 100.0*amount/(final_value - initial).abs();
+                chart.draw_series(std::iter::once(
+                        EmptyElement::at((t*scale, v))
+                        + Circle::new((0, 0), 4, RED.filled())
+                        + Text::new(format!("{name}: {amount:.3} ({pct:.1}%) @ {:.3} {unit}", t*scale), (8, -8), ("Arial", 15))
+                    ))?;
+            }
+        }
+
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .border_style(&BLACK)
+            .background_style(&GREY.mix(0.3))
+            .draw()?;
+
+        Ok(metrics)
+    }
+}
@@ -0,0 +1,232 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use sdomain_test::sdomain::{self, Fs};
+
+use crate::phasor::Phasor;
+use crate::sweep::Sweep;
+use crate::units::{format_farads, format_ohms, format_si};
+
+/// Reason a Touchstone file could not be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TouchstoneError {
+    /// The option line (`# GHz S MA R 50`) could not be parsed. Lines are
+    /// numbered from 1.
+    Options {line: usize},
+    /// A data value could not be parsed as a number.
+    Data {line: usize},
+    /// The file holds parameters other than S parameters.
+    Unsupported(String),
+    /// The number of data values is not a whole number of frequency points.
+    Incomplete,
+}
+
+impl fmt::Display for TouchstoneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TouchstoneError::Options {line} => write!(f, "invalid option line {line}"),
+            TouchstoneError::Data {line} => write!(f, "invalid data on line {line}"),
+            TouchstoneError::Unsupported(kind) => write!(f, "unsupported parameter type {kind}"),
+            TouchstoneError::Incomplete => write!(f, "data ends partway through a frequency point"),
+        }
+    }
+}
+
+impl std::error::Error for TouchstoneError {}
+
+/// Two-port S parameters read from a .s2p file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct S2p {
+    /// Reference impedance in Ω.
+    pub z0: f64,
+    /// Frequencies in Hz.
+    pub freqs: Vec<f64>,
+    /// S11, S21, S12, S22 at each frequency, in the file's order.
+    pub params: Vec<[Phasor; 4]>,
+}
+
+/// How a capacitor was mounted for a two-port measurement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fixture {
+    /// Capacitor from the through line to ground, the usual setup for
+    /// low-impedance parts: Z = Z0·S21/(2·(1 - S21)).
+    ShuntThrough,
+    /// Capacitor in series with the through line: Z = 2·Z0·(1 - S21)/S21.
+    SeriesThrough,
+}
+
+impl S2p {
+    /// Impedance of the part under test found from S21.
+    pub fn impedance(&self, fixture: Fixture) -> Sweep {
+        let one = Phasor::from(1.0);
+        let z = self.params.iter()
+            .map(|s| {
+                let s21 = s[1];
+                match fixture {
+                    Fixture::ShuntThrough => (s21/(one - s21)).scale(self.z0/2.0),
+                    Fixture::SeriesThrough => ((one - s21)/s21).scale(2.0*self.z0),
+                }
+            })
+            .collect::<Vec<Phasor>>();
+        Sweep {
+            freqs: self.freqs.clone(),
+            mags: z.iter().map(|z| z.abs()).collect(),
+            phases: z.iter().map(|z| z.arg_deg()).collect(),
+        }
+    }
+}
+
+/// Read a two-port Touchstone (.s2p) file of S parameters. The option line
+/// sets the frequency unit, the data format (MA, DB, or RI), and the
+/// reference impedance; without one, the Touchstone defaults of GHz, MA,
+/// and 50 Ω apply. Comments after `!` are skipped and a frequency point may
+/// wrap across lines.
+/// Returns the parameters, or an error naming the line that could not be
+/// read.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::touchstone::{self, Fixture};
+///
+/// let text = "\
+/// ! Shunt-through measurement
+/// # MHz S RI R 50
+/// 1 0 0 0.5 0 0.5 0 0 0
+/// ";
+/// let data = touchstone::parse_s2p(text).unwrap();
+/// assert_eq!(data.freqs, vec![1e6]);
+/// // S21 = 0.5 in a shunt-through fixture is 25Ω·0.5/0.5
+/// assert!((data.impedance(Fixture::ShuntThrough).mags[0] - 25.0).abs() < 1e-9);
+/// ```
+pub fn parse_s2p(text: &str) -> Result<S2p, TouchstoneError> {
+    let mut freq_scale = 1e9;
+    let mut format = "ma".to_string();
+    let mut z0 = 50.0;
+    let mut values: Vec<(usize, f64)> = vec![];
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.split('!').next().unwrap_or("").trim().to_ascii_lowercase();
+        if line.is_empty() {continue;}
+        if let Some(options) = line.strip_prefix('#') {
+            let mut fields = options.split_whitespace();
+            while let Some(field) = fields.next() {
+                match field {
+                    "hz" => freq_scale = 1.0,
+                    "khz" => freq_scale = 1e3,
+                    "mhz" => freq_scale = 1e6,
+                    "ghz" => freq_scale = 1e9,
+                    "s" => {},
+                    "y" | "z" | "h" | "g" => return Err(TouchstoneError::Unsupported(field.to_uppercase())),
+                    "ma" | "db" | "ri" => format = field.to_string(),
+                    "r" => {
+                        z0 = fields.next()
+                            .and_then(|v| v.parse::<f64>().ok())
+                            .ok_or(TouchstoneError::Options {line: index + 1})?;
+                    },
+                    _ => return Err(TouchstoneError::Options {line: index + 1}),
+                }
+            }
+            continue;
+        }
+        for field in line.split_whitespace() {
+            let value = field.parse::<f64>().map_err(|_| TouchstoneError::Data {line: index + 1})?;
+            values.push((index + 1, value));
+        }
+    }
+    if values.len() % 9 != 0 {
+        return Err(TouchstoneError::Incomplete);
+    }
+
+    let pair = |a: f64, b: f64| match format.as_str() {
+        "ri" => Phasor::new(a, b),
+        "db" => Phasor::from_polar(10f64.powf(a/20.0), b),
+        _ => Phasor::from_polar(a, b),
+    };
+    let mut data = S2p {z0, freqs: vec![], params: vec![]};
+    for point in values.chunks(9) {
+        let v = point.iter().map(|(_, value)| *value).collect::<Vec<f64>>();
+        data.freqs.push(v[0]*freq_scale);
+        data.params.push([pair(v[1], v[2]), pair(v[3], v[4]), pair(v[5], v[6]), pair(v[7], v[8])]);
+    }
+    Ok(data)
+}
+
+/// Series R-L-C model of a capacitor fitted to a measured impedance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RclFit {
+    /// Equivalent series resistance in Ω.
+    pub esr: f64,
+    /// Capacitance in F.
+    pub capacitance: f64,
+    /// Equivalent series inductance in H.
+    pub esl: f64,
+}
+
+impl RclFit {
+    /// The fitted model, ready for `PDNModel::add_capacitor`.
+    pub fn model(&self) -> Fs {
+        sdomain::gen::rcl(self.esr, self.capacitance, self.esl)
+    }
+
+    /// Self-resonant frequency of the fitted model in Hz.
+    pub fn srf(&self) -> f64 {
+        1.0/(2.0*PI*(self.esl*self.capacitance).sqrt())
+    }
+}
+
+impl fmt::Display for RclFit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "C = {}, ESR = {}, ESL = {}",
+            format_farads(self.capacitance), format_ohms(self.esr), format_si(self.esl, "H")
+        )
+    }
+}
+
+/// Fit a series R-L-C model to a measured capacitor impedance. The
+/// reactance X = ωL - 1/(ωC) is fitted by least squares with each point
+/// weighted by its own size, so the capacitive and inductive ends count
+/// equally, and the ESR is the resistance at the impedance minimum.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::sweep::Sweep;
+/// use sdomain_test_plotters::touchstone;
+///
+/// use sdomain_test::sdomain;
+///
+/// let measured = Sweep::of(&sdomain::gen::rcl(5e-3, 1e-6, 0.5e-9), 1e3, 1e9);
+/// let fit = touchstone::fit_rcl(&measured);
+/// assert!((fit.capacitance/1e-6 - 1.0).abs() < 1e-3);
+/// assert!((fit.esl/0.5e-9 - 1.0).abs() < 1e-3);
+/// assert!((fit.esr/5e-3 - 1.0).abs() < 0.05);
+/// ```
+pub fn fit_rcl(sweep: &Sweep) -> RclFit {
+    // X·ω = L·ω² - 1/C is linear in L and 1/C
+    let (mut a11, mut a12, mut a22, mut b1, mut b2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for ((freq, mag), phase) in sweep.freqs.iter().zip(sweep.mags.iter()).zip(sweep.phases.iter()) {
+        let omega = 2.0*PI*freq;
+        let y = mag*phase.to_radians().sin()*omega;
+        if y == 0.0 || !y.is_finite() {continue;}
+        let weight = 1.0/(y*y);
+        let (x1, x2) = (omega*omega, -1.0);
+        a11 += weight*x1*x1;
+        a12 += weight*x1*x2;
+        a22 += weight*x2*x2;
+        b1 += weight*x1*y;
+        b2 += weight*x2*y;
+    }
+    let det = a11*a22 - a12*a12;
+    let esl = (b1*a22 - b2*a12)/det;
+    let inverse_c = (a11*b2 - a12*b1)/det;
+
+    let minimum = sweep.mags.iter().enumerate()
+        .fold((0, f64::INFINITY), |best, (index, mag)| if *mag < best.1 {(index, *mag)} else {best})
+        .0;
+    let esr = sweep.mags.get(minimum)
+        .zip(sweep.phases.get(minimum))
+        .map(|(mag, phase)| mag*phase.to_radians().cos())
+        .unwrap_or(f64::NAN);
+
+    RclFit {esr, capacitance: 1.0/inverse_c, esl}
+}
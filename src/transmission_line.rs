@@ -0,0 +1,211 @@
+use std::f64::consts::PI;
+
+use sdomain_test::complex::Complex;
+use sdomain_test::sdomain;
+
+use crate::phasor::Phasor;
+use crate::plottable::Plottable;
+use crate::sweep::Sweep;
+
+/// A uniform transmission line, such as a trace, cable, or plane pair,
+/// described by its resistance R, inductance L, conductance G, and
+/// capacitance C per unit length. Unlike the lumped networks, a line is
+/// not a rational function of s, so it is evaluated at each frequency
+/// rather than built as an `Fs`; any `Fs` or other model can terminate it.
+/// For the same reason a line cannot sit inside an `Fs` expression: combine
+/// the lumped parts into the load first, then terminate the line with it
+/// using `terminated` to get a model the plotters accept.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::transmission_line::TransmissionLine;
+///
+/// use sdomain_test::sdomain;
+///
+/// // 50Ω line, 1ns long: a quarter wave at 250MHz
+/// let line = TransmissionLine::lossless(50.0, 1e-9);
+/// assert!((line.delay() - 1e-9).abs() < 1e-18);
+/// assert!((line.quarter_wave_freq() - 250e6).abs() < 1e-3);
+///
+/// // A matched line looks like its characteristic impedance at any length
+/// let matched = line.input_impedance(&sdomain::gen::resistor(50.0), 123e6);
+/// assert!((matched.abs() - 50.0).abs() < 1e-9);
+///
+/// // A quarter wave inverts its load, Zin = Z0²/ZL
+/// let inverted = line.input_impedance(&sdomain::gen::resistor(25.0), 250e6);
+/// assert!((inverted.abs() - 100.0).abs() < 1e-6);
+///
+/// // 1m of FR-4 microstrip from its per meter parameters
+/// let trace = TransmissionLine::rlgc(5.0, 330e-9, 1e-4, 130e-12, 1.0);
+/// assert!((trace.characteristic_impedance(1e9).abs() - 50.4).abs() < 0.1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransmissionLine {
+    /// Series resistance per unit length in Ω.
+    pub r: f64,
+    /// Series inductance per unit length in H.
+    pub l: f64,
+    /// Shunt conductance per unit length in S.
+    pub g: f64,
+    /// Shunt capacitance per unit length in F.
+    pub c: f64,
+    /// Length of the line, in the same unit as the per unit length values.
+    pub length: f64,
+}
+
+impl TransmissionLine {
+    /// Line of `length` from its R, L, G, and C per unit length.
+    pub fn rlgc(r: f64, l: f64, g: f64, c: f64, length: f64) -> TransmissionLine {
+        TransmissionLine {r, l, g, c, length}
+    }
+
+    /// Lossless line with characteristic impedance `z0` in Ω and one way
+    /// propagation delay `delay` in s, taking the whole line as the unit
+    /// length.
+    pub fn lossless(z0: f64, delay: f64) -> TransmissionLine {
+        TransmissionLine::rlgc(0.0, z0*delay, 0.0, delay/z0, 1.0)
+    }
+
+    /// One way propagation delay in s, ignoring the losses.
+    pub fn delay(&self) -> f64 {
+        self.length*(self.l*self.c).sqrt()
+    }
+
+    /// Lowest frequency in Hz at which the line is a quarter wave long. A
+    /// shorted line is antiresonant and an open line resonant here and at
+    /// every odd multiple.
+    pub fn quarter_wave_freq(&self) -> f64 {
+        1.0/(4.0*self.delay())
+    }
+
+    /// Characteristic impedance at `freq` in Hz, √((R + jωL)/(G + jωC)).
+    pub fn characteristic_impedance(&self, freq: f64) -> Phasor {
+        let (series, shunt) = self.per_length(freq);
+        (series/shunt).sqrt()
+    }
+
+    /// Propagation constant per unit length at `freq` in Hz,
+    /// √((R + jωL)(G + jωC)): attenuation in Np as the real part and phase
+    /// in rad as the imaginary part.
+    pub fn propagation(&self, freq: f64) -> Phasor {
+        let (series, shunt) = self.per_length(freq);
+        (series*shunt).sqrt()
+    }
+
+    /// Impedance at `freq` in Hz looking into the line with `load` at the
+    /// far end, Z0·(ZL + Z0·tanh γl)/(Z0 + ZL·tanh γl).
+    pub fn input_impedance<P: Plottable + ?Sized>(&self, load: &P, freq: f64) -> Phasor {
+        let response = load.response(freq);
+        let load = Phasor::from_polar(response.mag(), response.phase_deg());
+        let z0 = self.characteristic_impedance(freq);
+        let t = self.tanh_length(freq);
+        z0*(load + z0*t)/(z0 + load*t)
+    }
+
+    /// Impedance at `freq` in Hz looking into the line left open at the far
+    /// end, Z0/tanh γl.
+    pub fn open_impedance(&self, freq: f64) -> Phasor {
+        self.characteristic_impedance(freq)/self.tanh_length(freq)
+    }
+
+    /// Impedance at `freq` in Hz looking into the line shorted at the far
+    /// end, Z0·tanh γl.
+    pub fn short_impedance(&self, freq: f64) -> Phasor {
+        self.characteristic_impedance(freq)*self.tanh_length(freq)
+    }
+
+    /// The line terminated by `load`, as a model the plotters can sweep
+    /// like any other. Its display name is "Line into" followed by the
+    /// load's.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::plottable::Plottable;
+    /// use sdomain_test_plotters::transmission_line::TransmissionLine;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// let line = TransmissionLine::lossless(50.0, 1e-9);
+    /// let inverter = line.terminated(sdomain::gen::resistor(25.0).named("25Ω"));
+    /// assert_eq!(inverter.display_name(), "Line into 25Ω");
+    /// assert!((inverter.response(250e6).mag() - 100.0).abs() < 1e-6);
+    /// assert!(inverter.response(250e6).phase_deg().abs() < 1e-6);
+    ///
+    /// // Below the quarter wave a short line into a resistor is inductive
+    /// assert!(inverter.response(100e6).phase_deg() > 0.0);
+    /// ```
+    pub fn terminated<P: Plottable>(&self, load: P) -> TerminatedLine<P> {
+        TerminatedLine {line: *self, load}
+    }
+
+    /// Input impedance of the line terminated by `load` at each of `freqs`.
+    pub fn input_sweep<P: Plottable + ?Sized>(&self, load: &P, freqs: Vec<f64>) -> Sweep {
+        let responses = freqs.iter().map(|f| self.input_impedance(load, *f)).collect::<Vec<Phasor>>();
+        to_sweep(freqs, responses)
+    }
+
+    /// Input impedance of the open line at each of `freqs`.
+    pub fn open_sweep(&self, freqs: Vec<f64>) -> Sweep {
+        let responses = freqs.iter().map(|f| self.open_impedance(*f)).collect::<Vec<Phasor>>();
+        to_sweep(freqs, responses)
+    }
+
+    /// Input impedance of the shorted line at each of `freqs`.
+    pub fn short_sweep(&self, freqs: Vec<f64>) -> Sweep {
+        let responses = freqs.iter().map(|f| self.short_impedance(*f)).collect::<Vec<Phasor>>();
+        to_sweep(freqs, responses)
+    }
+
+    /// Series impedance R + jωL and shunt admittance G + jωC per unit length.
+    fn per_length(&self, freq: f64) -> (Phasor, Phasor) {
+        let w = 2.0*PI*freq;
+        (Phasor::new(self.r, w*self.l), Phasor::new(self.g, w*self.c))
+    }
+
+    /// tanh γl, as (1 - e^(-2γl))/(1 + e^(-2γl)) so long lossy lines do
+    /// not overflow.
+    fn tanh_length(&self, freq: f64) -> Phasor {
+        let decay = (-self.propagation(freq).scale(2.0*self.length)).exp();
+        (Phasor::from(1.0) - decay)/(Phasor::from(1.0) + decay)
+    }
+}
+
+/// A transmission line with a load at its far end, created by
+/// `TransmissionLine::terminated`. Its response is the input impedance.
+#[derive(Clone)]
+pub struct TerminatedLine<P> {
+    pub line: TransmissionLine,
+    pub load: P,
+}
+
+impl<P: Plottable> Plottable for TerminatedLine<P> {
+    fn response(&self, freq: f64) -> Complex {
+        to_complex(self.line.input_impedance(&self.load, freq), freq)
+    }
+
+    fn display_name(&self) -> String {
+        format!("Line into {}", self.load.display_name())
+    }
+}
+
+/// `z` as the response of an `Fs` at `freq`: `Complex` cannot be built
+/// directly, so a series R-L or R-C with impedance `z` at that frequency is
+/// evaluated instead.
+fn to_complex(z: Phasor, freq: f64) -> Complex {
+    let w = 2.0*PI*freq;
+    if z.im == 0.0 || w == 0.0 {
+        sdomain::gen::resistor(z.re).calculate_freq(freq)
+    } else if z.im > 0.0 {
+        sdomain::gen::rl(z.re, z.im/w).calculate_freq(freq)
+    } else {
+        (sdomain::gen::resistor(z.re) + &sdomain::gen::capacitor(-1.0/(w*z.im))).calculate_freq(freq)
+    }
+}
+
+fn to_sweep(freqs: Vec<f64>, responses: Vec<Phasor>) -> Sweep {
+    Sweep {
+        freqs,
+        mags: responses.iter().map(Phasor::abs).collect(),
+        phases: responses.iter().map(Phasor::arg_deg).collect(),
+    }
+}
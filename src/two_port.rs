@@ -0,0 +1,148 @@
+use sdomain_test::sdomain::{self, Fs};
+
+use crate::phasor::Phasor;
+use crate::sweep::Sweep;
+
+/// A two-port network described by its impedance (Z) parameters.
+#[derive(Clone)]
+pub struct TwoPort {
+    pub z11: Fs,
+    pub z12: Fs,
+    pub z21: Fs,
+    pub z22: Fs,
+}
+
+/// Z parameters of a two-port evaluated at a single frequency.
+#[derive(Clone, Copy, Debug)]
+pub struct ZParams {
+    pub z11: Phasor,
+    pub z12: Phasor,
+    pub z21: Phasor,
+    pub z22: Phasor,
+}
+
+/// S parameters of a two-port evaluated at a single frequency.
+#[derive(Clone, Copy, Debug)]
+pub struct SParams {
+    pub s11: Phasor,
+    pub s12: Phasor,
+    pub s21: Phasor,
+    pub s22: Phasor,
+}
+
+impl TwoPort {
+    /// T network: `series_in` from port 1 and `series_out` from port 2 to a
+    /// middle node, with `shunt` from the middle node to ground.
+    /// ```text
+    ///   1 ──[ series_in ]──┬──[ series_out ]── 2
+    ///                   [ shunt ]
+    ///                      │
+    ///                     GND
+    /// ```
+    pub fn tee(series_in: Fs, shunt: Fs, series_out: Fs) -> TwoPort {
+        TwoPort {
+            z11: series_in + &shunt,
+            z12: shunt.clone(),
+            z21: shunt.clone(),
+            z22: series_out + &shunt,
+        }
+    }
+
+    /// Pi network: `shunt_in` from port 1 and `shunt_out` from port 2 to
+    /// ground, with `series` between the ports.
+    /// ```text
+    ///   1 ──┬──[ series ]──┬── 2
+    ///  [ shunt_in ]   [ shunt_out ]
+    ///       │              │
+    ///      GND            GND
+    /// ```
+    pub fn pi(shunt_in: Fs, series: Fs, shunt_out: Fs) -> TwoPort {
+        let loop_total = shunt_in.clone() + &series + &shunt_out;
+        let transfer = shunt_out.clone() / &(loop_total / &shunt_in);
+        TwoPort {
+            z11: sdomain::parallel(shunt_in.clone(), series.clone() + &shunt_out),
+            z12: transfer.clone(),
+            z21: transfer,
+            z22: sdomain::parallel(shunt_out, series + &shunt_in),
+        }
+    }
+
+    /// Insertion loss in dB at each frequency between real `source` and
+    /// `load` impedances in Ω, as for `ZParams::insertion_loss`.
+    pub fn insertion_loss(&self, freqs: &[f64], source: f64, load: f64) -> Vec<f64> {
+        self.z_params(freqs).iter().map(|z| z.insertion_loss(source, load)).collect()
+    }
+
+    /// Evaluate the Z parameters at each frequency.
+    pub fn z_params(&self, freqs: &[f64]) -> Vec<ZParams> {
+        let phasors = |model: &Fs| {
+            let sweep = Sweep::at(model, freqs.to_vec());
+            sweep.mags.iter().zip(sweep.phases.iter())
+                .map(|(m, p)| Phasor::from_polar(*m, *p))
+                .collect::<Vec<Phasor>>()
+        };
+        let (z11, z12, z21, z22) = (phasors(&self.z11), phasors(&self.z12), phasors(&self.z21), phasors(&self.z22));
+        (0..freqs.len())
+            .map(|i| ZParams {z11: z11[i], z12: z12[i], z21: z21[i], z22: z22[i]})
+            .collect()
+    }
+}
+
+impl ZParams {
+    /// Convert to S parameters against a real reference impedance `z0`.
+    pub fn to_s(&self, z0: f64) -> SParams {
+        let z0 = Phasor::from(z0);
+        let cross = self.z12*self.z21;
+        let det = (self.z11 + z0)*(self.z22 + z0) - cross;
+        SParams {
+            s11: ((self.z11 - z0)*(self.z22 + z0) - cross)/det,
+            s12: (self.z12*z0).scale(2.0)/det,
+            s21: (self.z21*z0).scale(2.0)/det,
+            s22: ((self.z11 + z0)*(self.z22 - z0) - cross)/det,
+        }
+    }
+
+    /// Insertion loss in dB of the network placed between a `source` and a
+    /// `load` impedance in Ω: how far the load voltage falls compared to
+    /// connecting the source straight to the load. With equal terminations
+    /// this is -20·log10(|S21|) referenced to that impedance.
+    ///
+    /// # Examples
+    /// ```
+    /// use sdomain_test_plotters::two_port::TwoPort;
+    ///
+    /// use sdomain_test::sdomain;
+    ///
+    /// // 6 dB, 50Ω T attenuator
+    /// let pad = TwoPort::tee(sdomain::gen::resistor(16.61), sdomain::gen::resistor(66.93), sdomain::gen::resistor(16.61));
+    /// let z = pad.z_params(&[1e6])[0];
+    /// assert!((z.insertion_loss(50.0, 50.0) - 6.0).abs() < 0.01);
+    /// assert!((z.insertion_loss(50.0, 50.0) + 20.0*z.to_s(50.0).s21.abs().log10()).abs() < 1e-9);
+    /// ```
+    pub fn insertion_loss(&self, source: f64, load: f64) -> f64 {
+        let (zs, zl) = (Phasor::from(source), Phasor::from(load));
+        let det = (self.z11 + zs)*(self.z22 + zl) - self.z12*self.z21;
+        20.0*(det.abs()/(self.z21*(zs + zl)).abs()).log10()
+    }
+
+    /// Rollett stability factor. The network is unconditionally stable when
+    /// K > 1 and the S parameter determinant is less than one.
+    pub fn k_factor(&self) -> f64 {
+        let cross = self.z12*self.z21;
+        (2.0*self.z11.re*self.z22.re - cross.re)/cross.abs()
+    }
+}
+
+impl SParams {
+    /// Determinant of the S parameter matrix.
+    pub fn det(&self) -> Phasor {
+        self.s11*self.s22 - self.s12*self.s21
+    }
+
+    /// Edwards-Sinsky stability factor for the load side. The network is
+    /// unconditionally stable when µ > 1.
+    pub fn mu(&self) -> f64 {
+        let s11_mag = self.s11.abs();
+        (1.0 - s11_mag*s11_mag)/((self.s22 - self.det()*self.s11.conj()).abs() + (self.s12*self.s21).abs())
+    }
+}
@@ -0,0 +1,82 @@
+/// Format a frequency with an SI prefix, e.g. `1.5 MHz`.
+pub fn format_freq(freq: f64) -> String {
+    let (scale, unit) = if freq >= 1e9 {(1e-9, "GHz")}
+        else if freq >= 1e6 {(1e-6, "MHz")}
+        else if freq >= 1e3 {(1e-3, "kHz")}
+        else {(1.0, "Hz")};
+    format!("{} {unit}", format_plain(freq*scale))
+}
+
+/// Format an impedance with an SI prefix, e.g. `12.5 mΩ`.
+pub fn format_ohms(ohms: f64) -> String {
+    let (scale, unit) = if ohms >= 1e3 {(1e-3, "kΩ")}
+        else if ohms >= 1.0 {(1.0, "Ω")}
+        else if ohms >= 1e-3 {(1e3, "mΩ")}
+        else {(1e6, "µΩ")};
+    format!("{} {unit}", format_plain(ohms*scale))
+}
+
+/// Format a capacitance with an SI prefix, e.g. `4.7 µF`.
+pub fn format_farads(farads: f64) -> String {
+    let (scale, unit) = if farads >= 1e-6 {(1e6, "µF")}
+        else if farads >= 1e-9 {(1e9, "nF")}
+        else {(1e12, "pF")};
+    format!("{} {unit}", format_plain(farads*scale))
+}
+
+/// Format a value of any unit with an SI prefix from µ to G, e.g. `12.5 krad/s`.
+pub fn format_si(value: f64, unit: &str) -> String {
+    let magnitude = value.abs();
+    let (scale, prefix) = if magnitude >= 1e9 {(1e-9, "G")}
+        else if magnitude >= 1e6 {(1e-6, "M")}
+        else if magnitude >= 1e3 {(1e-3, "k")}
+        else if magnitude >= 1.0 || magnitude == 0.0 {(1.0, "")}
+        else if magnitude >= 1e-3 {(1e3, "m")}
+        else {(1e6, "µ")};
+    format!("{} {prefix}{unit}", format_plain(value*scale))
+}
+
+/// Format a value in engineering notation with `digits` significant digits,
+/// e.g. `3.5 mΩ` or `120 kΩ`. Prefixes run from p to G.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::units::format_eng;
+///
+/// assert_eq!(format_eng(0.0035, "Ω", 3), "3.5 mΩ");
+/// assert_eq!(format_eng(120000.0, "Ω", 3), "120 kΩ");
+/// assert_eq!(format_eng(0.012345, "Ω", 2), "12 mΩ");
+/// assert_eq!(format_eng(999.96, "Ω", 3), "1 kΩ");
+/// ```
+pub fn format_eng(value: f64, unit: &str, digits: usize) -> String {
+    const PREFIXES: [&str; 8] = ["p", "n", "µ", "m", "", "k", "M", "G"];
+    if value == 0.0 || !value.is_finite() {
+        return format!("{value} {unit}");
+    }
+    let digits = digits.max(1) as i32;
+    // Round to the significant digits first so that e.g. 999.96 becomes 1 k
+    let exponent = value.abs().log10().floor() as i32;
+    let factor = 10f64.powi(digits - 1 - exponent);
+    let rounded = (value*factor).round()/factor;
+    let group = (rounded.abs().log10().floor() as i32).div_euclid(3).clamp(-4, 3);
+    let mantissa = rounded/10f64.powi(3*group);
+    let decimals = (digits - 1 - mantissa.abs().log10().floor() as i32).max(0) as usize;
+    let text = format!("{mantissa:.decimals$}");
+    let text = if text.contains('.') {text.trim_end_matches('0').trim_end_matches('.').to_string()} else {text};
+    format!("{text} {}{unit}", PREFIXES[(group + 4) as usize])
+}
+
+/// Format a number with up to three decimal places, dropping trailing zeros.
+pub fn format_plain(value: f64) -> String {
+    let text = format!("{value:.3}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Escape the characters with special meaning in HTML or XML text and
+/// attributes, e.g. `a &lt; b` for `a < b`.
+pub fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,125 @@
+use std::f64::consts::PI;
+
+use sdomain_test::sdomain::{self, Fs};
+
+use crate::plottable::Plottable;
+use crate::spectrum::Spectrum;
+
+/// Closed-loop output impedance of a buck regulator.
+///
+/// The open-loop output impedance is the output inductor (with its winding
+/// resistance) in parallel with the output capacitor (with its ESR). The
+/// control loop is modeled as an integrator crossing over at `bandwidth`,
+/// which suppresses the open-loop impedance by `1 + T(s)`:
+/// ```text
+///   Zout(s) = Zopen(s) / (1 + ωc/s)
+/// ```
+/// so the regulator holds the rail stiff well below the bandwidth and the
+/// output filter takes over above it.
+#[derive(Clone, Debug)]
+pub struct BuckVrm {
+    /// Control loop crossover frequency in Hz.
+    pub bandwidth: f64,
+    /// Output inductance in H.
+    pub inductance: f64,
+    /// Output inductor winding resistance in Ω.
+    pub inductor_resistance: f64,
+    /// Output capacitance in F.
+    pub capacitance: f64,
+    /// Output capacitor ESR in Ω.
+    pub capacitor_esr: f64,
+}
+
+impl BuckVrm {
+    /// Open-loop output impedance of the output filter.
+    pub fn open_loop_model(&self) -> Fs {
+        let z_inductor = sdomain::gen::rl(self.inductor_resistance, self.inductance);
+        let z_capacitor = sdomain::gen::resistor(self.capacitor_esr) + &sdomain::gen::capacitor(self.capacitance);
+        sdomain::parallel(z_inductor, z_capacitor)
+    }
+
+    /// Closed-loop output impedance, suitable as the source of a `PDNModel`.
+    pub fn model(&self) -> Fs {
+        let wc = 2.0*PI*self.bandwidth;
+        // 1 + ωc/s is a 1Ω resistor in series with a 1/ωc farad capacitor
+        let loop_suppression = sdomain::gen::resistor(1.0) + &sdomain::gen::capacitor(1.0/wc);
+        self.open_loop_model() / &loop_suppression
+    }
+}
+
+/// Ripple source of a buck converter in continuous conduction. The inductor
+/// current is a triangle wave at the switching frequency, rising for the
+/// duty cycle D = Vout/Vin and falling for the rest of the period, with a
+/// peak-to-peak swing of
+/// ```text
+///   ΔI = (Vin - Vout)·D / (L·f_sw)
+/// ```
+/// Its n-th harmonic has a peak amplitude of
+/// ```text
+///   In = ΔI·|sin(π·n·D)| / (π²·n²·D·(1 - D))
+/// ```
+/// so raising f_sw shrinks the ripple current and moves it up in frequency,
+/// where the output filter may attenuate it more or less depending on its
+/// resonances.
+///
+/// # Examples
+/// ```
+/// use sdomain_test_plotters::vrm::BuckRipple;
+///
+/// // 12V to 6V with 2.2uH at 500kHz: 2.73A pk-pk
+/// let buck = BuckRipple {vin: 12.0, vout: 6.0, inductance: 2.2e-6, harmonics: 5};
+/// assert_eq!(buck.duty(), 0.5);
+/// let ripple = buck.ripple_current(500e3);
+/// assert!((ripple - 2.727).abs() < 1e-3);
+///
+/// // At 50% duty the even harmonics vanish and the fundamental is 4ΔI/π²
+/// let source = buck.source(500e3);
+/// assert_eq!(source.freqs, vec![500e3, 1e6, 1.5e6, 2e6, 2.5e6]);
+/// assert!((source.amps[0] - 4.0*ripple/std::f64::consts::PI.powi(2)).abs() < 1e-9);
+/// assert!(source.amps[1] < 1e-9);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BuckRipple {
+    /// Input voltage in V.
+    pub vin: f64,
+    /// Output voltage in V, below `vin`.
+    pub vout: f64,
+    /// Output inductance in H.
+    pub inductance: f64,
+    /// Number of harmonics of the switching frequency to keep, including the
+    /// fundamental.
+    pub harmonics: usize,
+}
+
+impl BuckRipple {
+    /// Duty cycle of the high-side switch, Vout/Vin.
+    pub fn duty(&self) -> f64 {
+        self.vout/self.vin
+    }
+
+    /// Peak-to-peak inductor ripple current in A when switching at `f_sw` in Hz.
+    pub fn ripple_current(&self, f_sw: f64) -> f64 {
+        (self.vin - self.vout)*self.duty()/(self.inductance*f_sw)
+    }
+
+    /// Ripple current spectrum in A peak when switching at `f_sw` in Hz,
+    /// from the fundamental up to the last harmonic kept.
+    pub fn source(&self, f_sw: f64) -> Spectrum {
+        let duty = self.duty();
+        let ripple = self.ripple_current(f_sw);
+        let harmonics = (1..=self.harmonics)
+            .map(|n| {
+                let n = n as f64;
+                (n*f_sw, ripple*(PI*n*duty).sin().abs()/(PI*PI*n*n*duty*(1.0 - duty)))
+            })
+            .collect::<Vec<(f64, f64)>>();
+        Spectrum::from_harmonics(&harmonics)
+    }
+
+    /// Ripple voltage spectrum at the load in V peak when switching at
+    /// `f_sw` in Hz, with `model` the transfer impedance from the inductor
+    /// current to the load voltage, e.g. the PDN impedance.
+    pub fn at_load<P: Plottable + ?Sized>(&self, model: &P, f_sw: f64) -> Spectrum {
+        self.source(f_sw).through(model)
+    }
+}
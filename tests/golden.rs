@@ -0,0 +1,86 @@
+//! Golden image checks of the impedance chart: a recorded reference must be
+//! matched by the same chart, missed by a changed one, and never recorded
+//! silently.
+#![cfg(feature = "bitmap")]
+
+use std::path::{Path, PathBuf};
+
+use sdomain_test::passives::capacitor::Capacitor;
+use sdomain_test::pdn::PDNModel;
+use sdomain_test::sdomain;
+
+use sdomain_test_plotters::config::PlotConfig;
+use sdomain_test_plotters::pdn_impedance_plotter::pdn_plotter;
+use sdomain_test_plotters::testing::{self, GoldenError, Tolerance};
+
+const SIZE: (u32, u32) = (480, 360);
+
+fn pdn() -> PDNModel {
+    let mut pdn = PDNModel::from(sdomain::gen::rl(52e-3, 1.5e-6), None);
+    pdn.add_capacitor("0603 22uF", Capacitor::from(22e-6, "0603").model(), 1);
+    pdn.add_capacitor("0402 100nF", Capacitor::from(100e-9, "0402").model(), 4);
+    pdn
+}
+
+/// A fresh reference path in the temporary directory, one per test so the
+/// tests can run in parallel.
+fn reference(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("sdomain_test_plotters_{name}.rgb"));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Record the PDN chart drawn with `config` as the reference at `path`.
+fn record(path: &Path, config: &PlotConfig) {
+    let pdn = pdn();
+    testing::record_or_check_golden(path, SIZE, Tolerance::exact(), true, |area| {
+        pdn_plotter::plot_with_config(&pdn, area, config)
+    }).unwrap();
+}
+
+/// Check the PDN chart drawn with `config` against the reference at `path`.
+fn check(path: &Path, config: &PlotConfig) -> Result<(), Box <dyn std::error::Error>> {
+    let pdn = pdn();
+    testing::record_or_check_golden(path, SIZE, Tolerance::exact(), false, |area| {
+        pdn_plotter::plot_with_config(&pdn, area, config)
+    })
+}
+
+#[test]
+fn missing_reference_is_an_error() {
+    let path = reference("golden_missing");
+    let error = check(&path, &PlotConfig::default()).unwrap_err();
+    assert_eq!(error.downcast_ref::<GoldenError>(), Some(&GoldenError::MissingReference(path.clone())));
+    assert!(!path.exists());
+}
+
+#[test]
+fn chart_matches_its_reference() {
+    let path = reference("golden_pdn");
+    let config = PlotConfig {fmax: 100e6, target: Some(0.1), ..PlotConfig::default()};
+    record(&path, &config);
+    check(&path, &config).unwrap();
+}
+
+#[test]
+fn changed_chart_misses_its_reference() {
+    let path = reference("golden_pdn_changed");
+    let config = PlotConfig {fmax: 100e6, target: Some(0.1), ..PlotConfig::default()};
+    record(&path, &config);
+
+    let without_target = PlotConfig {target: None, ..config.clone()};
+    let error = check(&path, &without_target).unwrap_err();
+    assert!(matches!(error.downcast_ref::<GoldenError>(), Some(GoldenError::Mismatch(_))));
+}
+
+#[test]
+fn resized_chart_misses_its_reference() {
+    let path = reference("golden_pdn_resized");
+    record(&path, &PlotConfig::default());
+
+    let pdn = pdn();
+    let error = testing::record_or_check_golden(&path, (SIZE.0/2, SIZE.1), Tolerance::default(), false, |area| {
+        pdn_plotter::plot_with_config(&pdn, area, &PlotConfig::default())
+    }).unwrap_err();
+    assert!(matches!(error.downcast_ref::<GoldenError>(), Some(GoldenError::SizeMismatch {..})));
+}
@@ -0,0 +1,97 @@
+//! Accuracy of the numerical Laplace inversions against transform pairs with
+//! known closed forms.
+
+use std::f64::consts::PI;
+
+use sdomain_test_plotters::phasor::Phasor;
+use sdomain_test_plotters::time_domain::ilt::{self, Method};
+
+const TIMES: [f64; 6] = [0.1, 0.25, 0.5, 1.0, 1.5, 2.0];
+
+/// A transform and its inverse.
+struct Pair {
+    name: &'static str,
+    transform: Box<dyn Fn(Phasor) -> Phasor>,
+    inverse: Box<dyn Fn(f64) -> f64>,
+}
+
+/// ω/((s + a)² + ω²) is e^(-at)·sin(ωt).
+fn damped_sine(name: &'static str, a: f64, omega: f64) -> Pair {
+    Pair {
+        name,
+        transform: Box::new(move |s: Phasor| {
+            let shifted = s + Phasor::from(a);
+            Phasor::from(omega)/(shifted*shifted + Phasor::from(omega*omega))
+        }),
+        inverse: Box::new(move |t: f64| (-a*t).exp()*(omega*t).sin()),
+    }
+}
+
+fn step() -> Pair {
+    Pair {name: "step", transform: Box::new(|s: Phasor| Phasor::from(1.0)/s), inverse: Box::new(|_| 1.0)}
+}
+
+fn ramp() -> Pair {
+    Pair {name: "1/s²", transform: Box::new(|s: Phasor| Phasor::from(1.0)/(s*s)), inverse: Box::new(|t| t)}
+}
+
+fn decay() -> Pair {
+    Pair {name: "1/(s + 1)", transform: Box::new(|s: Phasor| Phasor::from(1.0)/(s + Phasor::from(1.0))), inverse: Box::new(|t: f64| (-t).exp())}
+}
+
+/// Check `method` on every pair at every time, against an absolute
+/// tolerance, and check that the error estimate is about the size of the
+/// actual error.
+fn check(method: Method, pairs: &[Pair], tolerance: f64) {
+    for pair in pairs.iter() {
+        for t in TIMES {
+            let inversion = ilt::invert(&pair.transform, t, method);
+            let actual = (inversion.value - (pair.inverse)(t)).abs();
+            assert!(actual < tolerance, "{method:?} {} at t = {t}: off by {actual:e}", pair.name);
+            assert!(actual <= 2.0*inversion.error + 1e-12, "{method:?} {} at t = {t}: off by {actual:e}, estimated {:e}", pair.name, inversion.error);
+        }
+    }
+}
+
+#[test]
+fn talbot_matches_closed_forms() {
+    check(Method::Talbot(32), &[step(), ramp(), decay(), damped_sine("slow damped sine", 1.0, 0.5), damped_sine("fast damped sine", 0.5, 2.0*PI)], 1e-9);
+}
+
+#[test]
+fn euler_matches_closed_forms() {
+    check(Method::Euler(15), &[step(), ramp(), decay(), damped_sine("slow damped sine", 1.0, 0.5), damped_sine("fast damped sine", 0.5, 2.0*PI)], 1e-8);
+}
+
+#[test]
+fn stehfest_matches_closed_forms() {
+    // Stehfest samples only the real axis, so it is held to slow,
+    // well-damped oscillations
+    check(Method::Stehfest(14), &[step(), ramp(), decay(), damped_sine("slow damped sine", 1.0, 0.5)], 1e-4);
+}
+
+#[test]
+fn stehfest_misses_fast_oscillations() {
+    let sine = damped_sine("fast damped sine", 0.5, 2.0*PI);
+    let worst = TIMES.iter()
+        .map(|t| (ilt::invert(&sine.transform, *t, Method::Stehfest(14)).value - (sine.inverse)(*t)).abs())
+        .fold(0.0, f64::max);
+    assert!(worst > 1e-2);
+}
+
+#[test]
+fn smallest_orders_still_estimate_an_error() {
+    let ramp = ramp();
+    for method in [Method::Talbot(0), Method::Talbot(1), Method::Euler(0), Method::Euler(1), Method::Stehfest(0), Method::Stehfest(2), Method::Stehfest(3)] {
+        let inversion = ilt::invert(&ramp.transform, 1.0, method);
+        assert!(inversion.error > 0.0, "{method:?}");
+    }
+}
+
+#[test]
+fn non_positive_times_are_nan() {
+    for method in [Method::Talbot(32), Method::Euler(15), Method::Stehfest(14)] {
+        assert!(ilt::invert(&step().transform, 0.0, method).value.is_nan());
+        assert!(ilt::invert(&step().transform, -1.0, method).value.is_nan());
+    }
+}